@@ -28,6 +28,21 @@ test("server version", async () => {
   expect(version.icons).toBeUndefined();
 });
 
+test("health endpoint", async () => {
+  const response = await fetch(`http://localhost:${port}/health`);
+  expect(response.status).toBe(200);
+  expect(await response.json()).toEqual({ status: "ok" });
+});
+
+test("metrics endpoint", async () => {
+  const response = await fetch(`http://localhost:${port}/metrics`);
+  expect(response.status).toBe(200);
+  const body = await response.text();
+  expect(body).toContain("# TYPE oeis_requests_total counter");
+  expect(body).toContain("# TYPE oeis_errors_total counter");
+  expect(body).toContain("# TYPE oeis_cache_hits_total counter");
+});
+
 test("server capabilities", async () => {
   const capabilities = await client.getServerCapabilities();
   printObject(capabilities);
@@ -47,7 +62,7 @@ test("server instructions", async () => {
 test("list prompts", async () => {
   const response = await client.listPrompts();
   printObject(response);
-  expect(response.prompts).toHaveLength(1);
+  expect(response.prompts).toHaveLength(4);
 });
 
 test("Prompt(sequence_analysis)", async () => {
@@ -71,10 +86,47 @@ test("Prompt(sequence_analysis)", async () => {
   expect(assistantMessage.content.text).toBeDefined();
 });
 
+test("Prompt(compare_sequences)", async () => {
+  const response = await client.getPrompt({
+    name: "compare_sequences",
+    arguments: {
+      first_id: "A000045",
+      second_id: "A000032",
+    },
+  });
+
+  expect(response.messages).toHaveLength(2);
+});
+
+test("Prompt(explain_sequence)", async () => {
+  const response = await client.getPrompt({
+    name: "explain_sequence",
+    arguments: {
+      sequence_id: "A000045",
+      level: "high_school",
+    },
+  });
+
+  expect(response.messages).toHaveLength(2);
+});
+
+test("Prompt(generate_code)", async () => {
+  const response = await client.getPrompt({
+    name: "generate_code",
+    arguments: {
+      sequence_id: "A000045",
+      language: "Python",
+    },
+  });
+
+  expect(response.messages).toHaveLength(2);
+  expect(response.messages[0].content.text).toContain("Python");
+});
+
 test("list tools", async () => {
   const response = await client.listTools();
   printObject(response);
-  expect(response.tools).toHaveLength(3);
+  expect(response.tools).toHaveLength(47);
 });
 
 test("Tool(get_url)", async () => {
@@ -117,10 +169,23 @@ test("Tool(search_by_subsequence)", async () => {
   expect(response.structuredContent).toBeDefined();
 });
 
+test("Tool(get_terms)", async () => {
+  const response = await client.callTool({
+    name: "get_terms",
+    arguments: { id: "A000045", count: 5 },
+  });
+
+  expect(response.isError).toBe(false);
+  expect(response.content).toHaveLength(1);
+
+  expect(response.structuredContent).toBeDefined();
+  expect(response.structuredContent.terms).toEqual([0, 1, 1, 2, 3]);
+});
+
 test("list resource templates", async () => {
   const response = await client.listResourceTemplates();
   printObject(response);
-  expect(response.resourceTemplates).toHaveLength(1);
+  expect(response.resourceTemplates).toHaveLength(2);
 });
 
 test("Resource(oeis://sequence/{id})", async () => {
@@ -133,3 +198,23 @@ test("Resource(oeis://sequence/{id})", async () => {
   expect(content.mimeType).toBe("text");
   expect(content.text).toBeDefined();
 });
+
+test("Resource(oeis://sequence/{id}?format=markdown)", async () => {
+  const response = await client.readResource({ uri: "oeis://sequence/A000045?format=markdown" });
+
+  expect(response.contents).toHaveLength(1);
+
+  const content = response.contents[0];
+  expect(content.mimeType).toBe("text/markdown");
+  expect(content.text).toBeDefined();
+});
+
+test("Resource(oeis://bfile/{id})", async () => {
+  const response = await client.readResource({ uri: "oeis://bfile/A000045" });
+
+  expect(response.contents).toHaveLength(1);
+
+  const content = response.contents[0];
+  expect(content.uri).toBe("oeis://bfile/A000045");
+  expect(content.text).toBeDefined();
+});
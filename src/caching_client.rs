@@ -0,0 +1,364 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use serde::Serialize;
+use tracing::debug;
+
+use crate::metrics::METRICS;
+use crate::oeis_client::{
+    BFileEntry, MatchCount, OEISClient, OEISResult, OEISSequence, SearchResult, SignMode,
+};
+
+struct CacheEntry {
+    sequence: Option<OEISSequence>,
+    expires_at: Instant,
+}
+
+type CacheSlot = Arc<tokio::sync::Mutex<Option<CacheEntry>>>;
+
+/// `find_by_id` results shared by every clone of a `CachingClient`
+#[derive(Default)]
+pub struct SequenceCache {
+    slots: Mutex<HashMap<String, CacheSlot>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+/// Size and hit/miss counts of a `SequenceCache`
+#[derive(Debug, PartialEq, Serialize)]
+pub struct CacheStats {
+    /// Cached lookups, including expired ones not yet refreshed
+    pub entries: usize,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl SequenceCache {
+    fn slot(&self, id: &str) -> CacheSlot {
+        self.slots
+            .lock()
+            .unwrap()
+            .entry(id.to_string())
+            .or_default()
+            .clone()
+    }
+
+    /// Drop every entry, returning how many slots were dropped.
+    /// Lookups in flight finish normally but their results are not kept.
+    pub fn clear(&self) -> usize {
+        let mut slots = self.slots.lock().unwrap();
+        let cleared = slots.len();
+        slots.clear();
+        cleared
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        let entries = self
+            .slots
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|slot| slot.try_lock().is_ok_and(|entry| entry.is_some()))
+            .count();
+        CacheStats {
+            entries,
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// `OEISClient` decorator that memoizes `find_by_id` results (including "not found") for a TTL
+#[derive(Clone)]
+pub struct CachingClient<C: OEISClient> {
+    inner: C,
+    ttl: Duration,
+    cache: Arc<SequenceCache>,
+}
+
+impl<C: OEISClient> CachingClient<C> {
+    pub fn new(inner: C, ttl: Duration) -> Self {
+        Self {
+            inner,
+            ttl,
+            cache: Arc::default(),
+        }
+    }
+
+    /// The cache shared by this client and its clones, for the admin endpoints
+    pub fn cache(&self) -> Arc<SequenceCache> {
+        self.cache.clone()
+    }
+}
+
+#[async_trait]
+impl<C: OEISClient> OEISClient for CachingClient<C> {
+    async fn find_by_id(&self, id: &str) -> OEISResult<Option<OEISSequence>> {
+        // Holding the per-ID lock across the upstream call makes concurrent lookups
+        // for the same ID wait for the first one instead of all hitting OEIS
+        let slot = self.cache.slot(id);
+        let mut entry = slot.lock().await;
+
+        if let Some(cached) = entry.as_ref().filter(|e| e.expires_at > Instant::now()) {
+            debug!("Cache hit: {}", id);
+            METRICS.inc_cache_hits();
+            self.cache.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(cached.sequence.clone());
+        }
+
+        debug!("Cache miss: {}", id);
+        self.cache.misses.fetch_add(1, Ordering::Relaxed);
+        let sequence = self.inner.find_by_id(id).await?;
+        *entry = Some(CacheEntry {
+            sequence: sequence.clone(),
+            expires_at: Instant::now() + self.ttl,
+        });
+        Ok(sequence)
+    }
+
+    /// Not cached: the point of the raw body is to see what OEIS answers right now
+    async fn find_raw_by_id(&self, id: &str) -> OEISResult<String> {
+        self.inner.find_raw_by_id(id).await
+    }
+
+    async fn sequence_exists(&self, id: &str) -> OEISResult<bool> {
+        self.inner.sequence_exists(id).await
+    }
+
+    async fn find_by_name(&self, name: &str) -> OEISResult<Option<OEISSequence>> {
+        self.inner.find_by_name(name).await
+    }
+
+    async fn search_by_subsequence(
+        &self,
+        subsequence: &[i64],
+        start: usize,
+        mode: SignMode,
+        max_results: usize,
+    ) -> OEISResult<SearchResult> {
+        self.inner
+            .search_by_subsequence(subsequence, start, mode, max_results)
+            .await
+    }
+
+    async fn count_subsequence_matches(&self, subsequence: &[i64]) -> OEISResult<MatchCount> {
+        self.inner.count_subsequence_matches(subsequence).await
+    }
+
+    async fn search_by_pattern(
+        &self,
+        pattern: &[Option<i64>],
+        start: usize,
+        mode: SignMode,
+        max_results: usize,
+    ) -> OEISResult<SearchResult> {
+        self.inner
+            .search_by_pattern(pattern, start, mode, max_results)
+            .await
+    }
+
+    async fn search_by_keyword(
+        &self,
+        keyword: &str,
+        start: usize,
+        max_results: usize,
+    ) -> OEISResult<SearchResult> {
+        self.inner
+            .search_by_keyword(keyword, start, max_results)
+            .await
+    }
+
+    async fn search_text(&self, query: &str) -> OEISResult<SearchResult> {
+        self.inner.search_text(query).await
+    }
+
+    async fn fetch_bfile(&self, id: &str) -> OEISResult<Option<Vec<BFileEntry>>> {
+        self.inner.fetch_bfile(id).await
+    }
+
+    async fn latest_sequence(&self) -> OEISResult<Option<OEISSequence>> {
+        self.inner.latest_sequence().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oeis_client::OEISError;
+    use std::sync::atomic::AtomicUsize;
+
+    // Mock client counting upstream calls
+    #[derive(Clone, Default)]
+    struct CountingClient {
+        calls: Arc<AtomicUsize>,
+        fail: bool,
+    }
+
+    #[async_trait]
+    impl OEISClient for CountingClient {
+        async fn find_by_id(&self, id: &str) -> OEISResult<Option<OEISSequence>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            tokio::task::yield_now().await;
+            if self.fail {
+                return Err(OEISError::Request("Mock error".to_string()));
+            }
+            if id == "NON_EXISTENT" {
+                return Ok(None);
+            }
+            Ok(Some(OEISSequence {
+                number: 45,
+                data: "0, 1, 1, 2, 3, 5, 8".to_string(),
+                name: "Fibonacci numbers".to_string(),
+                keyword: "nonn".to_string(),
+                ..Default::default()
+            }))
+        }
+
+        async fn find_raw_by_id(&self, _id: &str) -> OEISResult<String> {
+            Ok(String::new())
+        }
+
+        async fn sequence_exists(&self, _id: &str) -> OEISResult<bool> {
+            Ok(false)
+        }
+
+        async fn find_by_name(&self, _name: &str) -> OEISResult<Option<OEISSequence>> {
+            Ok(None)
+        }
+
+        async fn search_by_subsequence(
+            &self,
+            _subsequence: &[i64],
+            _start: usize,
+            _mode: SignMode,
+            _max_results: usize,
+        ) -> OEISResult<SearchResult> {
+            Ok(SearchResult::default())
+        }
+
+        async fn count_subsequence_matches(&self, _subsequence: &[i64]) -> OEISResult<MatchCount> {
+            Ok(MatchCount::default())
+        }
+
+        async fn search_by_pattern(
+            &self,
+            _pattern: &[Option<i64>],
+            _start: usize,
+            _mode: SignMode,
+            _max_results: usize,
+        ) -> OEISResult<SearchResult> {
+            Ok(SearchResult::default())
+        }
+
+        async fn search_by_keyword(
+            &self,
+            _keyword: &str,
+            _start: usize,
+            _max_results: usize,
+        ) -> OEISResult<SearchResult> {
+            Ok(SearchResult::default())
+        }
+
+        async fn search_text(&self, _query: &str) -> OEISResult<SearchResult> {
+            Ok(SearchResult::default())
+        }
+
+        async fn fetch_bfile(&self, _id: &str) -> OEISResult<Option<Vec<BFileEntry>>> {
+            Ok(None)
+        }
+
+        async fn latest_sequence(&self) -> OEISResult<Option<OEISSequence>> {
+            Ok(None)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_second_call_hits_cache() {
+        let inner = CountingClient::default();
+        let client = CachingClient::new(inner.clone(), Duration::from_secs(60));
+
+        let first = client.find_by_id("A000045").await.unwrap();
+        let second = client.find_by_id("A000045").await.unwrap();
+
+        assert_eq!(first.unwrap().number, 45);
+        assert_eq!(second.unwrap().number, 45);
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_not_found_is_cached() {
+        let inner = CountingClient::default();
+        let client = CachingClient::new(inner.clone(), Duration::from_secs(60));
+
+        assert!(client.find_by_id("NON_EXISTENT").await.unwrap().is_none());
+        assert!(client.find_by_id("NON_EXISTENT").await.unwrap().is_none());
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_error_is_not_cached() {
+        let inner = CountingClient {
+            fail: true,
+            ..Default::default()
+        };
+        let client = CachingClient::new(inner.clone(), Duration::from_secs(60));
+
+        assert!(client.find_by_id("A000045").await.is_err());
+        assert!(client.find_by_id("A000045").await.is_err());
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_entry_expires_after_ttl() {
+        let inner = CountingClient::default();
+        let client = CachingClient::new(inner.clone(), Duration::from_millis(50));
+
+        client.find_by_id("A000045").await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        client.find_by_id("A000045").await.unwrap();
+
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_lookups_share_upstream_call() {
+        let inner = CountingClient::default();
+        let client = CachingClient::new(inner.clone(), Duration::from_secs(60));
+
+        let (a, b, c) = tokio::join!(
+            client.find_by_id("A000045"),
+            client.find_by_id("A000045"),
+            client.find_by_id("A000045"),
+        );
+
+        assert!(a.is_ok() && b.is_ok() && c.is_ok());
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_clear_and_stats() {
+        let inner = CountingClient::default();
+        let client = CachingClient::new(inner.clone(), Duration::from_secs(60));
+
+        client.find_by_id("A000045").await.unwrap();
+        client.find_by_id("A000045").await.unwrap();
+        client.find_by_id("NON_EXISTENT").await.unwrap();
+        assert_eq!(
+            client.cache().stats(),
+            CacheStats {
+                entries: 2,
+                hits: 1,
+                misses: 2,
+            }
+        );
+
+        assert_eq!(client.cache().clear(), 2);
+        assert_eq!(client.cache().stats().entries, 0);
+
+        client.find_by_id("A000045").await.unwrap();
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 3);
+    }
+}
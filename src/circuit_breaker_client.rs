@@ -0,0 +1,348 @@
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::time::Instant;
+use tracing::{info, warn};
+
+use crate::oeis_client::{
+    BFileEntry, MatchCount, OEISClient, OEISError, OEISResult, OEISSequence, SearchResult, SignMode,
+};
+
+/// `OEISClient` decorator that stops calling OEIS after `failure_threshold` consecutive
+/// outage failures (timeouts, connection errors, 5xx), failing fast with `CircuitOpen` for
+/// `cooldown`. The first call after the cooldown is let through as a probe: success closes
+/// the circuit, another outage failure reopens it.
+#[derive(Clone)]
+pub struct CircuitBreakerClient<C: OEISClient> {
+    inner: C,
+    failure_threshold: u32,
+    cooldown: Duration,
+    state: Arc<Mutex<BreakerState>>,
+}
+
+#[derive(Default)]
+struct BreakerState {
+    consecutive_failures: u32,
+    /// Calls fail fast until then; `None` while the circuit is closed
+    open_until: Option<Instant>,
+}
+
+impl<C: OEISClient> CircuitBreakerClient<C> {
+    pub fn new(inner: C, failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            inner,
+            failure_threshold: failure_threshold.max(1),
+            cooldown,
+            state: Arc::new(Mutex::new(BreakerState::default())),
+        }
+    }
+
+    /// Run `call` unless the circuit is open, and record how it went
+    async fn guarded<T>(&self, call: impl Future<Output = OEISResult<T>>) -> OEISResult<T> {
+        self.admit()?;
+        let result = call.await;
+        self.record(&result);
+        result
+    }
+
+    /// Fail fast while the circuit is open. Once the cooldown is over, admit a single probe;
+    /// the cooldown is re-armed meanwhile, so concurrent calls keep failing fast and a probe
+    /// that never reports back (e.g., a cancelled call) does not leave the circuit stuck.
+    fn admit(&self) -> OEISResult<()> {
+        let mut state = self.state.lock().unwrap();
+        let Some(open_until) = state.open_until else {
+            return Ok(());
+        };
+
+        let now = Instant::now();
+        if now < open_until {
+            return Err(OEISError::CircuitOpen(open_until - now));
+        }
+        state.open_until = Some(now + self.cooldown);
+        info!("OEIS circuit breaker half-open: probing OEIS");
+        Ok(())
+    }
+
+    fn record<T>(&self, result: &OEISResult<T>) {
+        let mut state = self.state.lock().unwrap();
+        if result.as_ref().is_err_and(is_outage) {
+            state.consecutive_failures += 1;
+            let probe_failed = state.open_until.is_some();
+            if probe_failed || state.consecutive_failures >= self.failure_threshold {
+                if !probe_failed {
+                    warn!(
+                        "OEIS circuit breaker opened after {} consecutive failures; failing fast for {:?}",
+                        state.consecutive_failures, self.cooldown
+                    );
+                }
+                state.open_until = Some(Instant::now() + self.cooldown);
+            }
+        } else {
+            // OEIS answered, even if only with a 404 or another client-side error
+            if state.open_until.take().is_some() {
+                info!("OEIS circuit breaker closed: OEIS is reachable again");
+            }
+            state.consecutive_failures = 0;
+        }
+    }
+}
+
+/// Failures suggesting OEIS itself is down, as opposed to a bad request or a missing entry
+fn is_outage(error: &OEISError) -> bool {
+    match error {
+        OEISError::Timeout | OEISError::Request(_) => true,
+        OEISError::Http(status) => status.is_server_error(),
+        _ => false,
+    }
+}
+
+#[async_trait]
+impl<C: OEISClient> OEISClient for CircuitBreakerClient<C> {
+    async fn find_by_id(&self, id: &str) -> OEISResult<Option<OEISSequence>> {
+        self.guarded(self.inner.find_by_id(id)).await
+    }
+
+    async fn find_raw_by_id(&self, id: &str) -> OEISResult<String> {
+        self.guarded(self.inner.find_raw_by_id(id)).await
+    }
+
+    async fn sequence_exists(&self, id: &str) -> OEISResult<bool> {
+        self.guarded(self.inner.sequence_exists(id)).await
+    }
+
+    async fn find_by_name(&self, name: &str) -> OEISResult<Option<OEISSequence>> {
+        self.guarded(self.inner.find_by_name(name)).await
+    }
+
+    async fn search_by_subsequence(
+        &self,
+        subsequence: &[i64],
+        start: usize,
+        mode: SignMode,
+        max_results: usize,
+    ) -> OEISResult<SearchResult> {
+        self.guarded(
+            self.inner
+                .search_by_subsequence(subsequence, start, mode, max_results),
+        )
+        .await
+    }
+
+    async fn count_subsequence_matches(&self, subsequence: &[i64]) -> OEISResult<MatchCount> {
+        self.guarded(self.inner.count_subsequence_matches(subsequence))
+            .await
+    }
+
+    async fn search_by_pattern(
+        &self,
+        pattern: &[Option<i64>],
+        start: usize,
+        mode: SignMode,
+        max_results: usize,
+    ) -> OEISResult<SearchResult> {
+        self.guarded(
+            self.inner
+                .search_by_pattern(pattern, start, mode, max_results),
+        )
+        .await
+    }
+
+    async fn search_by_keyword(
+        &self,
+        keyword: &str,
+        start: usize,
+        max_results: usize,
+    ) -> OEISResult<SearchResult> {
+        self.guarded(self.inner.search_by_keyword(keyword, start, max_results))
+            .await
+    }
+
+    async fn search_text(&self, query: &str) -> OEISResult<SearchResult> {
+        self.guarded(self.inner.search_text(query)).await
+    }
+
+    async fn fetch_bfile(&self, id: &str) -> OEISResult<Option<Vec<BFileEntry>>> {
+        self.guarded(self.inner.fetch_bfile(id)).await
+    }
+
+    async fn latest_sequence(&self) -> OEISResult<Option<OEISSequence>> {
+        self.guarded(self.inner.latest_sequence()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    // Mock client counting upstream calls, failing them all while `failing` is set
+    #[derive(Clone, Default)]
+    struct FlakyClient {
+        calls: Arc<AtomicUsize>,
+        failing: Arc<AtomicBool>,
+    }
+
+    impl FlakyClient {
+        fn calls(&self) -> usize {
+            self.calls.load(Ordering::SeqCst)
+        }
+
+        fn set_failing(&self, failing: bool) {
+            self.failing.store(failing, Ordering::SeqCst);
+        }
+
+        fn respond<T>(&self, value: T) -> OEISResult<T> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if self.failing.load(Ordering::SeqCst) {
+                Err(OEISError::Request("connection refused".to_string()))
+            } else {
+                Ok(value)
+            }
+        }
+    }
+
+    #[async_trait]
+    impl OEISClient for FlakyClient {
+        async fn find_by_id(&self, _id: &str) -> OEISResult<Option<OEISSequence>> {
+            self.respond(None)
+        }
+
+        async fn find_raw_by_id(&self, _id: &str) -> OEISResult<String> {
+            self.respond(String::new())
+        }
+
+        async fn sequence_exists(&self, _id: &str) -> OEISResult<bool> {
+            self.respond(false)
+        }
+
+        async fn find_by_name(&self, _name: &str) -> OEISResult<Option<OEISSequence>> {
+            self.respond(None)
+        }
+
+        async fn search_by_subsequence(
+            &self,
+            _subsequence: &[i64],
+            _start: usize,
+            _mode: SignMode,
+            _max_results: usize,
+        ) -> OEISResult<SearchResult> {
+            self.respond(SearchResult::default())
+        }
+
+        async fn count_subsequence_matches(&self, _subsequence: &[i64]) -> OEISResult<MatchCount> {
+            self.respond(MatchCount::default())
+        }
+
+        async fn search_by_pattern(
+            &self,
+            _pattern: &[Option<i64>],
+            _start: usize,
+            _mode: SignMode,
+            _max_results: usize,
+        ) -> OEISResult<SearchResult> {
+            self.respond(SearchResult::default())
+        }
+
+        async fn search_by_keyword(
+            &self,
+            _keyword: &str,
+            _start: usize,
+            _max_results: usize,
+        ) -> OEISResult<SearchResult> {
+            self.respond(SearchResult::default())
+        }
+
+        async fn search_text(&self, _query: &str) -> OEISResult<SearchResult> {
+            self.respond(SearchResult::default())
+        }
+
+        async fn fetch_bfile(&self, _id: &str) -> OEISResult<Option<Vec<BFileEntry>>> {
+            self.respond(None)
+        }
+
+        async fn latest_sequence(&self) -> OEISResult<Option<OEISSequence>> {
+            self.respond(None)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_opens_after_consecutive_failures_and_fails_fast() {
+        let inner = FlakyClient::default();
+        inner.set_failing(true);
+        let client = CircuitBreakerClient::new(inner.clone(), 3, Duration::from_secs(60));
+
+        for _ in 0..3 {
+            let error = client.find_by_id("A000045").await.unwrap_err();
+            assert!(matches!(error, OEISError::Request(_)));
+        }
+        assert_eq!(inner.calls(), 3);
+
+        // Every method fails fast without reaching OEIS
+        let started = Instant::now();
+        let error = client.find_by_id("A000045").await.unwrap_err();
+        assert!(matches!(error, OEISError::CircuitOpen(_)));
+        let error = client.search_text("fibonacci").await.unwrap_err();
+        assert!(matches!(error, OEISError::CircuitOpen(_)));
+        assert!(started.elapsed() < Duration::from_millis(100));
+        assert_eq!(inner.calls(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_success_resets_the_failure_count() {
+        let inner = FlakyClient::default();
+        let client = CircuitBreakerClient::new(inner.clone(), 2, Duration::from_secs(60));
+
+        inner.set_failing(true);
+        assert!(client.find_by_id("A000045").await.is_err());
+        inner.set_failing(false);
+        assert!(client.find_by_id("A000045").await.is_ok());
+        inner.set_failing(true);
+        assert!(client.find_by_id("A000045").await.is_err());
+
+        // Never two failures in a row, so the circuit stays closed
+        let error = client.find_by_id("A000045").await.unwrap_err();
+        assert!(matches!(error, OEISError::Request(_)));
+        assert_eq!(inner.calls(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_half_open_probe_closes_or_reopens() {
+        let inner = FlakyClient::default();
+        inner.set_failing(true);
+        let client = CircuitBreakerClient::new(inner.clone(), 1, Duration::from_millis(50));
+
+        assert!(client.find_by_id("A000045").await.is_err());
+        tokio::time::sleep(Duration::from_millis(60)).await;
+
+        // The probe fails, so the circuit opens for another cooldown
+        let error = client.find_by_id("A000045").await.unwrap_err();
+        assert!(matches!(error, OEISError::Request(_)));
+        let error = client.find_by_id("A000045").await.unwrap_err();
+        assert!(matches!(error, OEISError::CircuitOpen(_)));
+        assert_eq!(inner.calls(), 2);
+
+        // OEIS is back: the next probe succeeds and closes the circuit
+        inner.set_failing(false);
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert!(client.find_by_id("A000045").await.is_ok());
+        assert!(client.find_by_id("A000045").await.is_ok());
+        assert_eq!(inner.calls(), 4);
+    }
+
+    #[test]
+    fn test_is_outage() {
+        assert!(is_outage(&OEISError::Timeout));
+        assert!(is_outage(&OEISError::Request("refused".to_string())));
+        assert!(is_outage(&OEISError::Http(
+            reqwest::StatusCode::SERVICE_UNAVAILABLE
+        )));
+        assert!(!is_outage(&OEISError::Http(
+            reqwest::StatusCode::BAD_REQUEST
+        )));
+        assert!(!is_outage(&OEISError::NotFound));
+        assert!(!is_outage(&OEISError::RateLimited));
+        assert!(!is_outage(&OEISError::Deserialize("bad".to_string())));
+    }
+}
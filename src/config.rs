@@ -0,0 +1,468 @@
+use std::net::{IpAddr, Ipv4Addr};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail};
+
+use crate::oeis::{DEFAULT_TOOL_TIMEOUT_SECS, InputLimits};
+use crate::oeis_client::{
+    DEFAULT_BASE_URL, DEFAULT_MAX_BODY_BYTES, DEFAULT_MAX_RETRIES, DEFAULT_POOL_IDLE_TIMEOUT_SECS,
+    DEFAULT_POOL_MAX_IDLE_PER_HOST, DEFAULT_TIMEOUT_SECS,
+};
+
+const DEFAULT_PORT: u16 = 8000;
+/// All IPv4 interfaces, so the server is reachable from outside a container
+const DEFAULT_BIND_ADDRESS: IpAddr = IpAddr::V4(Ipv4Addr::UNSPECIFIED);
+const DEFAULT_SHUTDOWN_TIMEOUT_SECS: u64 = 30;
+const DEFAULT_BREAKER_COOLDOWN_SECS: u64 = 30;
+
+/// How the server talks to MCP clients (`MCP_TRANSPORT`)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Transport {
+    Http,
+    Stdio,
+}
+
+/// Settings of the upstream OEIS client
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClientConfig {
+    pub base_url: String,
+    /// Base URLs to fail over to when `base_url` is unavailable (`OEIS_MIRRORS`)
+    pub mirrors: Vec<String>,
+    pub timeout: Duration,
+    pub max_retries: u32,
+    pub pool_max_idle_per_host: usize,
+    pub pool_idle_timeout: Duration,
+    /// Scrape the sequence page when OEIS JSON cannot be parsed (`OEIS_SCRAPE_FALLBACK`)
+    pub scrape_fallback: bool,
+    /// Largest response body read from OEIS (`OEIS_MAX_BODY_BYTES`)
+    pub max_body_bytes: usize,
+    /// How OEIS can reach the operator, appended to the `User-Agent` (`OEIS_USER_AGENT_CONTACT`)
+    pub user_agent_contact: Option<String>,
+}
+
+/// Server settings read from environment variables and validated before startup
+#[derive(Clone, Debug, PartialEq)]
+pub struct Config {
+    pub transport: Transport,
+    /// Address the HTTP server listens on (`BIND_ADDRESS`)
+    pub bind_address: IpAddr,
+    pub port: u16,
+    pub client: ClientConfig,
+    /// `None` disables the sequence cache
+    pub cache_ttl: Option<Duration>,
+    /// `None` disables upstream rate limiting
+    pub max_rps: Option<u32>,
+    /// Consecutive OEIS failures that open the circuit breaker; `None` disables it
+    pub breaker_threshold: Option<u32>,
+    /// How long an open circuit breaker fails fast before probing OEIS again
+    pub breaker_cooldown: Duration,
+    /// Raw `CORS_ALLOWED_ORIGINS` value; `None` disables CORS
+    pub cors_allowed_origins: Option<String>,
+    /// Certificate and private key paths; `None` serves plain HTTP
+    pub tls_paths: Option<(PathBuf, PathBuf)>,
+    /// Suggest adjacent IDs when a sequence is not found (`OEIS_SUGGEST_IDS`)
+    pub suggest_ids: bool,
+    pub limits: InputLimits,
+    /// Time budget of a single tool call
+    pub tool_timeout: Duration,
+    /// Shared secret for the `/admin` routes; `None` leaves them unmounted
+    pub admin_token: Option<String>,
+    /// How long the HTTP server waits for open connections to finish after a shutdown signal
+    pub shutdown_timeout: Duration,
+    /// Offer the MCP prompts (`ENABLE_PROMPTS`)
+    pub enable_prompts: bool,
+    /// Offer the MCP resources (`ENABLE_RESOURCES`)
+    pub enable_resources: bool,
+}
+
+impl Config {
+    /// Load the configuration from the process environment
+    pub fn from_env() -> anyhow::Result<Self> {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    /// Load the configuration through `lookup`, which returns the value of a variable if set.
+    /// Empty values are treated as unset.
+    pub(crate) fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> anyhow::Result<Self> {
+        let var = |name: &str| {
+            lookup(name)
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+
+        let transport = match var("MCP_TRANSPORT").as_deref() {
+            None | Some("http") => Transport::Http,
+            Some("stdio") => Transport::Stdio,
+            Some(other) => bail!(
+                "Invalid MCP_TRANSPORT: {:?} (expected \"http\" or \"stdio\")",
+                other
+            ),
+        };
+
+        // Accept the bracketed form of IPv6 addresses too (e.g., "[::1]")
+        let bind_address = parse(
+            &|name: &str| var(name).map(|value| value.trim_matches(['[', ']']).to_string()),
+            "BIND_ADDRESS",
+            "an IPv4 or IPv6 address, e.g. 127.0.0.1 or ::",
+        )?
+        .unwrap_or(DEFAULT_BIND_ADDRESS);
+        let port = parse(&var, "PORT", "a port number from 0 to 65535")?.unwrap_or(DEFAULT_PORT);
+
+        let base_url = var("OEIS_BASE_URL").unwrap_or_else(|| DEFAULT_BASE_URL.to_string());
+        check_http_url("OEIS_BASE_URL", &base_url)?;
+
+        let mirrors: Vec<String> = var("OEIS_MIRRORS")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|mirror| !mirror.is_empty())
+            .map(str::to_string)
+            .collect();
+        for mirror in &mirrors {
+            check_http_url("OEIS_MIRRORS", mirror)?;
+        }
+
+        let timeout_secs: u64 = parse(&var, "OEIS_TIMEOUT_SECS", "a positive number of seconds")?
+            .unwrap_or(DEFAULT_TIMEOUT_SECS);
+        if timeout_secs == 0 {
+            bail!("Invalid OEIS_TIMEOUT_SECS: \"0\" (expected a positive number of seconds)");
+        }
+
+        let max_body_bytes = parse(&var, "OEIS_MAX_BODY_BYTES", "a positive number of bytes")?
+            .unwrap_or(DEFAULT_MAX_BODY_BYTES);
+        if max_body_bytes == 0 {
+            bail!("Invalid OEIS_MAX_BODY_BYTES: \"0\" (expected a positive number of bytes)");
+        }
+
+        let client = ClientConfig {
+            base_url,
+            mirrors,
+            timeout: Duration::from_secs(timeout_secs),
+            max_retries: parse(&var, "OEIS_MAX_RETRIES", "a non-negative integer")?
+                .unwrap_or(DEFAULT_MAX_RETRIES),
+            pool_max_idle_per_host: parse(
+                &var,
+                "OEIS_POOL_MAX_IDLE_PER_HOST",
+                "a non-negative integer",
+            )?
+            .unwrap_or(DEFAULT_POOL_MAX_IDLE_PER_HOST),
+            pool_idle_timeout: Duration::from_secs(
+                parse(&var, "OEIS_POOL_IDLE_TIMEOUT_SECS", "a number of seconds")?
+                    .unwrap_or(DEFAULT_POOL_IDLE_TIMEOUT_SECS),
+            ),
+            scrape_fallback: parse(&var, "OEIS_SCRAPE_FALLBACK", "\"true\" or \"false\"")?
+                .unwrap_or(false),
+            max_body_bytes,
+            user_agent_contact: var("OEIS_USER_AGENT_CONTACT"),
+        };
+
+        let cache_ttl = parse(&var, "OEIS_CACHE_TTL_SECS", "a number of seconds")?
+            .filter(|&secs| secs > 0)
+            .map(Duration::from_secs);
+        let max_rps = parse(&var, "OEIS_MAX_RPS", "a non-negative integer")?.filter(|&rps| rps > 0);
+        let breaker_threshold = parse(&var, "OEIS_BREAKER_THRESHOLD", "a non-negative integer")?
+            .filter(|&threshold| threshold > 0);
+        let breaker_cooldown_secs: u64 = parse(
+            &var,
+            "OEIS_BREAKER_COOLDOWN_SECS",
+            "a positive number of seconds",
+        )?
+        .unwrap_or(DEFAULT_BREAKER_COOLDOWN_SECS);
+        if breaker_cooldown_secs == 0 {
+            bail!(
+                "Invalid OEIS_BREAKER_COOLDOWN_SECS: \"0\" (expected a positive number of seconds)"
+            );
+        }
+
+        let suggest_ids =
+            parse(&var, "OEIS_SUGGEST_IDS", "\"true\" or \"false\"")?.unwrap_or(false);
+
+        let defaults = InputLimits::default();
+        let limits = InputLimits {
+            max_subsequence_length: parse(&var, "MAX_SUBSEQUENCE_TERMS", "a positive integer")?
+                .unwrap_or(defaults.max_subsequence_length),
+            max_find_many_ids: parse(&var, "MAX_BATCH_IDS", "a positive integer")?
+                .unwrap_or(defaults.max_find_many_ids),
+        };
+        for (name, value) in [
+            ("MAX_SUBSEQUENCE_TERMS", limits.max_subsequence_length),
+            ("MAX_BATCH_IDS", limits.max_find_many_ids),
+        ] {
+            if value == 0 {
+                bail!("Invalid {}: \"0\" (expected a positive integer)", name);
+            }
+        }
+
+        let tool_timeout_secs: u64 =
+            parse(&var, "TOOL_TIMEOUT_SECS", "a positive number of seconds")?
+                .unwrap_or(DEFAULT_TOOL_TIMEOUT_SECS);
+        if tool_timeout_secs == 0 {
+            bail!("Invalid TOOL_TIMEOUT_SECS: \"0\" (expected a positive number of seconds)");
+        }
+
+        let tls_paths = match (
+            var("TLS_CERT_PATH").map(PathBuf::from),
+            var("TLS_KEY_PATH").map(PathBuf::from),
+        ) {
+            (None, None) => None,
+            (Some(cert_path), Some(key_path)) => {
+                for path in [&cert_path, &key_path] {
+                    if !path.is_file() {
+                        bail!("TLS file not found: {}", path.display());
+                    }
+                }
+                Some((cert_path, key_path))
+            }
+            _ => bail!("TLS_CERT_PATH and TLS_KEY_PATH must be set together"),
+        };
+
+        Ok(Self {
+            transport,
+            bind_address,
+            port,
+            client,
+            cache_ttl,
+            max_rps,
+            breaker_threshold,
+            breaker_cooldown: Duration::from_secs(breaker_cooldown_secs),
+            cors_allowed_origins: var("CORS_ALLOWED_ORIGINS"),
+            tls_paths,
+            suggest_ids,
+            limits,
+            tool_timeout: Duration::from_secs(tool_timeout_secs),
+            admin_token: var("ADMIN_TOKEN"),
+            shutdown_timeout: Duration::from_secs(
+                parse(&var, "SHUTDOWN_TIMEOUT_SECS", "a number of seconds")?
+                    .unwrap_or(DEFAULT_SHUTDOWN_TIMEOUT_SECS),
+            ),
+            enable_prompts: parse(&var, "ENABLE_PROMPTS", "\"true\" or \"false\"")?.unwrap_or(true),
+            enable_resources: parse(&var, "ENABLE_RESOURCES", "\"true\" or \"false\"")?
+                .unwrap_or(true),
+        })
+    }
+}
+
+/// Reject `url` unless it is an absolute http(s) URL, naming the variable `name` it came from
+fn check_http_url(name: &str, url: &str) -> anyhow::Result<()> {
+    match reqwest::Url::parse(url) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => Ok(()),
+        _ => bail!(
+            "Invalid {}: {:?} (expected an http:// or https:// URL)",
+            name,
+            url
+        ),
+    }
+}
+
+/// Parse the variable `name` if set, naming it and the `expected` format on failure
+fn parse<T: FromStr>(
+    var: &impl Fn(&str) -> Option<String>,
+    name: &str,
+    expected: &str,
+) -> anyhow::Result<Option<T>> {
+    var(name)
+        .map(|value| {
+            value
+                .parse()
+                .map_err(|_| anyhow!("Invalid {}: {:?} (expected {})", name, value, expected))
+        })
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::net::Ipv6Addr;
+
+    fn config_from(vars: &[(&str, &str)]) -> anyhow::Result<Config> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        Config::from_lookup(|name| vars.get(name).cloned())
+    }
+
+    #[test]
+    fn test_defaults() {
+        let config = config_from(&[]).unwrap();
+
+        assert_eq!(config.transport, Transport::Http);
+        assert_eq!(config.bind_address, IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+        assert_eq!(config.port, 8000);
+        assert_eq!(config.client.base_url, "https://oeis.org");
+        assert!(config.client.mirrors.is_empty());
+        assert_eq!(config.client.timeout, Duration::from_secs(10));
+        assert_eq!(config.client.max_retries, 2);
+        assert!(!config.client.scrape_fallback);
+        assert_eq!(config.client.max_body_bytes, 8 * 1024 * 1024);
+        assert_eq!(config.client.user_agent_contact, None);
+        assert_eq!(config.cache_ttl, None);
+        assert_eq!(config.max_rps, None);
+        assert_eq!(config.breaker_threshold, None);
+        assert_eq!(config.breaker_cooldown, Duration::from_secs(30));
+        assert_eq!(config.cors_allowed_origins, None);
+        assert_eq!(config.tls_paths, None);
+        assert!(!config.suggest_ids);
+        assert_eq!(config.limits, InputLimits::default());
+        assert_eq!(config.tool_timeout, Duration::from_secs(60));
+        assert_eq!(config.admin_token, None);
+        assert_eq!(config.shutdown_timeout, Duration::from_secs(30));
+        assert!(config.enable_prompts);
+        assert!(config.enable_resources);
+    }
+
+    #[test]
+    fn test_valid_values() {
+        let config = config_from(&[
+            ("MCP_TRANSPORT", "stdio"),
+            ("PORT", " 9000 "),
+            ("OEIS_BASE_URL", "http://localhost:8080/"),
+            (
+                "OEIS_MIRRORS",
+                "https://mirror1.example/, ,http://mirror2.example",
+            ),
+            ("OEIS_TIMEOUT_SECS", "30"),
+            ("OEIS_MAX_BODY_BYTES", "1048576"),
+            ("OEIS_USER_AGENT_CONTACT", "ops@example.com"),
+            ("OEIS_CACHE_TTL_SECS", "0"),
+            ("OEIS_MAX_RPS", "5"),
+            ("OEIS_BREAKER_THRESHOLD", "5"),
+            ("OEIS_BREAKER_COOLDOWN_SECS", "120"),
+            ("OEIS_SUGGEST_IDS", "true"),
+            ("MAX_SUBSEQUENCE_TERMS", "1000"),
+            ("MAX_BATCH_IDS", "20"),
+            ("TOOL_TIMEOUT_SECS", "5"),
+            ("SHUTDOWN_TIMEOUT_SECS", "0"),
+            ("ENABLE_PROMPTS", "false"),
+            ("ENABLE_RESOURCES", "false"),
+        ])
+        .unwrap();
+
+        assert_eq!(config.transport, Transport::Stdio);
+        assert_eq!(config.port, 9000);
+        assert_eq!(config.client.base_url, "http://localhost:8080/");
+        assert_eq!(
+            config.client.mirrors,
+            vec!["https://mirror1.example/", "http://mirror2.example"]
+        );
+        assert_eq!(config.client.timeout, Duration::from_secs(30));
+        assert_eq!(config.client.max_body_bytes, 1024 * 1024);
+        assert_eq!(
+            config.client.user_agent_contact.as_deref(),
+            Some("ops@example.com")
+        );
+        assert_eq!(config.cache_ttl, None);
+        assert_eq!(config.max_rps, Some(5));
+        assert_eq!(config.breaker_threshold, Some(5));
+        assert_eq!(config.breaker_cooldown, Duration::from_secs(120));
+        assert!(config.suggest_ids);
+        assert_eq!(config.limits.max_subsequence_length, 1000);
+        assert_eq!(config.limits.max_find_many_ids, 20);
+        assert_eq!(config.tool_timeout, Duration::from_secs(5));
+        assert_eq!(config.shutdown_timeout, Duration::ZERO);
+        assert!(!config.enable_prompts);
+        assert!(!config.enable_resources);
+    }
+
+    #[test]
+    fn test_zero_limit_is_invalid() {
+        let error = config_from(&[("MAX_BATCH_IDS", "0")])
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("MAX_BATCH_IDS"));
+
+        let error = config_from(&[("OEIS_MAX_BODY_BYTES", "0")])
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("OEIS_MAX_BODY_BYTES"));
+    }
+
+    #[test]
+    fn test_empty_value_is_unset() {
+        let config = config_from(&[("PORT", "")]).unwrap();
+        assert_eq!(config.port, 8000);
+    }
+
+    #[test]
+    fn test_bind_address() {
+        let config = config_from(&[("BIND_ADDRESS", "127.0.0.1")]).unwrap();
+        assert_eq!(config.bind_address, IpAddr::V4(Ipv4Addr::LOCALHOST));
+
+        let config = config_from(&[("BIND_ADDRESS", "::")]).unwrap();
+        assert_eq!(config.bind_address, IpAddr::V6(Ipv6Addr::UNSPECIFIED));
+
+        let config = config_from(&[("BIND_ADDRESS", "[::1]")]).unwrap();
+        assert_eq!(config.bind_address, IpAddr::V6(Ipv6Addr::LOCALHOST));
+    }
+
+    #[test]
+    fn test_invalid_bind_address() {
+        for value in ["localhost", "127.0.0.1:8000", "256.0.0.1"] {
+            let error = config_from(&[("BIND_ADDRESS", value)])
+                .unwrap_err()
+                .to_string();
+            assert!(error.contains("BIND_ADDRESS"), "{}", error);
+            assert!(error.contains(value), "{}", error);
+        }
+    }
+
+    #[test]
+    fn test_invalid_port() {
+        let error = config_from(&[("PORT", "http")]).unwrap_err().to_string();
+        assert!(error.contains("PORT"));
+        assert!(error.contains("\"http\""));
+
+        assert!(config_from(&[("PORT", "70000")]).is_err());
+    }
+
+    #[test]
+    fn test_invalid_timeout() {
+        let error = config_from(&[("OEIS_TIMEOUT_SECS", "ten")])
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("OEIS_TIMEOUT_SECS"));
+
+        let error = config_from(&[("OEIS_TIMEOUT_SECS", "0")])
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("OEIS_TIMEOUT_SECS"));
+    }
+
+    #[test]
+    fn test_invalid_transport_and_base_url() {
+        let error = config_from(&[("MCP_TRANSPORT", "grpc")])
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("MCP_TRANSPORT"));
+
+        let error = config_from(&[("OEIS_BASE_URL", "oeis.org")])
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("OEIS_BASE_URL"));
+
+        let error = config_from(&[("OEIS_MIRRORS", "https://mirror.example,ftp://oeis.org")])
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("OEIS_MIRRORS"));
+        assert!(error.contains("ftp://oeis.org"));
+    }
+
+    #[test]
+    fn test_tls_paths_must_be_paired_and_exist() {
+        let error = config_from(&[("TLS_CERT_PATH", "/tmp/cert.pem")])
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("must be set together"));
+
+        let error = config_from(&[
+            ("TLS_CERT_PATH", "/nonexistent/cert.pem"),
+            ("TLS_KEY_PATH", "/nonexistent/key.pem"),
+        ])
+        .unwrap_err()
+        .to_string();
+        assert!(error.contains("TLS file not found"));
+    }
+}
@@ -1,41 +1,528 @@
-use rmcp::transport::streamable_http_server::{
-    StreamableHttpService, session::local::LocalSessionManager,
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::{HeaderMap, HeaderName, HeaderValue, Method, StatusCode, header},
+    response::IntoResponse,
+    routing::{get, post},
+};
+use axum_server::tls_rustls::RustlsConfig;
+use rmcp::{
+    ServiceExt,
+    transport::{
+        stdio,
+        streamable_http_server::{StreamableHttpService, session::local::LocalSessionManager},
+    },
+};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tower_http::{
+    compression::CompressionLayer,
+    cors::{AllowOrigin, Any, CorsLayer},
 };
+use tracing::{info, warn};
 
+mod caching_client;
+mod circuit_breaker_client;
+mod config;
+mod metrics;
 mod oeis;
 mod oeis_client;
+mod rate_limited_client;
 mod tracer;
 
-use oeis::OEIS;
-use oeis_client::OEISClientImpl;
+use caching_client::{CachingClient, SequenceCache};
+use circuit_breaker_client::CircuitBreakerClient;
+use config::{Config, Transport};
+use metrics::METRICS;
+use oeis::{HEALTH_CHECK_ID, OEIS};
+use oeis_client::{OEISClient, OEISClientImpl};
+use rate_limited_client::RateLimitedClient;
 use tracer::setup_tracing;
 
+/// Header carrying `ADMIN_TOKEN` on `/admin` requests
+const ADMIN_TOKEN_HEADER: &str = "x-admin-token";
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    metrics::mark_process_start();
+    let _tracing = setup_tracing();
+
+    // Validate every setting before starting, so misconfiguration fails fast with a clear message
+    let config = Config::from_env()?;
+
+    let client = OEISClientImpl::from_config(&config.client);
+    match config.max_rps {
+        Some(max_rps) => serve_with_breaker(RateLimitedClient::new(client, max_rps), config).await,
+        None => serve_with_breaker(client, config).await,
+    }
+}
+
+/// The breaker wraps the rate limiter, so calls rejected while it is open skip the slot wait
+async fn serve_with_breaker<C: OEISClient + Clone + 'static>(
+    client: C,
+    config: Config,
+) -> anyhow::Result<()> {
+    match config.breaker_threshold {
+        Some(threshold) => {
+            let client = CircuitBreakerClient::new(client, threshold, config.breaker_cooldown);
+            serve_with_cache(client, config).await
+        }
+        None => serve_with_cache(client, config).await,
+    }
+}
+
+async fn serve_with_cache<C: OEISClient + Clone + 'static>(
+    client: C,
+    config: Config,
+) -> anyhow::Result<()> {
+    match config.cache_ttl {
+        Some(ttl) => {
+            let client = CachingClient::new(client, ttl);
+            let cache = client.cache();
+            serve(client, config, Some(cache)).await
+        }
+        None => serve(client, config, None).await,
+    }
+}
+
+async fn serve<C: OEISClient + Clone + 'static>(
+    client: C,
+    config: Config,
+    cache: Option<Arc<SequenceCache>>,
+) -> anyhow::Result<()> {
+    match config.transport {
+        Transport::Http => serve_http(client, config, cache).await,
+        Transport::Stdio => serve_stdio(client, config).await,
+    }
+}
+
+async fn serve_http<C: OEISClient + Clone + 'static>(
+    client: C,
+    config: Config,
+    cache: Option<Arc<SequenceCache>>,
+) -> anyhow::Result<()> {
     println!("🔄 Starting OEIS MCP server...");
-    setup_tracing();
 
-    let port = get_port_from_env();
-    let bind_address = format!("0.0.0.0:{}", port);
+    let bind_address = SocketAddr::new(config.bind_address, config.port);
+    let router = build_router(client, &config, cache);
+
+    match config.tls_paths {
+        Some((cert_path, key_path)) => {
+            serve_https(
+                router,
+                bind_address,
+                cert_path,
+                key_path,
+                config.shutdown_timeout,
+            )
+            .await
+        }
+        None => {
+            let tcp_listener = tokio::net::TcpListener::bind(bind_address).await?;
+
+            // Tells the drain timer below that the shutdown signal arrived
+            let (signaled_tx, signaled_rx) = tokio::sync::oneshot::channel();
+            let server = axum::serve(tcp_listener, router).with_graceful_shutdown(async move {
+                shutdown_signal().await;
+                let _ = signaled_tx.send(());
+            });
+
+            println!("🚀 OEIS MCP server is ready at {}", bind_address);
+
+            // Race the graceful shutdown against the drain timeout; returning drops the
+            // remaining connections when the runtime shuts down
+            let drain_timeout = async {
+                match signaled_rx.await {
+                    Ok(()) => tokio::time::sleep(config.shutdown_timeout).await,
+                    Err(_) => std::future::pending().await,
+                }
+            };
+            tokio::select! {
+                _ = server => {}
+                _ = drain_timeout => warn!(
+                    "Connections still open after {}s; closing them",
+                    config.shutdown_timeout.as_secs()
+                ),
+            }
+            Ok(())
+        }
+    }
+}
 
+/// MCP server handler with the per-server settings of `config` applied
+fn oeis_server<C: OEISClient + Clone + 'static>(client: C, config: &Config) -> OEIS<C> {
+    OEIS::new(client)
+        .with_suggest_ids(config.suggest_ids)
+        .with_limits(config.limits)
+        .with_tool_timeout(config.tool_timeout)
+        .with_prompts(config.enable_prompts)
+        .with_resources(config.enable_resources)
+}
+
+/// Routes of the HTTP transport: health check, metrics, the MCP service, and the
+/// `/admin` routes when `ADMIN_TOKEN` is set
+fn build_router<C: OEISClient + Clone + 'static>(
+    client: C,
+    config: &Config,
+    cache: Option<Arc<SequenceCache>>,
+) -> axum::Router {
+    let health_client = client.clone();
+    let server_config = config.clone();
     let service = StreamableHttpService::new(
-        || Ok(OEIS::new(OEISClientImpl::new())),
+        move || Ok(oeis_server(client.clone(), &server_config)),
         LocalSessionManager::default().into(),
         Default::default(),
     );
 
-    let router = axum::Router::new().nest_service("/mcp", service);
-    let tcp_listener = tokio::net::TcpListener::bind(&bind_address).await?;
+    // The default predicate skips `text/event-stream`, so SSE streams are never buffered
+    let router = axum::Router::new()
+        .route("/health", get(health::<C>))
+        .route("/metrics", get(metrics))
+        .with_state(health_client)
+        .nest_service("/mcp", service);
+    let router = match &config.admin_token {
+        Some(token) => router.merge(admin_router(token.clone(), cache)),
+        None => router,
+    }
+    .layer(CompressionLayer::new());
+    match config.cors_allowed_origins.as_deref().map(cors_layer) {
+        Some(cors) => router.layer(cors),
+        None => router,
+    }
+}
+
+#[derive(Clone)]
+struct AdminState {
+    token: String,
+    cache: Option<Arc<SequenceCache>>,
+}
+
+/// Cache management routes, each requiring the `x-admin-token` header
+fn admin_router(token: String, cache: Option<Arc<SequenceCache>>) -> axum::Router {
+    axum::Router::new()
+        .route("/admin/cache/clear", post(clear_cache))
+        .route("/admin/cache/stats", get(cache_stats))
+        .with_state(AdminState { token, cache })
+}
+
+/// Check the admin token and return the cache, or the error response to send
+fn authorized_cache(
+    state: &AdminState,
+    headers: &HeaderMap,
+) -> Result<Arc<SequenceCache>, (StatusCode, Json<Value>)> {
+    let token = headers
+        .get(ADMIN_TOKEN_HEADER)
+        .map(HeaderValue::as_bytes)
+        .unwrap_or_default();
+    if !constant_time_eq(token, state.token.as_bytes()) {
+        return Err((
+            StatusCode::UNAUTHORIZED,
+            Json(json!({ "error": "missing or invalid admin token" })),
+        ));
+    }
+    state.cache.clone().ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": "cache is disabled (set OEIS_CACHE_TTL_SECS)" })),
+        )
+    })
+}
+
+/// Compare secrets without returning early on the first differing byte
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+async fn clear_cache(
+    State(state): State<AdminState>,
+    headers: HeaderMap,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let cache = authorized_cache(&state, &headers)?;
+    let cleared = cache.clear();
+    info!(cleared, "Cleared cached sequences");
+    Ok(Json(json!({ "cleared": cleared })))
+}
+
+async fn cache_stats(
+    State(state): State<AdminState>,
+    headers: HeaderMap,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let cache = authorized_cache(&state, &headers)?;
+    Ok(Json(json!(cache.stats())))
+}
+
+/// Serve the router over HTTPS, terminating TLS with rustls
+async fn serve_https(
+    router: axum::Router,
+    bind_address: SocketAddr,
+    cert_path: PathBuf,
+    key_path: PathBuf,
+    shutdown_timeout: Duration,
+) -> anyhow::Result<()> {
+    let config = RustlsConfig::from_pem_file(cert_path, key_path).await?;
+
+    let handle = axum_server::Handle::new();
+    let shutdown_handle = handle.clone();
+    tokio::spawn(async move {
+        shutdown_signal().await;
+        // axum-server closes the connections left open once the timeout elapses
+        shutdown_handle.graceful_shutdown(Some(shutdown_timeout));
+    });
+
+    println!("🚀 OEIS MCP server is ready at {} (TLS)", bind_address);
 
-    let server = axum::serve(tcp_listener, router)
-        .with_graceful_shutdown(async { tokio::signal::ctrl_c().await.unwrap() });
+    axum_server::bind_rustls(bind_address, config)
+        .handle(handle)
+        .serve(router.into_make_service())
+        .await?;
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct HealthQuery {
+    /// Also check that OEIS is reachable
+    #[serde(default)]
+    upstream: bool,
+}
+
+/// Liveness probe; `?upstream=true` additionally pings OEIS and reports "degraded" on failure.
+/// The ping goes through `sequence_exists`, which the cache never answers.
+async fn health<C: OEISClient>(
+    State(client): State<C>,
+    Query(HealthQuery { upstream }): Query<HealthQuery>,
+) -> Json<Value> {
+    if !upstream {
+        return Json(json!({ "status": "ok" }));
+    }
+
+    match client.sequence_exists(HEALTH_CHECK_ID).await {
+        Ok(true) => Json(json!({ "status": "ok" })),
+        Ok(false) => Json(json!({ "status": "degraded" })),
+        Err(e) => {
+            warn!("Health check could not reach OEIS: {}", e);
+            Json(json!({ "status": "degraded" }))
+        }
+    }
+}
+
+/// Resolves on Ctrl-C, or on SIGTERM as sent by systemd / Kubernetes on Unix
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("failed to install Ctrl-C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
 
-    println!("🚀 OEIS MCP server is ready at {}", bind_address);
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
 
-    let _ = server.await;
+    println!("🛑 Shutting down OEIS MCP server...");
+}
+
+/// Counters in the Prometheus text exposition format
+async fn metrics() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        METRICS.render(),
+    )
+}
+
+async fn serve_stdio<C: OEISClient + Clone + 'static>(
+    client: C,
+    config: Config,
+) -> anyhow::Result<()> {
+    // stdout carries the MCP protocol, so status messages go to stderr
+    eprintln!("🔄 Starting OEIS MCP server over stdio...");
+
+    let service = oeis_server(client, &config).serve(stdio()).await?;
+
+    eprintln!("🚀 OEIS MCP server is ready on stdio");
+
+    service.waiting().await?;
     Ok(())
 }
 
-fn get_port_from_env() -> String {
-    std::env::var("PORT").unwrap_or_else(|_| "8000".to_string())
+/// CORS policy for `CORS_ALLOWED_ORIGINS` (comma-separated origins, or `*` for any origin)
+fn cors_layer(value: &str) -> CorsLayer {
+    if value == "*" {
+        return CorsLayer::permissive();
+    }
+
+    let origins: Vec<HeaderValue> = value
+        .split(',')
+        .map(str::trim)
+        .filter(|origin| !origin.is_empty())
+        .filter_map(|origin| match HeaderValue::from_str(origin) {
+            Ok(origin) => Some(origin),
+            Err(_) => {
+                warn!("Ignoring invalid CORS origin: {:?}", origin);
+                None
+            }
+        })
+        .collect();
+
+    // Streamable HTTP clients need to read the session ID header set by the server
+    CorsLayer::new()
+        .allow_origin(AllowOrigin::list(origins))
+        .allow_methods([Method::GET, Method::POST, Method::DELETE])
+        .allow_headers(Any)
+        .expose_headers([HeaderName::from_static("mcp-session-id")])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    /// Serve `router` on an ephemeral local port, returning its base URL
+    async fn spawn_server(router: axum::Router) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await });
+        format!("http://{}", address)
+    }
+
+    #[tokio::test]
+    async fn test_compressed_response_round_trips() {
+        // Defaults only, regardless of the environment running the tests
+        let config = Config::from_lookup(|_| None).unwrap();
+        let router = build_router(OEISClientImpl::from_config(&config.client), &config, None);
+
+        let base_url = spawn_server(router).await;
+
+        let response = reqwest::Client::new()
+            .get(format!("{}/metrics", base_url))
+            .header(header::ACCEPT_ENCODING, "gzip")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.headers()[header::CONTENT_ENCODING], "gzip");
+
+        let compressed = response.bytes().await.unwrap();
+        let mut body = String::new();
+        GzDecoder::new(compressed.as_ref())
+            .read_to_string(&mut body)
+            .unwrap();
+        assert!(body.contains("# TYPE oeis_requests_total counter"));
+    }
+
+    #[tokio::test]
+    async fn test_admin_cache_clear_makes_lookups_miss() {
+        let oeis = httpmock::MockServer::start();
+        let upstream = oeis.mock(|when, then| {
+            when.path("/search").query_param("q", "id:A000045");
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .body(r#"{"count": 1, "results": [{"number": 45, "data": "0, 1, 1", "name": "Fibonacci numbers", "keyword": "nonn"}]}"#);
+        });
+        let client = CachingClient::new(
+            OEISClientImpl::with_url(oeis.base_url()),
+            std::time::Duration::from_secs(60),
+        );
+        let config =
+            Config::from_lookup(|name| (name == "ADMIN_TOKEN").then(|| "secret".to_string()))
+                .unwrap();
+        let base_url =
+            spawn_server(build_router(client.clone(), &config, Some(client.cache()))).await;
+        let http = reqwest::Client::new();
+
+        client.find_by_id("A000045").await.unwrap();
+        client.find_by_id("A000045").await.unwrap();
+        upstream.assert_calls(1);
+
+        let unauthorized = http
+            .post(format!("{}/admin/cache/clear", base_url))
+            .header(ADMIN_TOKEN_HEADER, "wrong")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(unauthorized.status(), StatusCode::UNAUTHORIZED);
+
+        let cleared: Value = http
+            .post(format!("{}/admin/cache/clear", base_url))
+            .header(ADMIN_TOKEN_HEADER, "secret")
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(cleared, json!({ "cleared": 1 }));
+
+        client.find_by_id("A000045").await.unwrap();
+        upstream.assert_calls(2);
+
+        let stats: Value = http
+            .get(format!("{}/admin/cache/stats", base_url))
+            .header(ADMIN_TOKEN_HEADER, "secret")
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(stats, json!({ "entries": 1, "hits": 1, "misses": 2 }));
+    }
+
+    #[tokio::test]
+    async fn test_upstream_health_check_bypasses_cache() {
+        let oeis = httpmock::MockServer::start();
+        let upstream = oeis.mock(|when, then| {
+            when.path("/search").query_param("q", "id:A000045");
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .body(r#"{"count": 1, "results": [{"number": 45, "data": "0, 1, 1", "name": "Fibonacci numbers", "keyword": "nonn"}]}"#);
+        });
+        let client = CachingClient::new(
+            OEISClientImpl::with_url(oeis.base_url()),
+            std::time::Duration::from_secs(60),
+        );
+        let config = Config::from_lookup(|_| None).unwrap();
+        let base_url = spawn_server(build_router(client.clone(), &config, None)).await;
+
+        client.find_by_id(HEALTH_CHECK_ID).await.unwrap();
+        upstream.assert_calls(1);
+
+        let health: Value = reqwest::get(format!("{}/health?upstream=true", base_url))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(health, json!({ "status": "ok" }));
+        upstream.assert_calls(2);
+    }
+
+    #[tokio::test]
+    async fn test_admin_routes_off_without_token() {
+        let config = Config::from_lookup(|_| None).unwrap();
+        let client = OEISClientImpl::from_config(&config.client);
+        let base_url = spawn_server(build_router(client, &config, None)).await;
+
+        let response = reqwest::Client::new()
+            .get(format!("{}/admin/cache/stats", base_url))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
 }
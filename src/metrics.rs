@@ -0,0 +1,181 @@
+use std::fmt::Write;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use tracing::info;
+
+/// Process-wide counters exposed at `GET /metrics`
+pub static METRICS: Metrics = Metrics::new();
+
+/// When the process started, reported as uptime by `server_status`
+static PROCESS_START: OnceLock<Instant> = OnceLock::new();
+
+/// Record the process start time; call first thing in `main`
+pub fn mark_process_start() {
+    PROCESS_START.get_or_init(Instant::now);
+}
+
+/// Time since `mark_process_start`, or since the first call if it was never made (as in tests)
+pub fn uptime() -> Duration {
+    PROCESS_START.get_or_init(Instant::now).elapsed()
+}
+
+#[derive(Debug, Default)]
+pub struct Metrics {
+    /// HTTP requests sent to OEIS, including retries
+    requests: AtomicU64,
+    /// OEIS requests that failed after retries (transport errors, 429 / 5xx)
+    errors: AtomicU64,
+    /// `find_by_id` lookups served from the cache
+    cache_hits: AtomicU64,
+}
+
+impl Metrics {
+    pub const fn new() -> Self {
+        Self {
+            requests: AtomicU64::new(0),
+            errors: AtomicU64::new(0),
+            cache_hits: AtomicU64::new(0),
+        }
+    }
+
+    pub fn inc_requests(&self) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn inc_errors(&self) {
+        self.errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn inc_cache_hits(&self) {
+        self.cache_hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Render the counters in the Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let counters = [
+            (
+                "oeis_requests_total",
+                "HTTP requests sent to OEIS, including retries.",
+                &self.requests,
+            ),
+            (
+                "oeis_errors_total",
+                "OEIS requests that failed after retries.",
+                &self.errors,
+            ),
+            (
+                "oeis_cache_hits_total",
+                "Sequence lookups served from the cache.",
+                &self.cache_hits,
+            ),
+        ];
+
+        let mut output = String::new();
+        for (name, help, counter) in counters {
+            let _ = writeln!(output, "# HELP {} {}", name, help);
+            let _ = writeln!(output, "# TYPE {} counter", name);
+            let _ = writeln!(output, "{} {}", name, counter.load(Ordering::Relaxed));
+        }
+        output
+    }
+}
+
+/// Counters of a single MCP session, logged as a summary when the session ends.
+/// The transport creates one server handler per session and drops it at teardown,
+/// so the summary is emitted from `Drop`.
+#[derive(Debug)]
+pub struct SessionStats {
+    started: Instant,
+    tool_calls: AtomicU64,
+    /// `Mcp-Session-Id` of the session, known from its first request after `initialize`
+    session_id: OnceLock<String>,
+}
+
+impl SessionStats {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            tool_calls: AtomicU64::new(0),
+            session_id: OnceLock::new(),
+        }
+    }
+
+    /// Count a tool call, remembering the session ID the first time it is seen
+    pub fn record_tool_call(&self, session_id: Option<&str>) {
+        if let Some(session_id) = session_id {
+            let _ = self.session_id.get_or_init(|| session_id.to_string());
+        }
+        self.tool_calls.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn tool_calls(&self) -> u64 {
+        self.tool_calls.load(Ordering::Relaxed)
+    }
+
+    pub fn session_id(&self) -> Option<&str> {
+        self.session_id.get().map(String::as_str)
+    }
+}
+
+impl Default for SessionStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for SessionStats {
+    fn drop(&mut self) {
+        info!(
+            session_id = self.session_id().unwrap_or("(none)"),
+            tool_calls = self.tool_calls(),
+            duration_secs = self.started.elapsed().as_secs_f64(),
+            "MCP session ended"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_starts_at_zero() {
+        let metrics = Metrics::new();
+
+        let output = metrics.render();
+
+        assert!(output.contains("# TYPE oeis_requests_total counter\noeis_requests_total 0\n"));
+        assert!(output.contains("# TYPE oeis_errors_total counter\noeis_errors_total 0\n"));
+        assert!(output.contains("# TYPE oeis_cache_hits_total counter\noeis_cache_hits_total 0\n"));
+    }
+
+    #[test]
+    fn test_render_reports_increments() {
+        let metrics = Metrics::new();
+
+        metrics.inc_requests();
+        metrics.inc_requests();
+        metrics.inc_errors();
+        metrics.inc_cache_hits();
+
+        let output = metrics.render();
+
+        assert!(output.contains("\noeis_requests_total 2\n"));
+        assert!(output.contains("\noeis_errors_total 1\n"));
+        assert!(output.contains("\noeis_cache_hits_total 1\n"));
+    }
+
+    #[test]
+    fn test_session_stats_counts_tool_calls() {
+        let stats = SessionStats::new();
+
+        stats.record_tool_call(None);
+        stats.record_tool_call(Some("session-1"));
+        stats.record_tool_call(Some("session-1"));
+
+        assert_eq!(stats.tool_calls(), 3);
+        assert_eq!(stats.session_id(), Some("session-1"));
+    }
+}
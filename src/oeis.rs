@@ -1,28 +1,197 @@
 use rmcp::{
     ErrorData as McpError, RoleServer, ServerHandler,
     handler::server::{
+        prompt::PromptContext,
         router::{prompt::PromptRouter, tool::ToolRouter},
+        tool::ToolCallContext,
         wrapper::Parameters,
     },
     model::*,
-    prompt, prompt_handler, prompt_router,
+    prompt, prompt_router,
     schemars::{self, JsonSchema},
     service::RequestContext,
-    tool, tool_handler, tool_router,
+    tool, tool_router,
 };
+use std::cmp::Ordering;
+use std::collections::hash_map::RandomState;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::stream::{self, StreamExt};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use tracing::info;
+use tracing::{info, instrument, warn};
+
+use crate::metrics::{self, SessionStats};
+use crate::oeis_client::{
+    BFileEntry, DEFAULT_MAX_RESULTS, MAX_SEARCH_RESULTS, MatchCount, OEISClient, OEISError,
+    OEISSequence, SearchResult, SequenceKeywords, SignMode,
+};
+use crate::tracer::next_request_id;
+
+/// Sequence fetched by the upstream health checks (`/health?upstream=true`, `server_status`)
+pub const HEALTH_CHECK_ID: &str = "A000045";
+
+/// Default maximum number of terms accepted by subsequence searches
+const DEFAULT_MAX_SUBSEQUENCE_LENGTH: usize = 64;
+
+/// Maximum number of cross-references resolved by `resolve_xrefs`
+const MAX_RESOLVED_XREFS: usize = 20;
+
+/// Maximum number of concurrent OEIS lookups made by `resolve_xrefs`
+const XREF_CONCURRENCY: usize = 4;
+
+/// Maximum radius accepted by `neighbors`
+const MAX_NEIGHBOR_RADIUS: usize = 10;
+
+/// Maximum number of concurrent OEIS lookups made by `neighbors`
+const NEIGHBOR_CONCURRENCY: usize = 4;
+
+/// Radius of the adjacent A-numbers suggested when an ID is not found
+const SUGGESTION_RADIUS: usize = 2;
+
+/// Default time budget of a single tool call, including every OEIS request it makes
+pub const DEFAULT_TOOL_TIMEOUT_SECS: u64 = 60;
+
+/// Default maximum number of IDs accepted by `find_many`
+const DEFAULT_MAX_FIND_MANY_IDS: usize = 50;
+
+/// Default number of concurrent OEIS lookups made by `find_many`
+const DEFAULT_FIND_MANY_CONCURRENCY: usize = 4;
+
+/// Maximum number of concurrent OEIS lookups made by `validate_ids`
+const VALIDATE_IDS_CONCURRENCY: usize = 4;
+
+/// Maximum number of b-file entries returned by a single `get_bfile` call
+const MAX_BFILE_PAGE_SIZE: usize = 1000;
+
+/// Maximum number of links listed in the `sequence_analysis` prompt
+const MAX_ANALYSIS_LINKS: usize = 20;
 
-use crate::oeis_client::{OEISClient, OEISSequence};
+/// Keyword used by `random_sequence` when none is given
+const DEFAULT_RANDOM_KEYWORD: &str = "nice";
+
+/// Maximum width of a `%S`/`%T`/`%U` data line in `format_entry`
+const ENTRY_DATA_LINE_WIDTH: usize = 70;
+
+/// Weight of the keywords that signal an entry's quality when ranking `suggest_sequences`
+const QUALITY_KEYWORDS: &[(&str, i32)] = &[
+    ("core", 3),
+    ("nice", 2),
+    ("easy", 1),
+    ("less", -1),
+    ("obsc", -1),
+    ("dumb", -2),
+    ("dead", -3),
+];
+
+/// Keywords used by OEIS to classify sequences, with what each one means
+const OEIS_KEYWORDS: &[(&str, &str)] = &[
+    (
+        "allocated",
+        "An A-number reserved for a sequence not yet submitted",
+    ),
+    ("base", "Depends on the base used to write the numbers"),
+    ("bref", "Only a few terms are known"),
+    ("changed", "Changed in the last few weeks"),
+    ("cofr", "A continued fraction expansion"),
+    ("cons", "Decimal expansion of a constant"),
+    ("core", "A fundamental sequence"),
+    (
+        "dead",
+        "An erroneous or duplicated sequence, kept for reference",
+    ),
+    ("dumb", "An unimportant sequence"),
+    ("dupe", "A duplicate of another sequence"),
+    ("easy", "Easy to compute further terms"),
+    ("eigen", "An eigensequence (fixed point of a transform)"),
+    ("fini", "A finite sequence"),
+    (
+        "frac",
+        "Numerators or denominators of a sequence of fractions",
+    ),
+    ("full", "All terms are listed"),
+    ("hard", "Further terms are hard to compute"),
+    ("hear", "Worth listening to"),
+    ("less", "Less interesting"),
+    ("look", "Worth plotting"),
+    ("more", "More terms are wanted"),
+    ("mult", "A multiplicative function"),
+    ("new", "Recently added"),
+    ("nice", "An exceptionally nice sequence"),
+    ("nonn", "All terms are nonnegative"),
+    ("obsc", "Obscure, needs a better definition"),
+    ("recycled", "A recycled A-number"),
+    ("sign", "Contains negative terms"),
+    ("tabf", "An irregular triangle or table read by rows"),
+    ("tabl", "A regular triangle read by rows"),
+    ("uned", "Not yet edited"),
+    ("unkn", "Little is known about the sequence"),
+    ("walk", "Counts walks or self-avoiding paths"),
+    ("word", "Depends on words in some language"),
+];
+
+/// Well-known sequence nicknames (normalized by `normalize_nickname`) and their A-numbers
+const SEQUENCE_NICKNAMES: &[(&str, &str)] = &[
+    ("bell", "A000110"),
+    ("catalan", "A000108"),
+    ("cubes", "A000578"),
+    ("euler totient", "A000010"),
+    ("factorials", "A000142"),
+    ("fibonacci", "A000045"),
+    ("lucas", "A000032"),
+    ("mersenne primes", "A000668"),
+    ("motzkin", "A001006"),
+    ("natural", "A000027"),
+    ("padovan", "A000931"),
+    ("partitions", "A000041"),
+    ("pell", "A000129"),
+    ("perfect", "A000396"),
+    ("powers of 2", "A000079"),
+    ("primes", "A000040"),
+    ("squares", "A000290"),
+    ("triangular", "A000217"),
+    ("tribonacci", "A000073"),
+];
+
+/// Caps on the size of tool inputs, so a client cannot trigger oversized OEIS queries
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InputLimits {
+    /// Maximum number of terms in a subsequence search
+    pub max_subsequence_length: usize,
+    /// Maximum number of IDs in a `find_many` or `validate_ids` batch
+    pub max_find_many_ids: usize,
+}
+
+impl Default for InputLimits {
+    fn default() -> Self {
+        Self {
+            max_subsequence_length: DEFAULT_MAX_SUBSEQUENCE_LENGTH,
+            max_find_many_ids: DEFAULT_MAX_FIND_MANY_IDS,
+        }
+    }
+}
 
 #[derive(Clone)]
 #[allow(clippy::upper_case_acronyms)]
 pub struct OEIS<C: OEISClient> {
     client: C,
-    #[allow(dead_code)]
+    /// Fixed seed for `random_sequence`, so tests are deterministic
+    rng_seed: Option<u64>,
+    /// Suggest existing adjacent IDs when a lookup by ID finds nothing
+    suggest_ids: bool,
+    limits: InputLimits,
+    tool_timeout: Duration,
+    /// Shared by clones of this handler, so the summary is logged once the whole session is gone
+    session: Arc<SessionStats>,
+    /// Advertise and serve prompts; tools are always enabled
+    prompts_enabled: bool,
+    /// Advertise and serve resources
+    resources_enabled: bool,
     tool_router: ToolRouter<OEIS<C>>,
-    #[allow(dead_code)]
     prompt_router: PromptRouter<OEIS<C>>,
 }
 
@@ -30,35 +199,479 @@ impl<C: OEISClient + Clone + 'static> OEIS<C> {
     pub fn new(client: C) -> Self {
         Self {
             client,
+            rng_seed: None,
+            suggest_ids: false,
+            limits: InputLimits::default(),
+            tool_timeout: Duration::from_secs(DEFAULT_TOOL_TIMEOUT_SECS),
+            session: Arc::new(SessionStats::new()),
+            prompts_enabled: true,
+            resources_enabled: true,
             tool_router: Self::tool_router(),
             prompt_router: Self::prompt_router(),
         }
     }
 
+    /// Enable "did you mean" suggestions, which cost extra OEIS requests on a miss
+    pub fn with_suggest_ids(mut self, suggest_ids: bool) -> Self {
+        self.suggest_ids = suggest_ids;
+        self
+    }
+
+    pub fn with_limits(mut self, limits: InputLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    pub fn with_tool_timeout(mut self, tool_timeout: Duration) -> Self {
+        self.tool_timeout = tool_timeout;
+        self
+    }
+
+    /// Leave prompts out of the capabilities and reject prompt requests when `false`
+    pub fn with_prompts(mut self, enabled: bool) -> Self {
+        self.prompts_enabled = enabled;
+        self
+    }
+
+    /// Leave resources out of the capabilities and reject resource requests when `false`
+    pub fn with_resources(mut self, enabled: bool) -> Self {
+        self.resources_enabled = enabled;
+        self
+    }
+
+    /// Server instructions describing only the enabled capabilities
+    fn instructions(&self) -> String {
+        let mut sections = vec![TOOL_INSTRUCTIONS];
+        if self.prompts_enabled {
+            sections.push(PROMPT_INSTRUCTIONS);
+        }
+        if self.resources_enabled {
+            sections.push(RESOURCE_INSTRUCTIONS);
+        }
+        sections.push(USAGE_INSTRUCTIONS);
+        sections.join(" ")
+    }
+
+    /// Reject requests for a capability the operator disabled, as if it were not implemented
+    fn check_enabled(&self, enabled: bool, capability: &str) -> Result<(), McpError> {
+        if enabled {
+            return Ok(());
+        }
+        Err(McpError::new(
+            ErrorCode::METHOD_NOT_FOUND,
+            format!("{} are not supported by this server", capability),
+            None,
+        ))
+    }
+
+    #[cfg(test)]
+    fn with_rng_seed(mut self, seed: u64) -> Self {
+        self.rng_seed = Some(seed);
+        self
+    }
+
+    /// Run a tool call within the per-call budget, so one slow call cannot tie up a session
+    async fn time_limited<T>(
+        &self,
+        call: impl Future<Output = Result<T, McpError>>,
+    ) -> Result<T, McpError> {
+        tokio::time::timeout(self.tool_timeout, call)
+            .await
+            .unwrap_or_else(|_| {
+                warn!("Tool call exceeded {:?}", self.tool_timeout);
+                Err(McpError::new(
+                    ErrorCode::INTERNAL_ERROR,
+                    "tool execution timed out".to_string(),
+                    Some(json!({"timeout_secs": self.tool_timeout.as_secs_f64()})),
+                ))
+            })
+    }
+
+    /// Render the resource at `uri` (e.g., "oeis://sequence/A000045?format=markdown")
+    async fn resource_text(&self, uri: &str) -> Result<(String, ResourceFormat), McpError> {
+        let (path, query) = match uri.split_once('?') {
+            Some((path, query)) => (path, Some(query)),
+            None => (uri, None),
+        };
+        let format = ResourceFormat::from_query(query)?;
+
+        // Parse URI patterns: oeis://sequence/{id}, oeis://bfile/{id}
+        let text = if let Some(id) = path.strip_prefix("oeis://sequence/") {
+            let sequence = self.find_sequence(id).await?;
+            match format {
+                ResourceFormat::Json => serde_json::to_string_pretty(&sequence),
+                ResourceFormat::Markdown => Ok(self.build_sequence_context(&sequence)),
+            }
+        } else if let Some(id) = path.strip_prefix("oeis://bfile/") {
+            if format == ResourceFormat::Markdown {
+                return Err(McpError::new(
+                    ErrorCode::INVALID_PARAMS,
+                    "b-file resources are only available as JSON".to_string(),
+                    Some(json!({"uri": uri})),
+                ));
+            }
+            // Return JSON list of `{ index, value }` entries
+            let entries = self.find_bfile(id).await?;
+            serde_json::to_string_pretty(&entries)
+        } else {
+            return Err(McpError::new(
+                ErrorCode::INVALID_PARAMS,
+                format!(
+                    "Invalid resource URI: {}. Expected format: oeis://sequence/{{id}} or oeis://bfile/{{id}}",
+                    uri
+                ),
+                Some(json!({"uri": uri})),
+            ));
+        }
+        .map_err(|e| McpError::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+
+        Ok((text, format))
+    }
+
     /// Find a sequence by ID from the OEIS API
     async fn find_sequence(&self, id: &str) -> Result<OEISSequence, McpError> {
-        let result = self
-            .client
-            .find_by_id(id)
+        let id = normalize_id(id)?;
+        let result = self.client.find_by_id(&id).await.map_err(client_error)?;
+        if let Some(sequence) = result {
+            return Ok(sequence);
+        }
+
+        let suggestions: Vec<String> = match id[1..].parse() {
+            Ok(number) if self.suggest_ids => self
+                .existing_neighbors(number, SUGGESTION_RADIUS as u64)
+                .await
+                .into_iter()
+                .map(|neighbor| neighbor.id)
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        if suggestions.is_empty() {
+            return Err(McpError::new(
+                ErrorCode::INVALID_PARAMS,
+                format!("No sequence found (by id: {})", id),
+                None,
+            ));
+        }
+
+        Err(McpError::new(
+            ErrorCode::INVALID_PARAMS,
+            format!(
+                "No sequence found (by id: {}). Did you mean {}?",
+                id,
+                suggestions.join(" or ")
+            ),
+            Some(json!({"suggestions": suggestions})),
+        ))
+    }
+
+    /// Fetch the existing sequences whose A-numbers are within `radius` of `number`
+    async fn existing_neighbors(&self, number: u64, radius: u64) -> Vec<Neighbor> {
+        // A-numbers start at A000001
+        let neighbor_ids: Vec<String> = (number.saturating_sub(radius).max(1)
+            ..=number.saturating_add(radius))
+            .filter(|&n| n != number)
+            .map(|n| format!("A{:06}", n))
+            .collect();
+
+        stream::iter(neighbor_ids)
+            .map(|neighbor_id| async move {
+                match self.client.find_by_id(&neighbor_id).await {
+                    Ok(found) => found.map(|s| Neighbor {
+                        id: neighbor_id,
+                        name: s.name,
+                    }),
+                    Err(e) => {
+                        warn!("Failed to fetch neighbor {}: {}", neighbor_id, e);
+                        None
+                    }
+                }
+            })
+            .buffered(NEIGHBOR_CONCURRENCY)
+            .filter_map(|neighbor| async move { neighbor })
+            .collect()
+            .await
+    }
+
+    /// Look up one ID for `find_many`, capturing failures in the entry instead of failing
+    async fn find_entry(&self, id: String) -> FindManyEntry {
+        let lookup = async {
+            let normalized = normalize_id(&id)?;
+            self.client
+                .find_by_id(&normalized)
+                .await
+                .map_err(client_error)
+        };
+
+        match lookup.await {
+            Ok(result) => FindManyEntry {
+                id,
+                result,
+                error: None,
+            },
+            Err(e) => FindManyEntry {
+                id,
+                result: None,
+                error: Some(e.message.to_string()),
+            },
+        }
+    }
+
+    /// Checks one ID for `validate_ids`, reporting failures in the entry rather than as an error
+    async fn validate_id(&self, id: String) -> IdValidity {
+        let lookup = async {
+            let normalized = normalize_id(&id)?;
+            self.client
+                .sequence_exists(&normalized)
+                .await
+                .map_err(client_error)
+        };
+
+        match lookup.await {
+            Ok(exists) => IdValidity {
+                id,
+                exists,
+                error: None,
+            },
+            Err(e) => IdValidity {
+                id,
+                exists: false,
+                error: Some(e.message.to_string()),
+            },
+        }
+    }
+
+    /// Search sequences by keyword from the OEIS API, returning at most `max_results`
+    /// (clamped to `MAX_SEARCH_RESULTS`) matches from `start` on
+    async fn search_keyword(
+        &self,
+        keyword: &str,
+        start: usize,
+        max_results: usize,
+    ) -> Result<SearchResult, McpError> {
+        let keyword = keyword.trim().to_lowercase();
+        if !OEIS_KEYWORDS.iter().any(|(name, _)| *name == keyword) {
+            return Err(McpError::new(
+                ErrorCode::INVALID_PARAMS,
+                format!(
+                    "Unknown keyword: {}. Expected one of: {}",
+                    keyword,
+                    OEIS_KEYWORDS
+                        .iter()
+                        .map(|(name, _)| *name)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                Some(json!({"keyword": keyword})),
+            ));
+        }
+
+        let max_results = max_results.clamp(1, MAX_SEARCH_RESULTS);
+        self.client
+            .search_by_keyword(&keyword, start, max_results)
             .await
-            .map_err(|e| McpError::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+            .map_err(client_error)
+    }
+
+    /// Search sequences by free text from the OEIS API
+    async fn search_free_text(&self, query: &str) -> Result<SearchResult, McpError> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Err(McpError::new(
+                ErrorCode::INVALID_PARAMS,
+                "Search query must not be empty".to_string(),
+                None,
+            ));
+        }
+
+        self.client.search_text(query).await.map_err(client_error)
+    }
+
+    /// Fetch the b-file (all known terms) of a sequence by ID from OEIS
+    async fn find_bfile(&self, id: &str) -> Result<Vec<BFileEntry>, McpError> {
+        let id = normalize_id(id)?;
+        let result = self.client.fetch_bfile(&id).await.map_err(client_error)?;
 
         result.ok_or_else(|| {
             McpError::new(
                 ErrorCode::INVALID_PARAMS,
-                format!("No sequence found (by id: {})", id),
+                format!("No b-file found (by id: {})", id),
                 None,
             )
         })
     }
 
     /// Search sequences by subsequence from the OEIS API
-    async fn search_sequences(&self, subsequence: &[i64]) -> Result<Vec<OEISSequence>, McpError> {
+    async fn search_sequences(
+        &self,
+        subsequence: &[i64],
+        start: usize,
+        mode: SignMode,
+        max_results: usize,
+    ) -> Result<SearchResult, McpError> {
+        if subsequence.is_empty() {
+            return Ok(SearchResult {
+                start,
+                ..Default::default()
+            });
+        }
+
+        self.check_subsequence_length(subsequence.len())?;
+
+        // Clamp here as well so no client implementation is ever asked for more than the ceiling
+        let max_results = max_results.clamp(1, MAX_SEARCH_RESULTS);
+        self.client
+            .search_by_subsequence(subsequence, start, mode, max_results)
+            .await
+            .map_err(client_error)
+    }
+
+    /// Reject subsequences longer than the configured limit
+    fn check_subsequence_length(&self, length: usize) -> Result<(), McpError> {
+        let max = self.limits.max_subsequence_length;
+        if length > max {
+            return Err(McpError::new(
+                ErrorCode::INVALID_PARAMS,
+                format!("Subsequence too long: {} terms (max: {})", length, max),
+                Some(json!({"length": length, "max": max})),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Count the sequences containing `subsequence` without fetching them
+    async fn count_matches(&self, subsequence: &[i64]) -> Result<MatchCount, McpError> {
+        if subsequence.is_empty() {
+            return Ok(MatchCount::default());
+        }
+
+        self.check_subsequence_length(subsequence.len())?;
+
         self.client
-            .search_by_subsequence(subsequence)
+            .count_subsequence_matches(subsequence)
             .await
-            .map_err(|e| McpError::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))
+            .map_err(client_error)
+    }
+}
+
+/// Representation requested with a resource URI's `?format=` query
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ResourceFormat {
+    Json,
+    Markdown,
+}
+
+impl ResourceFormat {
+    /// Parse the `format` parameter of a URI query string, defaulting to JSON
+    fn from_query(query: Option<&str>) -> Result<Self, McpError> {
+        let format = query
+            .into_iter()
+            .flat_map(|query| query.split('&'))
+            .find_map(|pair| pair.strip_prefix("format="));
+
+        match format {
+            None | Some("json") => Ok(ResourceFormat::Json),
+            Some("markdown") => Ok(ResourceFormat::Markdown),
+            Some(other) => Err(McpError::new(
+                ErrorCode::INVALID_PARAMS,
+                format!(
+                    "Unsupported resource format: {}. Expected \"json\" or \"markdown\"",
+                    other
+                ),
+                Some(json!({"format": other})),
+            )),
+        }
+    }
+}
+
+/// Normalize a sequence ID to the canonical A-number form (e.g., "45", "a45" -> "A000045")
+fn normalize_id(input: &str) -> Result<String, McpError> {
+    let trimmed = input.trim();
+    let digits = trimmed.strip_prefix(['A', 'a']).unwrap_or(trimmed);
+
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return Err(invalid_id_error(input));
     }
+
+    let number: u64 = digits.parse().map_err(|_| invalid_id_error(input))?;
+    Ok(format!("A{:06}", number))
+}
+
+fn invalid_id_error(input: &str) -> McpError {
+    McpError::new(
+        ErrorCode::INVALID_PARAMS,
+        format!(
+            "Invalid sequence ID: {}. Expected an A-number like A000045",
+            input
+        ),
+        Some(json!({"id": input})),
+    )
+}
+
+/// Map an OEIS client error to an MCP error
+fn client_error(error: OEISError) -> McpError {
+    let (code, data) = match &error {
+        OEISError::NotFound => (ErrorCode::INVALID_PARAMS, None),
+        OEISError::TooManyResults(count) => (
+            ErrorCode::INVALID_PARAMS,
+            Some(json!({"count": count, "too_broad": true})),
+        ),
+        OEISError::Http(status) => (
+            ErrorCode::INTERNAL_ERROR,
+            Some(json!({"status": status.as_u16()})),
+        ),
+        OEISError::RateLimited => (
+            ErrorCode::INTERNAL_ERROR,
+            Some(json!({"status": 429, "retryable": true})),
+        ),
+        OEISError::Timeout => (ErrorCode::INTERNAL_ERROR, Some(json!({"retryable": true}))),
+        OEISError::CircuitOpen(remaining) => (
+            ErrorCode::INTERNAL_ERROR,
+            Some(json!({"retryable": true, "retry_after_secs": remaining.as_secs() + 1})),
+        ),
+        OEISError::BodyTooLarge(max_bytes) => (
+            ErrorCode::INTERNAL_ERROR,
+            Some(json!({"max_bytes": max_bytes})),
+        ),
+        OEISError::Deserialize(_) | OEISError::Request(_) => (ErrorCode::INTERNAL_ERROR, None),
+    };
+    McpError::new(code, error.to_string(), data)
+}
+
+/// Parse the listed terms of `sequence` as `T`, rejecting a term out of its range (e.g.,
+/// 21! for `i64`) with `INVALID_PARAMS` naming the term, instead of a bare parse error
+fn parse_terms<T: FromStr>(sequence: &OEISSequence) -> Result<Vec<T>, McpError> {
+    parse_first_terms(sequence, usize::MAX)
+}
+
+/// Like `parse_terms`, for at most the first `count` terms; later terms are not parsed
+fn parse_first_terms<T: FromStr>(
+    sequence: &OEISSequence,
+    count: usize,
+) -> Result<Vec<T>, McpError> {
+    sequence
+        .data
+        .split(',')
+        .map(str::trim)
+        .filter(|term| !term.is_empty())
+        .take(count)
+        .zip(sequence.first_index()..)
+        .map(|(term, n)| {
+            term.parse().map_err(|_| {
+                McpError::new(
+                    ErrorCode::INVALID_PARAMS,
+                    format!(
+                        "{} has a term too large for this tool: a({}) = {} does not fit in {}",
+                        sequence.id(),
+                        n,
+                        term,
+                        std::any::type_name::<T>()
+                    ),
+                    Some(json!({"id": sequence.id(), "n": n, "term": term})),
+                )
+            })
+        })
+        .collect()
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -74,450 +687,8166 @@ pub struct FindResponse {
     pub result: OEISSequence,
 }
 
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct KeywordInfo {
+    pub keyword: String,
+    pub description: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ListKeywordsResponse {
+    pub keywords: Vec<KeywordInfo>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct FindByNameRequest {
+    /// The exact sequence name (e.g., "Fibonacci numbers"), matched ignoring case
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct FindByNameResponse {
+    /// `None` when no sequence has exactly this name
+    pub result: Option<OEISSequence>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct NicknameRequest {
+    /// Common name of the sequence (e.g., "Catalan numbers", "Fibonacci")
+    pub nickname: String,
+}
+
+/// How `lookup_by_nickname` resolved a nickname
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NicknameMatch {
+    /// Found in the built-in nickname table
+    Table,
+    /// Not in the table; the best OEIS match for the nickname within sequence names
+    NameSearch,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct NicknameLookupResponse {
+    pub id: String,
+    pub name: String,
+    pub matched_by: NicknameMatch,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct FindManyRequest {
+    /// The OEIS sequence IDs to fetch (e.g., ["A000045", "A000032"])
+    pub ids: Vec<String>,
+    /// Maximum number of concurrent OEIS requests (defaults to 4)
+    pub concurrency: Option<usize>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct FindManyEntry {
+    pub id: String,
+    /// `None` when the sequence was not found or could not be fetched
+    pub result: Option<OEISSequence>,
+    /// Why the lookup failed, if it did
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct FindManyResponse {
+    pub results: Vec<FindManyEntry>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ValidateIdsRequest {
+    /// The OEIS sequence IDs to check (e.g., ["A000045", "45", "A999999"])
+    pub ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct IdValidity {
+    /// The ID as given
+    pub id: String,
+    pub exists: bool,
+    /// Why the ID could not be checked (e.g., an invalid format), if it could not
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ValidateIdsResponse {
+    pub results: Vec<IdValidity>,
+}
+
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SearchRequest {
     pub subsequence: Vec<i64>,
+    /// Offset of the first result to return, for paging through matches (defaults to 0)
+    #[serde(default)]
+    pub start: usize,
+    /// Whether term signs must match exactly ("exact") or are ignored ("absolute", the default)
+    #[serde(default)]
+    pub mode: SignMode,
+    /// Maximum number of results to return (defaults to 10; values above 100 are clamped to 100)
+    pub max_results: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct PatternSearchRequest {
+    /// Terms to match in order; `null` is a wildcard matching any single term (e.g., [1, 1, null, 3, 5])
+    pub pattern: Vec<Option<i64>>,
+    /// Offset of the first result to return, for paging through matches (defaults to 0)
+    #[serde(default)]
+    pub start: usize,
+    /// Whether term signs must match exactly ("exact") or are ignored ("absolute", the default)
+    #[serde(default)]
+    pub mode: SignMode,
+    /// Maximum number of results to return (defaults to 10; values above 100 are clamped to 100)
+    pub max_results: Option<usize>,
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct SearchResponse {
+    /// Total number of matches in OEIS, which may exceed the number of returned results
+    pub count: usize,
+    /// Offset of the first returned result
+    pub start: usize,
+    /// Whether more results are available after this window
+    pub has_more: bool,
     pub results: Vec<OEISSequence>,
 }
 
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ResolvedXref {
+    pub id: String,
+    /// `None` when the cross-referenced sequence could not be fetched
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ResolveXrefsResponse {
+    pub id: String,
+    /// Number of distinct cross-referenced IDs, before capping
+    pub total: usize,
+    pub xrefs: Vec<ResolvedXref>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SequenceKeywordsResponse {
+    pub id: String,
+    #[serde(flatten)]
+    pub keywords: SequenceKeywords,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct BackReference {
+    pub id: String,
+    pub name: String,
+    /// Whether the queried sequence cross-references this one in turn
+    pub mutual: bool,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ReferencingSequencesResponse {
+    pub id: String,
+    /// Number of OEIS entries mentioning the ID, including the sequence itself, which may
+    /// exceed the first page of results
+    pub count: usize,
+    /// Other sequences mentioning the ID, from the first page of OEIS results
+    pub referenced_by: Vec<BackReference>,
+    /// A-numbers the sequence cross-references itself, from its `xref`
+    pub references: Vec<String>,
+}
+
 #[derive(Debug, Deserialize, JsonSchema)]
-pub struct SequenceAnalysisRequest {
-    /// The OEIS sequence ID to analyze (e.g., "A000045")
-    pub sequence_id: String,
+pub struct NeighborsRequest {
+    /// The OEIS sequence ID (e.g., "A000045")
+    pub id: String,
+    /// How many A-numbers to look at on each side (at most 10)
+    pub radius: usize,
 }
 
-#[tool_router]
-impl<C: OEISClient + Clone + 'static> OEIS<C> {
-    #[tool(description = "Get a URL of OEIS entry.")]
-    async fn get_url(&self, _: Parameters<EmptyRequest>) -> Result<CallToolResult, McpError> {
-        let url = "https://oeis.org";
-        Ok(CallToolResult::success(vec![ContentBlock::text(url)]))
-    }
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct Neighbor {
+    pub id: String,
+    pub name: String,
+}
 
-    #[tool(description = "Find a sequence by its ID.")]
-    async fn find_by_id(
-        &self,
-        Parameters(FindRequest { id }): Parameters<FindRequest>,
-    ) -> Result<CallToolResult, McpError> {
-        info!("Find sequence by ID: {:?}", id);
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct NeighborsResponse {
+    pub id: String,
+    /// Existing sequences within the radius, in A-number order; missing ones are skipped
+    pub neighbors: Vec<Neighbor>,
+}
 
-        let result = self.find_sequence(&id).await?;
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct KeywordSearchRequest {
+    /// OEIS keyword to filter by (e.g., "core", "nice")
+    pub keyword: String,
+    /// Maximum number of results to return (defaults to 10; values above 100 are clamped to 100)
+    pub limit: Option<usize>,
+}
 
-        Ok(CallToolResult::structured(json!(FindResponse { result })))
-    }
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RandomSequenceRequest {
+    /// OEIS keyword the sequence must be tagged with (defaults to "nice")
+    pub keyword: Option<String>,
+}
 
-    #[tool(description = "Search sequences by subsequence.")]
-    async fn search_by_subsequence(
-        &self,
-        Parameters(SearchRequest { subsequence }): Parameters<SearchRequest>,
-    ) -> Result<CallToolResult, McpError> {
-        info!("Search sequences by subsequence: {:?}", subsequence);
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct TextSearchRequest {
+    /// Free-text query matched against sequence names, comments, etc. (e.g., "number of partitions")
+    pub query: String,
+}
 
-        let results = self.search_sequences(&subsequence).await?;
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SuggestSequencesRequest {
+    /// Natural-language description of the sequence (e.g., "ways to tile a 2 x n strip with dominoes")
+    pub description: String,
+}
 
-        Ok(CallToolResult::structured(json!(SearchResponse {
-            results
-        })))
-    }
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SequenceSuggestion {
+    pub id: String,
+    pub name: String,
+    /// Sum of the weights of `keywords`: core +3, nice +2, easy +1, less / obsc -1, dumb -2, dead -3
+    pub score: i32,
+    /// The sequence's keywords that contributed to `score`
+    pub keywords: Vec<String>,
 }
 
-#[prompt_router]
-impl<C: OEISClient + Clone + 'static> OEIS<C> {
-    /// Provides a comprehensive analysis of an OEIS sequence
-    #[prompt(
-        description = "Analyzes an OEIS sequence in detail, providing mathematical context, patterns, and related sequences"
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SuggestSequencesResponse {
+    /// Total number of text matches in OEIS, which may exceed the number of suggestions
+    pub count: usize,
+    /// Highest score first; ties keep OEIS order
+    pub suggestions: Vec<SequenceSuggestion>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RankedSearchRequest {
+    pub subsequence: Vec<i64>,
+    /// Maximum number of matches to fetch and rank (defaults to 10; values above 100 are clamped to 100)
+    pub max_results: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SearchByTermAtRequest {
+    /// Index n of the term, honoring each sequence's offset
+    pub n: i64,
+    /// Required value of a(n)
+    pub value: i64,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SearchByTermAtResponse {
+    /// Number of sequences containing `value` anywhere that were checked at index n
+    pub scanned: usize,
+    /// Total number of sequences containing `value` anywhere, which may exceed `scanned`
+    pub count: usize,
+    /// The scanned sequences whose term a(n) equals `value`
+    pub results: Vec<OEISSequence>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct RankedResult {
+    /// Fraction of the supplied terms that open the sequence's data, in order (1.0 = exact prefix)
+    pub score: f64,
+    pub sequence: OEISSequence,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct RankedSearchResponse {
+    /// Total number of matches in OEIS, which may exceed the number of ranked results
+    pub count: usize,
+    /// Best structural match first; ties keep OEIS order
+    pub results: Vec<RankedResult>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GeneratingFunctionSearchRequest {
+    /// Generating function as written in OEIS formulas (e.g., "x/(1-x-x^2)")
+    pub expression: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct IsKnownSequenceRequest {
+    pub subsequence: Vec<i64>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct IsKnownSequenceResponse {
+    pub found: bool,
+    pub count: usize,
+    /// ID of the first match; `None` when nothing matched or the query is too broad to list
+    pub first_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetTermsRequest {
+    /// The OEIS sequence ID (e.g., "A000045")
+    pub id: String,
+    /// Number of leading terms to return
+    pub count: usize,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetTermsResponse {
+    pub id: String,
+    pub terms: Vec<i64>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetBFileRequest {
+    /// The OEIS sequence ID (e.g., "A000045")
+    pub id: String,
+    /// Number of b-file entries to skip, counted from the first listed entry rather than by index n (defaults to 0)
+    #[serde(default)]
+    pub start: usize,
+    /// Number of entries to return (at most 1000)
+    pub count: usize,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetBFileResponse {
+    pub id: String,
+    /// Number of entries in the whole b-file
+    pub total: usize,
+    /// Offset of the first returned entry
+    pub start: usize,
+    /// Whether more entries are available after this page
+    pub has_more: bool,
+    pub entries: Vec<BFileEntry>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SequencePlotDataRequest {
+    /// The OEIS sequence ID (e.g., "A000045")
+    pub id: String,
+    /// Maximum number of points to return (defaults to all available terms)
+    pub count: Option<usize>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PlotPoint {
+    /// Index of the term, honoring the sequence's offset
+    pub n: i64,
+    /// The term a(n); a string since terms can exceed `i64`
+    pub value: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SequencePlotDataResponse {
+    pub id: String,
+    pub points: Vec<PlotPoint>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TermRatio {
+    /// Index of the denominator term, honoring the sequence's offset
+    pub n: i64,
+    /// a(n+1) / a(n)
+    pub ratio: f64,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ConsecutiveRatiosResponse {
+    pub id: String,
+    pub ratios: Vec<TermRatio>,
+    /// Indices n where a(n) = 0, so a(n+1) / a(n) is undefined and left out of `ratios`
+    pub skipped: Vec<i64>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct TermsModRequest {
+    /// The OEIS sequence ID (e.g., "A000045")
+    pub id: String,
+    /// The modulus m (must be positive)
+    pub modulus: i64,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TermsModResponse {
+    pub id: String,
+    pub modulus: i64,
+    /// a(n) mod m for every listed term, each in 0..m even for negative terms
+    pub residues: Vec<i64>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SequenceAgeContextResponse {
+    pub id: String,
+    /// Creation timestamp as recorded by OEIS (e.g., "1991-04-30T03:00:00-04:00")
+    pub created: Option<String>,
+    /// Highest A-number among the most recently created sequences
+    pub latest_id: String,
+    /// Where the A-number falls between A000001 (0.0) and `latest_id` (1.0)
+    pub position: f64,
+    /// Sequences numbered after this one, roughly how many were added since
+    pub newer_sequences: i64,
+    /// Human-readable summary of how old the sequence is
+    pub context: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ServerStatusResponse {
+    pub name: String,
+    pub version: String,
+    /// Seconds since the server process started
+    pub uptime_secs: u64,
+    /// Whether OEIS answered a lookup of the health-check sequence
+    pub oeis_reachable: bool,
+    /// Why OEIS could not be reached, when `oeis_reachable` is false
+    pub oeis_error: Option<String>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct MediaLinksResponse {
+    pub id: String,
+    /// OEIS page that plays the sequence as music ("Listen")
+    pub listen_url: String,
+    /// OEIS page with plots of the sequence ("Graph")
+    pub graph_url: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct TermAtRequest {
+    /// The OEIS sequence ID (e.g., "A000045")
+    pub id: String,
+    /// Index n of the term a(n), honoring the sequence's offset
+    pub n: i64,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TermAtResponse {
+    pub id: String,
+    pub n: i64,
+    /// The term a(n); a string since terms can exceed `i64`
+    pub value: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ContainsValueRequest {
+    /// The OEIS sequence ID (e.g., "A000045")
+    pub id: String,
+    pub value: i64,
+    /// Check every term of the sequence's b-file instead of only its `data` (defaults to false)
+    #[serde(default)]
+    pub bfile: bool,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ContainsValueResponse {
+    pub id: String,
+    pub value: i64,
+    /// Whether `value` is among the checked terms
+    pub found: bool,
+    /// Every index n with a(n) = value, honoring the sequence's offset
+    pub indices: Vec<i64>,
+    /// Number of terms checked
+    pub checked_terms: usize,
+    /// What was checked: only the known terms, never the infinite sequence
+    pub note: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetProgramsResponse {
+    pub id: String,
+    pub maple: Option<Vec<String>>,
+    pub mathematica: Option<Vec<String>>,
+    /// Programs in other languages, each prefixed with the language, e.g. "(PARI) ..."
+    pub program: Option<Vec<String>>,
+}
+
+/// Kind of a `formula` line, guessed from common OEIS prefixes by `extract_formulas`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum FormulaKind {
+    GeneratingFunction,
+    ExponentialGeneratingFunction,
+    Recurrence,
+    ClosedForm,
+    Other,
+}
+
+#[derive(Debug, Default, Serialize, JsonSchema)]
+pub struct ExtractFormulasResponse {
+    pub id: String,
+    /// Lines starting with "G.f." or "O.g.f."
+    pub generating_functions: Vec<String>,
+    /// Lines starting with "E.g.f."
+    pub exponential_generating_functions: Vec<String>,
+    /// Lines referring to earlier terms, such as a(n-1) or a(n+1)
+    pub recurrences: Vec<String>,
+    /// Lines starting with "a(n) =" that do not refer to other terms
+    pub closed_forms: Vec<String>,
+    /// Everything else (asymptotics, identities, sums, ...)
+    pub other: Vec<String>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetCommentsResponse {
+    pub id: String,
+    /// The comments exactly as OEIS stores them, one entry per line
+    pub comments: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SequenceDifferencesRequest {
+    /// The OEIS sequence ID (e.g., "A000290")
+    pub id: String,
+    /// Order of the finite difference (0 returns the terms themselves)
+    pub order: usize,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SequenceDifferencesResponse {
+    pub id: String,
+    pub order: usize,
+    /// Computed from 128-bit terms; a difference outside the 64-bit JSON range is an error
+    pub differences: Vec<i128>,
+}
+
+/// Elementwise transform applied by `transform_sequence`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Transform {
+    /// a(0), a(0)+a(1), a(0)+a(1)+a(2), ...
+    PartialSums,
+    /// a(1)-a(0), a(2)-a(1), ...
+    Differences,
+    /// a(0), a(0)*a(1), a(0)*a(1)*a(2), ...
+    RunningProducts,
+    /// -a(0), -a(1), ...
+    Negate,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct TransformSequenceRequest {
+    /// The OEIS sequence ID (e.g., "A000027")
+    pub id: String,
+    /// Transform to apply: "partial_sums", "differences", "running_products", or "negate"
+    pub op: Transform,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TransformSequenceResponse {
+    pub id: String,
+    pub op: Transform,
+    /// Computed in 128-bit arithmetic; an error is returned rather than overflowing it or
+    /// the 64-bit range of JSON numbers
+    pub values: Vec<i128>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TriangleRowsResponse {
+    pub id: String,
+    /// Index of the first row, from the sequence's offset
+    pub first_row: i64,
+    /// Rows of the triangle; row i (counting from 0) has i + 1 entries, except that the
+    /// last row may be cut short by the end of the data
+    pub rows: Vec<Vec<i64>>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TermDiff {
+    pub n: i64,
+    pub first: i64,
+    pub second: i64,
+    /// second - first, in 128-bit arithmetic so it cannot overflow
+    pub difference: i128,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SequenceDiffResponse {
+    pub first_id: String,
+    pub second_id: String,
+    /// Number of indices n present in both sequences
+    pub compared: usize,
+    /// Number of compared indices where the terms are equal
+    pub equal: usize,
+    pub diffs: Vec<TermDiff>,
+}
+
+/// How `is_subsequence_of` looks for the candidate's terms within the container's
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SubsequenceMode {
+    /// The terms appear consecutively, in order
+    #[default]
+    Contiguous,
+    /// The terms appear in order, possibly with other terms between them
+    Scattered,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct IsSubsequenceRequest {
+    /// The OEIS sequence ID whose terms are looked for (e.g., "A000045")
+    pub candidate_id: String,
+    /// The OEIS sequence ID whose terms are searched (e.g., "A000045")
+    pub container_id: String,
+    /// "contiguous" (the default) or "scattered"
+    #[serde(default)]
+    pub mode: SubsequenceMode,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct IsSubsequenceResponse {
+    pub candidate_id: String,
+    pub container_id: String,
+    pub mode: SubsequenceMode,
+    pub found: bool,
+    /// Index n of the container's term where the earliest match starts, honoring its offset
+    pub start: Option<i64>,
+    /// Number of candidate terms looked for
+    pub candidate_terms: usize,
+    /// Number of container terms searched (only the data OEIS lists, not the b-file)
+    pub container_terms: usize,
+}
+
+/// Ordering of consecutive terms reported by `sequence_stats`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Monotonicity {
+    /// Every term equals the previous one (also the case for fewer than two terms)
+    Constant,
+    /// a(n+1) >= a(n) for every n
+    NonDecreasing,
+    /// a(n+1) <= a(n) for every n
+    NonIncreasing,
+    /// Neither non-decreasing nor non-increasing
+    None,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SequenceStatsResponse {
+    pub id: String,
+    pub count: usize,
+    /// Decimal string, since terms can exceed any fixed-width integer
+    pub min: Option<String>,
+    /// Decimal string, since terms can exceed any fixed-width integer
+    pub max: Option<String>,
+    /// Decimal string, computed digit by digit so it cannot overflow
+    pub sum: String,
+    pub monotonicity: Monotonicity,
+    /// The sequence has the `sign` keyword, so `min` and `sum` reflect negative terms
+    pub signed: bool,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct DetectProgressionResponse {
+    pub id: String,
+    /// Number of terms checked
+    pub count: usize,
+    /// Every pair of consecutive terms differs by the same amount
+    pub arithmetic: bool,
+    /// a(n+1) - a(n), computed in 128-bit arithmetic, when `arithmetic`
+    pub common_difference: Option<i128>,
+    /// Every pair of consecutive nonzero terms has the same ratio
+    pub geometric: bool,
+    /// a(n+1) / a(n) when `geometric`
+    pub common_ratio: Option<f64>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct FormatTermsRequest {
+    /// The OEIS sequence ID (e.g., "A000142")
+    pub id: String,
+    /// Group digits in threes with commas (e.g., 1,234,567); plain digits when false
+    pub grouping: bool,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct FormatTermsResponse {
+    pub id: String,
+    pub terms: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct VerifyRecurrenceRequest {
+    /// The OEIS sequence ID (e.g., "A000045")
+    pub id: String,
+    /// Coefficients c0, c1, ... of `a(n) = c0*a(n-1) + c1*a(n-2) + ...`
+    pub coefficients: Vec<i64>,
+    /// Order of the recurrence; must equal the number of coefficients
+    pub order: usize,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct RecurrenceMismatch {
+    /// Index of the term, honoring the sequence's offset
+    pub n: i64,
+    /// The term a(n) from the sequence data, as a decimal string
+    pub actual: String,
+    /// The value the recurrence predicts for a(n), as a decimal string since a wrong guess
+    /// at the coefficients can predict values past the 64-bit range of JSON numbers
+    pub predicted: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct VerifyRecurrenceResponse {
+    pub id: String,
+    /// Whether the recurrence holds for every checked term
+    pub holds: bool,
+    /// Indices n where the recurrence holds
+    pub matched: Vec<i64>,
+    /// Indices n where the recurrence fails, with the actual and predicted terms
+    pub failed: Vec<RecurrenceMismatch>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SequenceAnalysisRequest {
+    /// The OEIS sequence ID to analyze (e.g., "A000045")
+    pub sequence_id: String,
+}
+
+/// Audience the `explain_sequence` prompt pitches its explanation at
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Level {
+    /// A curious child with no formal mathematics beyond arithmetic
+    Child,
+    /// A high-school student comfortable with algebra
+    HighSchool,
+    /// An undergraduate who knows proofs, induction, and basic combinatorics
+    Undergrad,
+    /// A working mathematician
+    Expert,
+}
+
+impl Level {
+    /// The audience description and explanation guidelines for this level
+    fn instructions(self) -> (&'static str, &'static str) {
+        match self {
+            Level::Child => (
+                "a curious child",
+                "Use simple words, short sentences, and everyday pictures or games. \
+                Avoid formulas and jargon; show how to get the next few terms by hand.",
+            ),
+            Level::HighSchool => (
+                "a high-school student",
+                "Use basic algebra and at most one simple formula, explaining every symbol. \
+                Work through how the first few terms are computed and mention where the sequence shows up.",
+            ),
+            Level::Undergrad => (
+                "an undergraduate mathematics student",
+                "Give the formal definition and key formulas, sketch why the main identities hold, \
+                and connect the sequence to standard topics such as recurrences, generating functions, or combinatorics.",
+            ),
+            Level::Expert => (
+                "an expert mathematician",
+                "Be concise and precise. Focus on the deeper structure: generating functions, asymptotics, \
+                open problems, and connections to related sequences and the literature.",
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ExplainSequenceRequest {
+    /// The OEIS sequence ID to explain (e.g., "A000045")
+    pub sequence_id: String,
+    /// Audience level: "child", "high_school", "undergrad", or "expert"
+    pub level: Level,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GenerateCodeRequest {
+    /// The OEIS sequence ID to reproduce (e.g., "A000045")
+    pub sequence_id: String,
+    /// Programming language of the generator (e.g., "Rust", "Python")
+    pub language: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CompareSequencesRequest {
+    /// The first OEIS sequence ID to compare (e.g., "A000045")
+    pub first_id: String,
+    /// The second OEIS sequence ID to compare (e.g., "A000032")
+    pub second_id: String,
+}
+
+#[tool_router]
+impl<C: OEISClient + Clone + 'static> OEIS<C> {
+    #[tool(description = "Get a URL of OEIS entry.")]
+    async fn get_url(&self, _: Parameters<EmptyRequest>) -> Result<CallToolResult, McpError> {
+        let url = "https://oeis.org";
+        Ok(CallToolResult::success(vec![ContentBlock::text(url)]))
+    }
+
+    #[tool(
+        description = "List the OEIS keywords with a short description of each.",
+        output_schema = output_schema::<ListKeywordsResponse>()
     )]
-    async fn sequence_analysis(
+    async fn list_keywords(&self, _: Parameters<EmptyRequest>) -> Result<CallToolResult, McpError> {
+        let keywords = OEIS_KEYWORDS
+            .iter()
+            .map(|(keyword, description)| KeywordInfo {
+                keyword: keyword.to_string(),
+                description: description.to_string(),
+            })
+            .collect();
+
+        Ok(CallToolResult::structured(json!(ListKeywordsResponse {
+            keywords
+        })))
+    }
+
+    #[tool(
+        description = "Find a sequence by its ID.",
+        output_schema = output_schema::<FindResponse>()
+    )]
+    #[instrument(skip_all, fields(request_id = %next_request_id(), id = %id))]
+    async fn find_by_id(
         &self,
-        Parameters(SequenceAnalysisRequest { sequence_id }): Parameters<SequenceAnalysisRequest>,
-    ) -> Result<Vec<PromptMessage>, McpError> {
-        info!("Analyzing sequence: {:?}", sequence_id);
-        let sequence = self.find_sequence(&sequence_id).await?;
-        Ok(vec![
-            self.build_user_message(&sequence_id),
-            self.build_assistant_messages(&sequence),
-        ])
+        Parameters(FindRequest { id }): Parameters<FindRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        info!("Find sequence by ID: {:?}", id);
+
+        let result = self.find_sequence(&id).await?;
+
+        Ok(CallToolResult::structured(json!(FindResponse { result })))
     }
 
-    fn build_user_message(&self, sequence_id: &str) -> PromptMessage {
-        PromptMessage::new_text(
-            Role::User,
-            format!(
-                "Please provide a comprehensive analysis of OEIS sequence {}. \
-                Include:\n\
-                1. The definition and meaning of this sequence\n\
-                2. Mathematical properties and patterns\n\
-                3. Real-world applications or significance\n\
-                4. Relationships to other sequences\n\
-                5. Interesting facts or observations",
-                sequence_id
-            ),
-        )
+    #[tool(
+        description = "Find a sequence by its exact name.",
+        output_schema = output_schema::<FindByNameResponse>()
+    )]
+    async fn find_by_name(
+        &self,
+        Parameters(FindByNameRequest { name }): Parameters<FindByNameRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        info!("Find sequence by name: {:?}", name);
+
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(McpError::new(
+                ErrorCode::INVALID_PARAMS,
+                "Name must not be empty".to_string(),
+                None,
+            ));
+        }
+
+        let result = self.client.find_by_name(name).await.map_err(client_error)?;
+
+        Ok(CallToolResult::structured(json!(FindByNameResponse {
+            result
+        })))
+    }
+
+    #[tool(
+        description = "Resolve the A-number of a sequence from a common nickname like \"Catalan numbers\", falling back to an OEIS name search.",
+        output_schema = output_schema::<NicknameLookupResponse>()
+    )]
+    async fn lookup_by_nickname(
+        &self,
+        Parameters(NicknameRequest { nickname }): Parameters<NicknameRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        info!("Look up sequence by nickname: {:?}", nickname);
+
+        let normalized = normalize_nickname(&nickname);
+        if normalized.is_empty() {
+            return Err(McpError::new(
+                ErrorCode::INVALID_PARAMS,
+                "Nickname must not be empty".to_string(),
+                None,
+            ));
+        }
+
+        let table_id = SEQUENCE_NICKNAMES
+            .iter()
+            .find(|(known, _)| *known == normalized)
+            .map(|(_, id)| *id);
+        let (sequence, matched_by) = match table_id {
+            Some(id) => (self.find_sequence(id).await?, NicknameMatch::Table),
+            None => {
+                // OEIS has no escape for quotes inside a phrase, so drop them
+                let query = format!("name:\"{}\"", nickname.trim().replace('"', " "));
+                let result = self.search_free_text(&query).await?;
+                let sequence = result.sequences.into_iter().next().ok_or_else(|| {
+                    McpError::new(
+                        ErrorCode::INVALID_PARAMS,
+                        format!("No sequence found (by nickname: {})", nickname.trim()),
+                        None,
+                    )
+                })?;
+                (sequence, NicknameMatch::NameSearch)
+            }
+        };
+
+        Ok(CallToolResult::structured(json!(NicknameLookupResponse {
+            id: sequence.id(),
+            name: sequence.name,
+            matched_by,
+        })))
+    }
+
+    #[tool(
+        description = "Find several sequences by ID at once.",
+        output_schema = output_schema::<FindManyResponse>()
+    )]
+    async fn find_many(
+        &self,
+        Parameters(FindManyRequest { ids, concurrency }): Parameters<FindManyRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        info!("Find {} sequences by ID", ids.len());
+
+        let max = self.limits.max_find_many_ids;
+        if ids.len() > max {
+            return Err(McpError::new(
+                ErrorCode::INVALID_PARAMS,
+                format!("Too many IDs: {} (max: {})", ids.len(), max),
+                Some(json!({"count": ids.len(), "max": max})),
+            ));
+        }
+        let concurrency = concurrency.unwrap_or(DEFAULT_FIND_MANY_CONCURRENCY).max(1);
+
+        let mut entries: Vec<(usize, FindManyEntry)> = stream::iter(ids.into_iter().enumerate())
+            .map(|(index, id)| async move { (index, self.find_entry(id).await) })
+            .buffer_unordered(concurrency)
+            .collect()
+            .await;
+        // Completion order is arbitrary, so restore the requested order
+        entries.sort_by_key(|(index, _)| *index);
+
+        Ok(CallToolResult::structured(json!(FindManyResponse {
+            results: entries.into_iter().map(|(_, entry)| entry).collect(),
+        })))
+    }
+
+    #[tool(
+        description = "Check whether each of several sequence IDs exists in OEIS.",
+        output_schema = output_schema::<ValidateIdsResponse>()
+    )]
+    async fn validate_ids(
+        &self,
+        Parameters(ValidateIdsRequest { ids }): Parameters<ValidateIdsRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        info!("Validate {} sequence IDs", ids.len());
+
+        let max = self.limits.max_find_many_ids;
+        if ids.len() > max {
+            return Err(McpError::new(
+                ErrorCode::INVALID_PARAMS,
+                format!("Too many IDs: {} (max: {})", ids.len(), max),
+                Some(json!({"count": ids.len(), "max": max})),
+            ));
+        }
+
+        let results = stream::iter(ids)
+            .map(|id| self.validate_id(id))
+            .buffered(VALIDATE_IDS_CONCURRENCY)
+            .collect()
+            .await;
+
+        Ok(CallToolResult::structured(json!(ValidateIdsResponse {
+            results
+        })))
+    }
+
+    #[tool(
+        description = "Search sequences by subsequence.",
+        output_schema = output_schema::<SearchResponse>()
+    )]
+    #[instrument(skip_all, fields(request_id = %next_request_id(), subsequence = ?subsequence))]
+    async fn search_by_subsequence(
+        &self,
+        Parameters(SearchRequest {
+            subsequence,
+            start,
+            mode,
+            max_results,
+        }): Parameters<SearchRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        info!(
+            "Search sequences by subsequence: {:?} (start: {}, mode: {:?}, max_results: {:?})",
+            subsequence, start, mode, max_results
+        );
+
+        let max_results = max_results.unwrap_or(DEFAULT_MAX_RESULTS);
+        let result = self
+            .search_sequences(&subsequence, start, mode, max_results)
+            .await?;
+
+        Ok(CallToolResult::structured(json!(SearchResponse {
+            count: result.count,
+            start: result.start,
+            has_more: result.has_more(),
+            results: result.sequences,
+        })))
+    }
+
+    #[tool(
+        description = "Search sequences by a subsequence pattern where null terms are wildcards, for when some terms are unknown.",
+        output_schema = output_schema::<SearchResponse>()
+    )]
+    async fn search_by_pattern(
+        &self,
+        Parameters(PatternSearchRequest {
+            pattern,
+            start,
+            mode,
+            max_results,
+        }): Parameters<PatternSearchRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        info!(
+            "Search sequences by pattern: {:?} (start: {}, mode: {:?}, max_results: {:?})",
+            pattern, start, mode, max_results
+        );
+
+        let result = if pattern.is_empty() {
+            SearchResult {
+                start,
+                ..Default::default()
+            }
+        } else {
+            if pattern.iter().all(Option::is_none) {
+                return Err(McpError::new(
+                    ErrorCode::INVALID_PARAMS,
+                    "Pattern must contain at least one known term",
+                    None,
+                ));
+            }
+            self.check_subsequence_length(pattern.len())?;
+
+            let max_results = max_results
+                .unwrap_or(DEFAULT_MAX_RESULTS)
+                .clamp(1, MAX_SEARCH_RESULTS);
+            self.client
+                .search_by_pattern(&pattern, start, mode, max_results)
+                .await
+                .map_err(client_error)?
+        };
+
+        Ok(CallToolResult::structured(json!(SearchResponse {
+            count: result.count,
+            start: result.start,
+            has_more: result.has_more(),
+            results: result.sequences,
+        })))
+    }
+
+    #[tool(
+        description = "Search sequences by subsequence, ranking those that start with the given terms first.",
+        output_schema = output_schema::<RankedSearchResponse>()
+    )]
+    async fn search_ranked(
+        &self,
+        Parameters(RankedSearchRequest {
+            subsequence,
+            max_results,
+        }): Parameters<RankedSearchRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        info!("Ranked search by subsequence: {:?}", subsequence);
+
+        let result = self
+            .search_sequences(
+                &subsequence,
+                0,
+                SignMode::Absolute,
+                max_results.unwrap_or(DEFAULT_MAX_RESULTS),
+            )
+            .await?;
+
+        let mut results: Vec<RankedResult> = result
+            .sequences
+            .into_iter()
+            .map(|sequence| RankedResult {
+                score: prefix_score(&subsequence, &sequence.data),
+                sequence,
+            })
+            .collect();
+        // `sort_by` is stable, so equally scored results stay in OEIS order
+        results.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+        Ok(CallToolResult::structured(json!(RankedSearchResponse {
+            count: result.count,
+            results,
+        })))
+    }
+
+    #[tool(
+        description = "Find sequences whose term a(n) equals a value, honoring each sequence's offset.",
+        output_schema = output_schema::<SearchByTermAtResponse>()
+    )]
+    async fn search_by_term_at(
+        &self,
+        Parameters(SearchByTermAtRequest { n, value }): Parameters<SearchByTermAtRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        info!("Search sequences with a({}) = {}", n, value);
+
+        // OEIS cannot anchor a term to a position, so fetch the sequences containing the
+        // value anywhere (signs included) and keep those where it sits at index n
+        let result = self
+            .search_sequences(&[value], 0, SignMode::Exact, MAX_SEARCH_RESULTS)
+            .await?;
+        let scanned = result.sequences.len();
+        let expected = value.to_string();
+        let results = result
+            .sequences
+            .into_iter()
+            .filter(|sequence| term_at_index(sequence, n) == Some(expected.as_str()))
+            .collect();
+
+        Ok(CallToolResult::structured(json!(SearchByTermAtResponse {
+            scanned,
+            count: result.count,
+            results,
+        })))
+    }
+
+    #[tool(
+        description = "Resolve a sequence's cross-references to sequence names.",
+        output_schema = output_schema::<ResolveXrefsResponse>()
+    )]
+    async fn resolve_xrefs(
+        &self,
+        Parameters(FindRequest { id }): Parameters<FindRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        info!("Resolve cross-references of sequence: {:?}", id);
+
+        let sequence = self.find_sequence(&id).await?;
+        let xref_ids: Vec<String> = sequence
+            .xref_ids()
+            .into_iter()
+            .filter(|xref_id| *xref_id != sequence.id())
+            .collect();
+        let total = xref_ids.len();
+
+        let xrefs = stream::iter(xref_ids.into_iter().take(MAX_RESOLVED_XREFS))
+            .map(|xref_id| async move {
+                let name = match self.client.find_by_id(&xref_id).await {
+                    Ok(found) => found.map(|s| s.name),
+                    Err(e) => {
+                        warn!("Failed to resolve cross-reference {}: {}", xref_id, e);
+                        None
+                    }
+                };
+                ResolvedXref { id: xref_id, name }
+            })
+            .buffered(XREF_CONCURRENCY)
+            .collect()
+            .await;
+
+        Ok(CallToolResult::structured(json!(ResolveXrefsResponse {
+            id: sequence.id(),
+            total,
+            xrefs,
+        })))
+    }
+
+    #[tool(
+        description = "Get the sequences with adjacent A-numbers (e.g., A000044 and A000046 for A000045).",
+        output_schema = output_schema::<NeighborsResponse>()
+    )]
+    async fn neighbors(
+        &self,
+        Parameters(NeighborsRequest { id, radius }): Parameters<NeighborsRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        info!("Get neighbors of sequence: {:?} (radius: {})", id, radius);
+
+        if radius > MAX_NEIGHBOR_RADIUS {
+            return Err(McpError::new(
+                ErrorCode::INVALID_PARAMS,
+                format!("radius too large (max: {})", MAX_NEIGHBOR_RADIUS),
+                Some(json!({"radius": radius, "max": MAX_NEIGHBOR_RADIUS})),
+            ));
+        }
+
+        let id = normalize_id(&id)?;
+        let number: u64 = id[1..].parse().map_err(|_| invalid_id_error(&id))?;
+        let neighbors = self.existing_neighbors(number, radius as u64).await;
+
+        Ok(CallToolResult::structured(json!(NeighborsResponse {
+            id,
+            neighbors,
+        })))
+    }
+
+    #[tool(
+        description = "Get the OEIS keywords of a sequence as named booleans.",
+        output_schema = output_schema::<SequenceKeywordsResponse>()
+    )]
+    async fn sequence_keywords(
+        &self,
+        Parameters(FindRequest { id }): Parameters<FindRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        info!("Get keywords of sequence: {:?}", id);
+
+        let sequence = self.find_sequence(&id).await?;
+
+        Ok(CallToolResult::structured(json!(
+            SequenceKeywordsResponse {
+                id: sequence.id(),
+                keywords: sequence.keywords(),
+            }
+        )))
+    }
+
+    #[tool(
+        description = "Search sequences by OEIS keyword.",
+        output_schema = output_schema::<SearchResponse>()
+    )]
+    async fn search_by_keyword(
+        &self,
+        Parameters(KeywordSearchRequest { keyword, limit }): Parameters<KeywordSearchRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        info!(
+            "Search sequences by keyword: {:?} (limit: {:?})",
+            keyword, limit
+        );
+
+        let result = self
+            .search_keyword(&keyword, 0, limit.unwrap_or(DEFAULT_MAX_RESULTS))
+            .await?;
+
+        Ok(CallToolResult::structured(json!(SearchResponse {
+            count: result.count,
+            start: result.start,
+            has_more: result.has_more(),
+            results: result.sequences,
+        })))
+    }
+
+    #[tool(
+        description = "Get a random sequence tagged with an OEIS keyword (\"nice\" by default).",
+        output_schema = output_schema::<FindResponse>()
+    )]
+    async fn random_sequence(
+        &self,
+        Parameters(RandomSequenceRequest { keyword }): Parameters<RandomSequenceRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let keyword = keyword.unwrap_or_else(|| DEFAULT_RANDOM_KEYWORD.to_string());
+        info!("Get random sequence with keyword: {:?}", keyword);
+
+        // The first page tells us how many sequences there are to pick from
+        let first_page = self
+            .search_keyword(&keyword, 0, DEFAULT_MAX_RESULTS)
+            .await?;
+        if first_page.count == 0 {
+            return Err(McpError::new(
+                ErrorCode::INVALID_PARAMS,
+                format!("No sequences found (by keyword: {})", keyword),
+                Some(json!({"keyword": keyword})),
+            ));
+        }
+
+        let seed = self
+            .rng_seed
+            .unwrap_or_else(|| RandomState::new().build_hasher().finish());
+        let index = random_index(first_page.count, seed);
+        let sequence = if index < first_page.sequences.len() {
+            first_page.sequences.into_iter().nth(index)
+        } else {
+            self.search_keyword(&keyword, index, 1)
+                .await?
+                .sequences
+                .into_iter()
+                .next()
+        };
+
+        let result = sequence.ok_or_else(|| {
+            McpError::new(
+                ErrorCode::INTERNAL_ERROR,
+                format!("OEIS returned no sequence at index {}", index),
+                Some(json!({"keyword": keyword, "index": index})),
+            )
+        })?;
+
+        Ok(CallToolResult::structured(json!(FindResponse { result })))
+    }
+
+    #[tool(
+        description = "Search sequences by free text.",
+        output_schema = output_schema::<SearchResponse>()
+    )]
+    async fn search_text(
+        &self,
+        Parameters(TextSearchRequest { query }): Parameters<TextSearchRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        info!("Search sequences by text: {:?}", query);
+
+        let result = self.search_free_text(&query).await?;
+
+        Ok(CallToolResult::structured(json!(SearchResponse {
+            count: result.count,
+            start: result.start,
+            has_more: result.has_more(),
+            results: result.sequences,
+        })))
+    }
+
+    #[tool(
+        description = "List the sequences that mention a sequence (back-references), alongside the sequences it cross-references itself.",
+        output_schema = output_schema::<ReferencingSequencesResponse>()
+    )]
+    async fn referencing_sequences(
+        &self,
+        Parameters(FindRequest { id }): Parameters<FindRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        info!("Find sequences referencing: {:?}", id);
+
+        let id = normalize_id(&id)?;
+        // A bare A-number as the query matches every entry mentioning it, in any field
+        let (sequence, result) =
+            futures::try_join!(self.find_sequence(&id), self.search_free_text(&id))?;
+        let references = sequence.xref_ids();
+
+        let referenced_by = result
+            .sequences
+            .iter()
+            .map(|referencing| (referencing.id(), referencing))
+            .filter(|(referencing_id, _)| *referencing_id != id)
+            .map(|(referencing_id, referencing)| BackReference {
+                mutual: references.contains(&referencing_id),
+                id: referencing_id,
+                name: referencing.name.clone(),
+            })
+            .collect();
+
+        Ok(CallToolResult::structured(json!(
+            ReferencingSequencesResponse {
+                id,
+                count: result.count,
+                referenced_by,
+                references,
+            }
+        )))
+    }
+
+    #[tool(
+        description = "Suggest sequences matching a natural-language description, ranking well-known (core, nice) sequences first.",
+        output_schema = output_schema::<SuggestSequencesResponse>()
+    )]
+    async fn suggest_sequences(
+        &self,
+        Parameters(SuggestSequencesRequest { description }): Parameters<SuggestSequencesRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        info!("Suggest sequences for description: {:?}", description);
+
+        let result = self.search_free_text(&description).await?;
+
+        let mut suggestions: Vec<SequenceSuggestion> = result
+            .sequences
+            .iter()
+            .map(|sequence| {
+                let keywords: Vec<&str> = sequence.keyword.split(',').map(str::trim).collect();
+                let matched: Vec<(&str, i32)> = QUALITY_KEYWORDS
+                    .iter()
+                    .copied()
+                    .filter(|(keyword, _)| keywords.contains(keyword))
+                    .collect();
+                SequenceSuggestion {
+                    id: sequence.id(),
+                    name: sequence.name.clone(),
+                    score: matched.iter().map(|(_, weight)| weight).sum(),
+                    keywords: matched
+                        .iter()
+                        .map(|(keyword, _)| keyword.to_string())
+                        .collect(),
+                }
+            })
+            .collect();
+        // `sort_by_key` is stable, so equally scored suggestions stay in OEIS order
+        suggestions.sort_by_key(|suggestion| std::cmp::Reverse(suggestion.score));
+
+        Ok(CallToolResult::structured(json!(
+            SuggestSequencesResponse {
+                count: result.count,
+                suggestions,
+            }
+        )))
+    }
+
+    #[tool(
+        description = "Search sequences whose formulas mention a generating function expression.",
+        output_schema = output_schema::<SearchResponse>()
+    )]
+    async fn search_generating_function(
+        &self,
+        Parameters(GeneratingFunctionSearchRequest { expression }): Parameters<
+            GeneratingFunctionSearchRequest,
+        >,
+    ) -> Result<CallToolResult, McpError> {
+        info!("Search sequences by generating function: {:?}", expression);
+
+        let expression = expression.trim();
+        if expression.is_empty() {
+            return Err(McpError::new(
+                ErrorCode::INVALID_PARAMS,
+                "Generating function must not be empty".to_string(),
+                None,
+            ));
+        }
+        // OEIS has no escape for quotes inside a phrase, so drop them to keep the phrase intact
+        let query = format!("formula:\"{}\"", expression.replace('"', " "));
+        let result = self.search_free_text(&query).await?;
+
+        Ok(CallToolResult::structured(json!(SearchResponse {
+            count: result.count,
+            start: result.start,
+            has_more: result.has_more(),
+            results: result.sequences,
+        })))
+    }
+
+    #[tool(
+        description = "Check whether a subsequence matches any sequence in OEIS.",
+        output_schema = output_schema::<IsKnownSequenceResponse>()
+    )]
+    async fn is_known_sequence(
+        &self,
+        Parameters(IsKnownSequenceRequest { subsequence }): Parameters<IsKnownSequenceRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        info!("Check whether subsequence is known: {:?}", subsequence);
+
+        let MatchCount { count, first_id } = self.count_matches(&subsequence).await?;
+
+        Ok(CallToolResult::structured(json!(IsKnownSequenceResponse {
+            found: count > 0,
+            count,
+            first_id,
+        })))
+    }
+
+    #[tool(
+        description = "Get the first N terms of a sequence.",
+        output_schema = output_schema::<GetTermsResponse>()
+    )]
+    async fn get_terms(
+        &self,
+        Parameters(GetTermsRequest { id, count }): Parameters<GetTermsRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        info!("Get {} terms of sequence: {:?}", count, id);
+
+        if count == 0 {
+            return Err(McpError::new(
+                ErrorCode::INVALID_PARAMS,
+                "count must be greater than 0".to_string(),
+                Some(json!({"count": count})),
+            ));
+        }
+
+        let sequence = self.find_sequence(&id).await?;
+        // Terms past the requested prefix are not parsed, so they may exceed `i64`
+        let terms = parse_first_terms(&sequence, count)?;
+
+        Ok(CallToolResult::structured(json!(GetTermsResponse {
+            id: sequence.id(),
+            terms
+        })))
+    }
+
+    #[tool(
+        description = "Page through the b-file (all known terms) of a sequence as index/value entries.",
+        output_schema = output_schema::<GetBFileResponse>()
+    )]
+    async fn get_bfile(
+        &self,
+        Parameters(GetBFileRequest { id, start, count }): Parameters<GetBFileRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        info!(
+            "Get {} b-file entries of sequence {:?} from {}",
+            count, id, start
+        );
+
+        if count == 0 {
+            return Err(McpError::new(
+                ErrorCode::INVALID_PARAMS,
+                "count must be greater than 0".to_string(),
+                Some(json!({"count": count})),
+            ));
+        }
+        let count = count.min(MAX_BFILE_PAGE_SIZE);
+
+        let id = normalize_id(&id)?;
+        let bfile = self.find_bfile(&id).await?;
+        let total = bfile.len();
+        // A `start` past the end yields an empty page rather than an error
+        let entries: Vec<BFileEntry> = bfile.into_iter().skip(start).take(count).collect();
+
+        Ok(CallToolResult::structured(json!(GetBFileResponse {
+            id,
+            total,
+            start,
+            has_more: start.saturating_add(entries.len()) < total,
+            entries,
+        })))
+    }
+
+    #[tool(
+        description = "Render a sequence as an OEIS-style text entry (%S, %N, %C, %F, %Y, %K lines)."
+    )]
+    async fn format_entry(
+        &self,
+        Parameters(FindRequest { id }): Parameters<FindRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        info!("Format entry of sequence: {:?}", id);
+
+        let sequence = self.find_sequence(&id).await?;
+
+        Ok(CallToolResult::success(vec![ContentBlock::text(
+            format_oeis_entry(&sequence),
+        )]))
+    }
+
+    #[tool(
+        description = "Get (n, a(n)) pairs of a sequence, ready for plotting.",
+        output_schema = output_schema::<SequencePlotDataResponse>()
+    )]
+    async fn sequence_plot_data(
+        &self,
+        Parameters(SequencePlotDataRequest { id, count }): Parameters<SequencePlotDataRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        info!("Get plot data of sequence: {:?}", id);
+
+        let sequence = self.find_sequence(&id).await?;
+        let points = sequence
+            .data
+            .split(',')
+            .map(str::trim)
+            .filter(|term| !term.is_empty())
+            .take(count.unwrap_or(usize::MAX))
+            .zip(sequence.first_index()..)
+            .map(|(value, n)| PlotPoint {
+                n,
+                value: value.to_string(),
+            })
+            .collect();
+
+        Ok(CallToolResult::structured(json!(
+            SequencePlotDataResponse {
+                id: sequence.id(),
+                points,
+            }
+        )))
+    }
+
+    #[tool(description = "Export a sequence as CSV with index,value columns, honoring its offset.")]
+    async fn export_csv(
+        &self,
+        Parameters(FindRequest { id }): Parameters<FindRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        info!("Export sequence as CSV: {:?}", id);
+
+        let sequence = self.find_sequence(&id).await?;
+        let mut csv = String::from("index,value\n");
+        for (value, n) in sequence
+            .data
+            .split(',')
+            .map(str::trim)
+            .filter(|term| !term.is_empty())
+            .zip(sequence.first_index()..)
+        {
+            csv.push_str(&format!("{},{}\n", n, value));
+        }
+
+        Ok(CallToolResult::success(vec![ContentBlock::text(csv)]))
+    }
+
+    #[tool(
+        description = "Get a sequence's terms as strings, optionally with thousands separators (e.g., 1,234,567) for readability.",
+        output_schema = output_schema::<FormatTermsResponse>()
+    )]
+    async fn format_terms(
+        &self,
+        Parameters(FormatTermsRequest { id, grouping }): Parameters<FormatTermsRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        info!(
+            "Format terms of sequence: {:?} (grouping: {})",
+            id, grouping
+        );
+
+        let sequence = self.find_sequence(&id).await?;
+        // Grouped as digit strings, so terms of any size (e.g., 21!) need no integer parsing
+        let terms = sequence
+            .data
+            .split(',')
+            .map(str::trim)
+            .filter(|term| !term.is_empty())
+            .map(|term| {
+                if grouping {
+                    group_thousands(term)
+                } else {
+                    term.to_string()
+                }
+            })
+            .collect();
+
+        Ok(CallToolResult::structured(json!(FormatTermsResponse {
+            id: sequence.id(),
+            terms,
+        })))
+    }
+
+    #[tool(
+        description = "Get resource links for a sequence: its b-file, its OEIS web page, and its cross-referenced sequences."
+    )]
+    async fn sequence_links(
+        &self,
+        Parameters(FindRequest { id }): Parameters<FindRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        info!("Get resource links of sequence: {:?}", id);
+
+        let sequence = self.find_sequence(&id).await?;
+        let id = sequence.id();
+
+        let mut links = vec![
+            ContentBlock::resource_link(
+                RawResource::new(format!("oeis://bfile/{}", id), format!("{} b-file", id))
+                    .with_description(format!("All known terms of {}", id))
+                    .with_mime_type("application/json"),
+            ),
+            ContentBlock::resource_link(
+                RawResource::new(
+                    format!("https://oeis.org/{}", id),
+                    format!("{} on OEIS", id),
+                )
+                .with_description(format!("OEIS web page of {}", id))
+                .with_mime_type("text/html"),
+            ),
+            ContentBlock::resource_link(
+                RawResource::new(listen_url(&id), format!("Listen to {}", id))
+                    .with_description(format!("OEIS page playing {} as music", id))
+                    .with_mime_type("text/html"),
+            ),
+        ];
+        links.extend(sequence.xref_ids().into_iter().map(|xref_id| {
+            ContentBlock::resource_link(
+                RawResource::new(format!("oeis://sequence/{}", xref_id), xref_id)
+                    .with_description(format!("Cross-referenced from {}", id))
+                    .with_mime_type("application/json"),
+            )
+        }));
+
+        Ok(CallToolResult::success(links))
+    }
+
+    #[tool(
+        description = "Get the URLs of a sequence's OEIS listen (play as music) and graph pages.",
+        output_schema = output_schema::<MediaLinksResponse>()
+    )]
+    async fn media_links(
+        &self,
+        Parameters(FindRequest { id }): Parameters<FindRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        info!("Get media links of sequence: {:?}", id);
+
+        // Both pages are derived from the ID alone, so OEIS is not queried
+        let id = normalize_id(&id)?;
+
+        Ok(CallToolResult::structured(json!(MediaLinksResponse {
+            listen_url: listen_url(&id),
+            graph_url: format!("https://oeis.org/{}/graph", id),
+            id,
+        })))
+    }
+
+    #[tool(
+        description = "Get the term a(n) of a sequence, honoring its offset.",
+        output_schema = output_schema::<TermAtResponse>()
+    )]
+    async fn term_at(
+        &self,
+        Parameters(TermAtRequest { id, n }): Parameters<TermAtRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        info!("Get term a({}) of sequence: {:?}", n, id);
+
+        let sequence = self.find_sequence(&id).await?;
+        let terms: Vec<&str> = sequence
+            .data
+            .split(',')
+            .map(str::trim)
+            .filter(|term| !term.is_empty())
+            .collect();
+        let first = sequence.first_index();
+        let last = first + terms.len() as i64 - 1;
+
+        let value = n
+            .checked_sub(first)
+            .and_then(|index| usize::try_from(index).ok())
+            .and_then(|index| terms.get(index))
+            .ok_or_else(|| {
+                McpError::new(
+                    ErrorCode::INVALID_PARAMS,
+                    format!(
+                        "n = {} is out of range: {} has terms for n = {}..={}",
+                        n,
+                        sequence.id(),
+                        first,
+                        last
+                    ),
+                    Some(json!({"n": n, "min": first, "max": last})),
+                )
+            })?;
+
+        Ok(CallToolResult::structured(json!(TermAtResponse {
+            id: sequence.id(),
+            n,
+            value: value.to_string(),
+        })))
+    }
+
+    #[tool(
+        description = "Check whether a value appears among a sequence's known terms and at which indices. Only the terms in the sequence's data are checked, or its b-file when bfile is true.",
+        output_schema = output_schema::<ContainsValueResponse>()
+    )]
+    async fn contains_value(
+        &self,
+        Parameters(ContainsValueRequest { id, value, bfile }): Parameters<ContainsValueRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        info!(
+            "Check whether sequence {:?} contains {} (bfile: {})",
+            id, value, bfile
+        );
+
+        let sequence = self.find_sequence(&id).await?;
+        // Compare as strings, so terms beyond `i64` are skipped rather than failing the call
+        let (terms, source): (Vec<(i64, String)>, &str) = if bfile {
+            let entries = self.find_bfile(&sequence.id()).await?;
+            (
+                entries
+                    .into_iter()
+                    .map(|entry| (entry.index, entry.value))
+                    .collect(),
+                "b-file",
+            )
+        } else {
+            (
+                sequence
+                    .data
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|term| !term.is_empty())
+                    .zip(sequence.first_index()..)
+                    .map(|(term, n)| (n, term.to_string()))
+                    .collect(),
+                "data",
+            )
+        };
+
+        let target = value.to_string();
+        let indices: Vec<i64> = terms
+            .iter()
+            .filter(|(_, term)| *term == target)
+            .map(|(n, _)| *n)
+            .collect();
+        let note = match (terms.first(), terms.last()) {
+            (Some((first, _)), Some((last, _))) => format!(
+                "Only the {} terms in the {} (n = {}..={}) were checked; the sequence may continue beyond them, so a value not found here may still appear later.",
+                terms.len(),
+                source,
+                first,
+                last
+            ),
+            _ => format!("The {} has no terms to check.", source),
+        };
+
+        Ok(CallToolResult::structured(json!(ContainsValueResponse {
+            id: sequence.id(),
+            value,
+            found: !indices.is_empty(),
+            indices,
+            checked_terms: terms.len(),
+            note,
+        })))
+    }
+
+    #[tool(
+        description = "Get the verbatim OEIS JSON response for a sequence ID, for debugging or reading fields this server does not model."
+    )]
+    async fn raw_lookup(
+        &self,
+        Parameters(FindRequest { id }): Parameters<FindRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        info!("Raw lookup of sequence: {:?}", id);
+
+        let id = normalize_id(&id)?;
+        let body = self
+            .client
+            .find_raw_by_id(&id)
+            .await
+            .map_err(client_error)?;
+
+        Ok(CallToolResult::success(vec![ContentBlock::text(body)]))
+    }
+
+    #[tool(
+        description = "Get the Maple, Mathematica, and other programs that generate a sequence.",
+        output_schema = output_schema::<GetProgramsResponse>()
+    )]
+    async fn get_programs(
+        &self,
+        Parameters(FindRequest { id }): Parameters<FindRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        info!("Get programs of sequence: {:?}", id);
+
+        let sequence = self.find_sequence(&id).await?;
+
+        Ok(CallToolResult::structured(json!(GetProgramsResponse {
+            id: sequence.id(),
+            maple: sequence.maple,
+            mathematica: sequence.mathematica,
+            program: sequence.program,
+        })))
+    }
+
+    #[tool(
+        description = "Get the raw comments of a sequence, one entry per line, without any formatting.",
+        output_schema = output_schema::<GetCommentsResponse>()
+    )]
+    async fn get_comments(
+        &self,
+        Parameters(FindRequest { id }): Parameters<FindRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        info!("Get comments of sequence: {:?}", id);
+
+        let sequence = self.find_sequence(&id).await?;
+
+        Ok(CallToolResult::structured(json!(GetCommentsResponse {
+            id: sequence.id(),
+            comments: sequence.comment.unwrap_or_default(),
+        })))
+    }
+
+    #[tool(
+        description = "Get a sequence's formulas grouped into generating functions, exponential generating functions, recurrences, closed forms, and other.",
+        output_schema = output_schema::<ExtractFormulasResponse>()
+    )]
+    async fn extract_formulas(
+        &self,
+        Parameters(FindRequest { id }): Parameters<FindRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        info!("Extract formulas of sequence: {:?}", id);
+
+        let sequence = self.find_sequence(&id).await?;
+        let mut response = ExtractFormulasResponse {
+            id: sequence.id(),
+            ..Default::default()
+        };
+        for line in sequence.formula.unwrap_or_default() {
+            let group = match classify_formula(&line) {
+                FormulaKind::GeneratingFunction => &mut response.generating_functions,
+                FormulaKind::ExponentialGeneratingFunction => {
+                    &mut response.exponential_generating_functions
+                }
+                FormulaKind::Recurrence => &mut response.recurrences,
+                FormulaKind::ClosedForm => &mut response.closed_forms,
+                FormulaKind::Other => &mut response.other,
+            };
+            group.push(line);
+        }
+
+        Ok(CallToolResult::structured(json!(response)))
+    }
+
+    #[tool(
+        description = "Compute the n-th finite difference of an OEIS sequence's terms.",
+        output_schema = output_schema::<SequenceDifferencesResponse>()
+    )]
+    async fn sequence_differences(
+        &self,
+        Parameters(SequenceDifferencesRequest { id, order }): Parameters<
+            SequenceDifferencesRequest,
+        >,
+    ) -> Result<CallToolResult, McpError> {
+        info!("Compute order-{} differences of sequence: {:?}", order, id);
+
+        let sequence = self.find_sequence(&id).await?;
+        // Parsed as 128-bit, so large terms with small differences (e.g., 10^20 + n) work
+        let terms: Vec<i128> = parse_terms(&sequence)?;
+
+        if order >= terms.len() {
+            return Err(McpError::new(
+                ErrorCode::INVALID_PARAMS,
+                format!(
+                    "order must be less than the number of available terms ({})",
+                    terms.len()
+                ),
+                Some(json!({"order": order, "available_terms": terms.len()})),
+            ));
+        }
+
+        let differences = finite_differences(&terms, order).ok_or_else(|| {
+            McpError::new(
+                ErrorCode::INTERNAL_ERROR,
+                "Finite differences overflowed".to_string(),
+                Some(json!({"order": order})),
+            )
+        })?;
+        check_json_range(&differences, sequence.first_index(), "Finite differences")?;
+        let response = serde_json::to_value(SequenceDifferencesResponse {
+            id: sequence.id(),
+            order,
+            differences,
+        })
+        .map_err(|e| McpError::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+
+        Ok(CallToolResult::structured(response))
+    }
+
+    #[tool(
+        description = "Apply partial sums, differences, running products, or negation to a sequence's terms.",
+        output_schema = output_schema::<TransformSequenceResponse>()
+    )]
+    async fn transform_sequence(
+        &self,
+        Parameters(TransformSequenceRequest { id, op }): Parameters<TransformSequenceRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        info!("Apply {:?} to sequence: {:?}", op, id);
+
+        let sequence = self.find_sequence(&id).await?;
+        let terms: Vec<i128> = parse_terms(&sequence)?;
+
+        let values = transform_terms(&terms, op).ok_or_else(|| {
+            McpError::new(
+                ErrorCode::INTERNAL_ERROR,
+                format!("{:?} overflowed 128-bit integers", op),
+                Some(json!({"op": op})),
+            )
+        })?;
+        check_json_range(&values, sequence.first_index(), &format!("{:?}", op))?;
+        let response = serde_json::to_value(TransformSequenceResponse {
+            id: sequence.id(),
+            op,
+            values,
+        })
+        .map_err(|e| McpError::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+
+        Ok(CallToolResult::structured(response))
+    }
+
+    #[tool(
+        description = "Split a triangle sequence (OEIS keyword tabl, e.g., Pascal's triangle) into its rows.",
+        output_schema = output_schema::<TriangleRowsResponse>()
+    )]
+    async fn triangle_rows(
+        &self,
+        Parameters(FindRequest { id }): Parameters<FindRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        info!("Get triangle rows of sequence: {:?}", id);
+
+        let sequence = self.find_sequence(&id).await?;
+        if !sequence.keywords().tabl {
+            return Err(McpError::new(
+                ErrorCode::INVALID_PARAMS,
+                format!(
+                    "{} is not a triangle read by rows (no \"tabl\" keyword)",
+                    sequence.id()
+                ),
+                Some(json!({"keyword": sequence.keyword})),
+            ));
+        }
+        let terms: Vec<i64> = parse_terms(&sequence)?;
+
+        Ok(CallToolResult::structured(json!(TriangleRowsResponse {
+            id: sequence.id(),
+            first_row: sequence.first_index(),
+            rows: split_triangle_rows(&terms),
+        })))
+    }
+
+    #[tool(
+        description = "Compare two sequences term by term over the indices n they share, honoring their offsets.",
+        output_schema = output_schema::<SequenceDiffResponse>()
+    )]
+    async fn sequence_diff(
+        &self,
+        Parameters(CompareSequencesRequest {
+            first_id,
+            second_id,
+        }): Parameters<CompareSequencesRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        info!("Diff sequences: {:?} and {:?}", first_id, second_id);
+
+        let (first, second) = futures::try_join!(
+            async {
+                self.find_sequence(&first_id)
+                    .await
+                    .map_err(|e| self.prefix_error("first sequence", e))
+            },
+            async {
+                self.find_sequence(&second_id)
+                    .await
+                    .map_err(|e| self.prefix_error("second sequence", e))
+            },
+        )?;
+        let first_terms: Vec<i64> = parse_terms(&first)?;
+        let second_terms: Vec<i64> = parse_terms(&second)?;
+
+        let diffs = diff_terms(
+            (first.first_index(), &first_terms),
+            (second.first_index(), &second_terms),
+        );
+
+        Ok(CallToolResult::structured(json!(SequenceDiffResponse {
+            first_id: first.id(),
+            second_id: second.id(),
+            compared: diffs.len(),
+            equal: diffs.iter().filter(|diff| diff.difference == 0).count(),
+            diffs,
+        })))
+    }
+
+    #[tool(
+        description = "Check whether the terms of one sequence appear in another, contiguously or scattered, and where.",
+        output_schema = output_schema::<IsSubsequenceResponse>()
+    )]
+    async fn is_subsequence_of(
+        &self,
+        Parameters(IsSubsequenceRequest {
+            candidate_id,
+            container_id,
+            mode,
+        }): Parameters<IsSubsequenceRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        info!(
+            "Check whether {:?} is a {:?} subsequence of {:?}",
+            candidate_id, mode, container_id
+        );
+
+        let (candidate, container) = futures::try_join!(
+            async {
+                self.find_sequence(&candidate_id)
+                    .await
+                    .map_err(|e| self.prefix_error("candidate sequence", e))
+            },
+            async {
+                self.find_sequence(&container_id)
+                    .await
+                    .map_err(|e| self.prefix_error("container sequence", e))
+            },
+        )?;
+        let candidate_terms: Vec<i64> = parse_terms(&candidate)?;
+        let container_terms: Vec<i64> = parse_terms(&container)?;
+
+        let position = match mode {
+            SubsequenceMode::Contiguous => find_contiguous(&candidate_terms, &container_terms),
+            SubsequenceMode::Scattered => find_scattered(&candidate_terms, &container_terms),
+        };
+
+        Ok(CallToolResult::structured(json!(IsSubsequenceResponse {
+            candidate_id: candidate.id(),
+            container_id: container.id(),
+            mode,
+            found: position.is_some(),
+            start: position.map(|position| container.first_index() + position as i64),
+            candidate_terms: candidate_terms.len(),
+            container_terms: container_terms.len(),
+        })))
+    }
+
+    #[tool(
+        description = "Detect whether a sequence's terms form an arithmetic or geometric progression, with its common difference or ratio.",
+        output_schema = output_schema::<DetectProgressionResponse>()
+    )]
+    async fn detect_progression(
+        &self,
+        Parameters(FindRequest { id }): Parameters<FindRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        info!("Detect progression in sequence: {:?}", id);
+
+        let sequence = self.find_sequence(&id).await?;
+        let terms: Vec<i64> = parse_terms(&sequence)?;
+        // Any two terms trivially share a difference and a ratio
+        if terms.len() < 3 {
+            return Err(McpError::new(
+                ErrorCode::INVALID_PARAMS,
+                format!(
+                    "{} has {} term(s); at least 3 are needed to detect a progression",
+                    sequence.id(),
+                    terms.len()
+                ),
+                Some(json!({"count": terms.len()})),
+            ));
+        }
+
+        let common_difference = common_difference(&terms);
+        let common_ratio = common_ratio(&terms);
+
+        Ok(CallToolResult::structured(json!(
+            DetectProgressionResponse {
+                id: sequence.id(),
+                count: terms.len(),
+                arithmetic: common_difference.is_some(),
+                common_difference,
+                geometric: common_ratio.is_some(),
+                common_ratio,
+            }
+        )))
+    }
+
+    #[tool(
+        description = "Get the count, min, max, sum, and monotonicity of a sequence's terms.",
+        output_schema = output_schema::<SequenceStatsResponse>()
+    )]
+    async fn sequence_stats(
+        &self,
+        Parameters(FindRequest { id }): Parameters<FindRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        info!("Get statistics of sequence: {:?}", id);
+
+        let sequence = self.find_sequence(&id).await?;
+        // Growing sequences (e.g., A000142) soon exceed any fixed-width integer
+        let terms: Vec<DecimalInt> = parse_terms(&sequence)?;
+
+        Ok(CallToolResult::structured(json!(SequenceStatsResponse {
+            id: sequence.id(),
+            count: terms.len(),
+            min: terms.iter().min().map(DecimalInt::to_string),
+            max: terms.iter().max().map(DecimalInt::to_string),
+            sum: terms
+                .iter()
+                .fold(DecimalInt::default(), |sum, term| sum.add(term))
+                .to_string(),
+            monotonicity: monotonicity(&terms),
+            signed: sequence.keywords().sign,
+        })))
+    }
+
+    #[tool(
+        description = "Get a sequence's terms reduced modulo m, as non-negative residues (e.g., Fibonacci mod 10).",
+        output_schema = output_schema::<TermsModResponse>()
+    )]
+    async fn terms_mod(
+        &self,
+        Parameters(TermsModRequest { id, modulus }): Parameters<TermsModRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        info!("Reduce terms of sequence {:?} modulo {}", id, modulus);
+
+        if modulus <= 0 {
+            return Err(McpError::new(
+                ErrorCode::INVALID_PARAMS,
+                "modulus must be greater than 0".to_string(),
+                Some(json!({"modulus": modulus})),
+            ));
+        }
+
+        let sequence = self.find_sequence(&id).await?;
+        // Reduced digit by digit, so terms too large for `i64` need no big-integer parsing
+        let residues = sequence
+            .data
+            .split(',')
+            .map(str::trim)
+            .filter(|term| !term.is_empty())
+            .map(|term| {
+                decimal_mod(term, modulus).ok_or_else(|| {
+                    McpError::new(
+                        ErrorCode::INTERNAL_ERROR,
+                        format!("Invalid term in {}: {:?}", sequence.id(), term),
+                        None,
+                    )
+                })
+            })
+            .collect::<Result<Vec<i64>, McpError>>()?;
+
+        Ok(CallToolResult::structured(json!(TermsModResponse {
+            id: sequence.id(),
+            modulus,
+            residues,
+        })))
+    }
+
+    #[tool(
+        description = "Get the server's name, version, and uptime, and whether OEIS is reachable.",
+        output_schema = output_schema::<ServerStatusResponse>()
+    )]
+    async fn server_status(&self, _: Parameters<EmptyRequest>) -> Result<CallToolResult, McpError> {
+        info!("Get server status");
+
+        // `sequence_exists` bypasses the sequence cache, so this really reaches OEIS
+        let oeis_error = match self.client.sequence_exists(HEALTH_CHECK_ID).await {
+            Ok(true) => None,
+            Ok(false) => Some(format!("{} was not found on OEIS", HEALTH_CHECK_ID)),
+            Err(e) => {
+                warn!("Server status could not reach OEIS: {}", e);
+                Some(e.to_string())
+            }
+        };
+        let implementation = server_implementation();
+
+        Ok(CallToolResult::structured(json!(ServerStatusResponse {
+            name: implementation.name,
+            version: implementation.version,
+            uptime_secs: metrics::uptime().as_secs(),
+            oeis_reachable: oeis_error.is_none(),
+            oeis_error,
+        })))
+    }
+
+    #[tool(
+        description = "Describe how old a sequence is: its creation date and where its A-number falls relative to the newest OEIS entries.",
+        output_schema = output_schema::<SequenceAgeContextResponse>()
+    )]
+    async fn sequence_age_context(
+        &self,
+        Parameters(FindRequest { id }): Parameters<FindRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        info!("Get age context of sequence: {:?}", id);
+
+        let (sequence, latest) = futures::try_join!(self.find_sequence(&id), async {
+            self.client.latest_sequence().await.map_err(client_error)
+        })?;
+        let latest = latest.ok_or_else(|| {
+            McpError::new(
+                ErrorCode::INTERNAL_ERROR,
+                "OEIS returned no recently created sequences".to_string(),
+                None,
+            )
+        })?;
+
+        // The sequence itself may be newer than the page of recent entries
+        let latest_number = latest.number.max(sequence.number);
+        let position = if latest_number > 1 {
+            (sequence.number - 1) as f64 / (latest_number - 1) as f64
+        } else {
+            1.0
+        };
+        let newer_sequences = latest_number - sequence.number;
+        let latest_id = format!("A{:06}", latest_number);
+
+        Ok(CallToolResult::structured(json!(
+            SequenceAgeContextResponse {
+                context: age_context(
+                    &sequence.id(),
+                    sequence.created.as_deref(),
+                    position,
+                    newer_sequences,
+                    &latest_id
+                ),
+                id: sequence.id(),
+                created: sequence.created,
+                latest_id,
+                position,
+                newer_sequences,
+            }
+        )))
+    }
+
+    #[tool(
+        description = "Get the ratios a(n+1)/a(n) of consecutive terms of a sequence as floating-point values, for convergence analysis.",
+        output_schema = output_schema::<ConsecutiveRatiosResponse>()
+    )]
+    async fn consecutive_ratios(
+        &self,
+        Parameters(FindRequest { id }): Parameters<FindRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        info!("Compute consecutive ratios of sequence: {:?}", id);
+
+        let sequence = self.find_sequence(&id).await?;
+        // Ratios are floating-point anyway, so terms past `i64` (e.g., 21!) still parse
+        let terms: Vec<f64> = parse_terms(&sequence)?;
+
+        let mut ratios = Vec::new();
+        let mut skipped = Vec::new();
+        for (pair, n) in terms.windows(2).zip(sequence.first_index()..) {
+            if pair[0] == 0.0 {
+                skipped.push(n);
+            } else {
+                ratios.push(TermRatio {
+                    n,
+                    ratio: pair[1] / pair[0],
+                });
+            }
+        }
+
+        Ok(CallToolResult::structured(json!(
+            ConsecutiveRatiosResponse {
+                id: sequence.id(),
+                ratios,
+                skipped,
+            }
+        )))
+    }
+
+    #[tool(
+        description = "Check whether a linear recurrence a(n) = c0*a(n-1) + c1*a(n-2) + ... holds across a sequence's terms.",
+        output_schema = output_schema::<VerifyRecurrenceResponse>()
+    )]
+    async fn verify_recurrence(
+        &self,
+        Parameters(VerifyRecurrenceRequest {
+            id,
+            coefficients,
+            order,
+        }): Parameters<VerifyRecurrenceRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        info!(
+            "Verify order-{} recurrence {:?} against sequence: {:?}",
+            order, coefficients, id
+        );
+
+        if order == 0 || coefficients.len() != order {
+            return Err(McpError::new(
+                ErrorCode::INVALID_PARAMS,
+                "order must be positive and equal to the number of coefficients".to_string(),
+                Some(json!({"order": order, "coefficients": coefficients.len()})),
+            ));
+        }
+
+        let sequence = self.find_sequence(&id).await?;
+        let terms: Vec<i64> = parse_terms(&sequence)?;
+
+        if order >= terms.len() {
+            return Err(McpError::new(
+                ErrorCode::INVALID_PARAMS,
+                format!(
+                    "order must be less than the number of available terms ({})",
+                    terms.len()
+                ),
+                Some(json!({"order": order, "available_terms": terms.len()})),
+            ));
+        }
+
+        let predictions = recurrence_predictions(&terms, &coefficients).ok_or_else(|| {
+            McpError::new(
+                ErrorCode::INTERNAL_ERROR,
+                "Recurrence evaluation overflowed".to_string(),
+                Some(json!({"order": order})),
+            )
+        })?;
+
+        let mut matched = Vec::new();
+        let mut failed = Vec::new();
+        let first_checked = sequence.first_index() + order as i64;
+        for ((&actual, predicted), n) in terms[order..].iter().zip(predictions).zip(first_checked..)
+        {
+            if actual as i128 == predicted {
+                matched.push(n);
+            } else {
+                failed.push(RecurrenceMismatch {
+                    n,
+                    actual: actual.to_string(),
+                    predicted: predicted.to_string(),
+                });
+            }
+        }
+
+        Ok(CallToolResult::structured(json!(
+            VerifyRecurrenceResponse {
+                id: sequence.id(),
+                holds: failed.is_empty(),
+                matched,
+                failed,
+            }
+        )))
+    }
+}
+
+/// Render a sequence in the OEIS internal format, e.g. "%N A000045 Fibonacci numbers"
+fn format_oeis_entry(sequence: &OEISSequence) -> String {
+    let id = sequence.id();
+    let mut lines = Vec::new();
+
+    let data_tags = ["%S", "%T", "%U"];
+    let data_lines = wrap_data(&sequence.data, ENTRY_DATA_LINE_WIDTH, data_tags.len());
+    for (tag, data) in data_tags.into_iter().zip(data_lines) {
+        lines.push(format!("{} {} {}", tag, id, data));
+    }
+    lines.push(format!("%N {} {}", id, sequence.name));
+
+    for (tag, field) in [
+        ("%C", &sequence.comment),
+        ("%D", &sequence.reference),
+        ("%H", &sequence.link),
+        ("%F", &sequence.formula),
+        ("%e", &sequence.example),
+        ("%p", &sequence.maple),
+        ("%t", &sequence.mathematica),
+        ("%o", &sequence.program),
+        ("%Y", &sequence.xref),
+    ] {
+        for line in field.iter().flatten() {
+            lines.push(format!("{} {} {}", tag, id, line));
+        }
+    }
+
+    lines.push(format!("%K {} {}", id, sequence.keyword));
+    if let Some(offset) = &sequence.offset {
+        lines.push(format!("%O {} {}", id, offset));
+    }
+    if let Some(author) = &sequence.author {
+        lines.push(format!("%A {} {}", id, author));
+    }
+
+    lines.join("\n")
+}
+
+/// Extract `(label, url)` pairs from OEIS link lines containing HTML anchors,
+/// resolving site-relative URLs against https://oeis.org
+fn extract_links(raw: &[String]) -> Vec<(String, String)> {
+    let mut links = Vec::new();
+
+    for line in raw {
+        let mut rest = line.as_str();
+        while let Some(start) = rest.find("<a href=\"") {
+            rest = &rest[start + "<a href=\"".len()..];
+            let Some(url_end) = rest.find('"') else { break };
+            let url = &rest[..url_end];
+            rest = &rest[url_end..];
+
+            let Some(label_start) = rest.find('>') else {
+                break;
+            };
+            rest = &rest[label_start + 1..];
+            let Some(label_end) = rest.find("</a>") else {
+                break;
+            };
+            let label = rest[..label_end].trim();
+            rest = &rest[label_end + "</a>".len()..];
+
+            let url = if url.starts_with('/') {
+                format!("https://oeis.org{}", url)
+            } else {
+                url.to_string()
+            };
+            links.push((label.to_string(), url));
+        }
+    }
+
+    links
+}
+
+/// Split comma-separated terms into at most `max_lines` lines of about `width` characters,
+/// breaking after commas; the last line takes whatever remains
+fn wrap_data(data: &str, width: usize, max_lines: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut current = String::new();
+
+    for term in data.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+        if !current.is_empty()
+            && current.len() + term.len() + 1 > width
+            && lines.len() + 1 < max_lines
+        {
+            lines.push(std::mem::take(&mut current));
+        }
+        current.push_str(term);
+        current.push(',');
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+
+    // Like OEIS, the final line has no trailing comma
+    if let Some(last) = lines.last_mut() {
+        last.pop();
+    }
+    lines
+}
+
+/// Map a seed to an index in `0..count` using the SplitMix64 finalizer
+fn random_index(count: usize, seed: u64) -> usize {
+    let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^= z >> 31;
+    (z % count as u64) as usize
+}
+
+/// Applies the forward difference operator `order` times; `None` on overflow
+fn finite_differences(terms: &[i128], order: usize) -> Option<Vec<i128>> {
+    let mut values = terms.to_vec();
+    for _ in 0..order {
+        values = values
+            .windows(2)
+            .map(|pair| pair[1].checked_sub(pair[0]))
+            .collect::<Option<_>>()?;
+    }
+    Some(values)
+}
+
+/// Fraction of `terms` that appear consecutively at the start of the comma-separated `data`
+fn prefix_score(terms: &[i64], data: &str) -> f64 {
+    if terms.is_empty() {
+        return 0.0;
+    }
+    // Compare as strings, since data terms can exceed `i64`
+    let matched = data
+        .split(',')
+        .map(str::trim)
+        .zip(terms)
+        .take_while(|(term, expected)| *term == expected.to_string())
+        .count();
+    matched as f64 / terms.len() as f64
+}
+
+/// Applies `op` to the terms; `None` on overflow
+fn transform_terms(terms: &[i128], op: Transform) -> Option<Vec<i128>> {
+    let mut acc: i128 = 0;
+    match op {
+        Transform::PartialSums => terms
+            .iter()
+            .map(|&t| {
+                acc = acc.checked_add(t)?;
+                Some(acc)
+            })
+            .collect(),
+        Transform::Differences => finite_differences(terms, 1),
+        Transform::RunningProducts => {
+            acc = 1;
+            terms
+                .iter()
+                .map(|&t| {
+                    acc = acc.checked_mul(t)?;
+                    Some(acc)
+                })
+                .collect()
+        }
+        Transform::Negate => terms.iter().map(|&t| t.checked_neg()).collect(),
+    }
+}
+
+/// The term a(n) of `sequence`, honoring its offset; `None` outside the available data
+fn term_at_index(sequence: &OEISSequence, n: i64) -> Option<&str> {
+    let index = usize::try_from(n.checked_sub(sequence.first_index())?).ok()?;
+    sequence
+        .data
+        .split(',')
+        .map(str::trim)
+        .filter(|term| !term.is_empty())
+        .nth(index)
+}
+
+/// Reject a 128-bit result outside the `i64` / `u64` range, which `serde_json` cannot write
+/// as a JSON number, as an overflow naming the first such value and its index n
+fn check_json_range(values: &[i128], first_index: i64, what: &str) -> Result<(), McpError> {
+    let out_of_range = values
+        .iter()
+        .zip(first_index..)
+        .find(|(value, _)| i64::try_from(**value).is_err() && u64::try_from(**value).is_err());
+    match out_of_range {
+        Some((value, n)) => Err(McpError::new(
+            ErrorCode::INTERNAL_ERROR,
+            format!(
+                "{} overflowed the 64-bit range of JSON numbers at n = {} ({})",
+                what, n, value
+            ),
+            Some(json!({"n": n, "value": value.to_string()})),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// JSON schema advertised as a tool's `outputSchema`, generated from its response type
+fn output_schema<T: JsonSchema + 'static>() -> Arc<JsonObject> {
+    rmcp::handler::server::tool::schema_for_output::<T>()
+        .unwrap_or_else(|e| panic!("invalid tool output schema: {}", e))
+}
+
+/// Splits flat triangle data into rows of 1, 2, 3, ... terms
+fn split_triangle_rows(terms: &[i64]) -> Vec<Vec<i64>> {
+    let mut rows = Vec::new();
+    let mut rest = terms;
+    while !rest.is_empty() {
+        let (row, tail) = rest.split_at((rows.len() + 1).min(rest.len()));
+        rows.push(row.to_vec());
+        rest = tail;
+    }
+    rows
+}
+
+/// Pairs up the terms of two sequences (each given with its first index) at every index
+/// present in both
+fn diff_terms(first: (i64, &[i64]), second: (i64, &[i64])) -> Vec<TermDiff> {
+    let (first_index, first_terms) = first;
+    let (second_index, second_terms) = second;
+    let start = first_index.max(second_index);
+
+    first_terms
+        .iter()
+        .skip((start - first_index) as usize)
+        .zip(second_terms.iter().skip((start - second_index) as usize))
+        .zip(start..)
+        .map(|((&a, &b), n)| TermDiff {
+            n,
+            first: a,
+            second: b,
+            difference: b as i128 - a as i128,
+        })
+        .collect()
+}
+
+/// Position in `haystack` where `needle` first appears as consecutive terms
+fn find_contiguous(needle: &[i64], haystack: &[i64]) -> Option<usize> {
+    if needle.is_empty() {
+        return Some(0);
+    }
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// Position in `haystack` of the first term of the earliest in-order (not necessarily
+/// consecutive) occurrence of `needle`. Matching each term greedily at its first possible
+/// position finds a match whenever one exists, and it starts as early as possible.
+fn find_scattered(needle: &[i64], haystack: &[i64]) -> Option<usize> {
+    let mut start = None;
+    let mut rest = haystack.iter().enumerate();
+    for term in needle {
+        let (position, _) = rest.find(|(_, t)| *t == term)?;
+        start.get_or_insert(position);
+    }
+    Some(start.unwrap_or(0))
+}
+
+/// The canonical residue in `0..modulus` of a decimal integer of any length (e.g., "-7" mod 3
+/// is 2); `None` if `term` is not an integer. `modulus` must be positive.
+fn decimal_mod(term: &str, modulus: i64) -> Option<i64> {
+    let (negative, digits) = match term.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, term),
+    };
+    if digits.is_empty() {
+        return None;
+    }
+
+    let modulus = modulus as i128;
+    let residue = digits.chars().try_fold(0i128, |residue, c| {
+        Some((residue * 10 + c.to_digit(10)? as i128) % modulus)
+    })?;
+    let residue = if negative {
+        (modulus - residue) % modulus
+    } else {
+        residue
+    };
+    Some(residue as i64)
+}
+
+/// Summary like "A000045 (created 1991-04-30) is among the oldest quarter of OEIS sequences;
+/// about 389956 sequences have been added after it, up to A390001". A-numbers are assigned
+/// roughly in order of submission, so `position` in the A-number range stands in for age.
+fn age_context(
+    id: &str,
+    created: Option<&str>,
+    position: f64,
+    newer_sequences: i64,
+    latest_id: &str,
+) -> String {
+    let created = created
+        .and_then(|created| created.split('T').next())
+        .map(|date| format!(" (created {})", date))
+        .unwrap_or_default();
+    let era = match position {
+        p if p < 0.25 => "is among the oldest quarter of OEIS sequences",
+        p if p < 0.5 => "is in the older half of OEIS sequences",
+        p if p < 0.75 => "is in the newer half of OEIS sequences",
+        p if p < 0.99 => "is among the newest quarter of OEIS sequences",
+        _ => "is one of the most recent additions to OEIS",
+    };
+    if newer_sequences == 0 {
+        return format!("{}{} {}; it is the newest entry", id, created, era);
+    }
+    format!(
+        "{}{} {}; about {} sequences have been added after it, up to {}",
+        id, created, era, newer_sequences, latest_id
+    )
+}
+
+/// Name and version of this server, as announced in `initialize` and by `server_status`
+fn server_implementation() -> Implementation {
+    Implementation::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))
+        .with_title("OEIS MCP server")
+        .with_description(env!("CARGO_PKG_DESCRIPTION"))
+        .with_website_url("https://github.com/23prime/oeis-mcp-server-rs")
+}
+
+/// Integer of any length parsed from a decimal OEIS term, ordered by value
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct DecimalInt {
+    negative: bool,
+    /// Base-10 digits, most significant first, without leading zeros (empty for 0)
+    digits: Vec<u8>,
+}
+
+impl DecimalInt {
+    fn add(&self, other: &DecimalInt) -> DecimalInt {
+        if self.negative == other.negative {
+            return DecimalInt {
+                negative: self.negative,
+                digits: add_magnitudes(&self.digits, &other.digits),
+            };
+        }
+        match cmp_magnitudes(&self.digits, &other.digits) {
+            Ordering::Equal => DecimalInt::default(),
+            Ordering::Greater => DecimalInt {
+                negative: self.negative,
+                digits: sub_magnitudes(&self.digits, &other.digits),
+            },
+            Ordering::Less => DecimalInt {
+                negative: other.negative,
+                digits: sub_magnitudes(&other.digits, &self.digits),
+            },
+        }
+    }
+}
+
+impl FromStr for DecimalInt {
+    type Err = ();
+
+    fn from_str(term: &str) -> Result<Self, Self::Err> {
+        let (negative, digits) = match term.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, term),
+        };
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(());
+        }
+        let digits: Vec<u8> = digits
+            .bytes()
+            .map(|b| b - b'0')
+            .skip_while(|&d| d == 0)
+            .collect();
+        Ok(DecimalInt {
+            negative: negative && !digits.is_empty(),
+            digits,
+        })
+    }
+}
+
+impl std::fmt::Display for DecimalInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.digits.is_empty() {
+            return write!(f, "0");
+        }
+        let digits: String = self.digits.iter().map(|&d| char::from(b'0' + d)).collect();
+        write!(f, "{}{}", if self.negative { "-" } else { "" }, digits)
+    }
+}
+
+impl Ord for DecimalInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_magnitudes(&self.digits, &other.digits),
+            (true, true) => cmp_magnitudes(&other.digits, &self.digits),
+        }
+    }
+}
+
+impl PartialOrd for DecimalInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Compares digit vectors without leading zeros
+fn cmp_magnitudes(a: &[u8], b: &[u8]) -> Ordering {
+    a.len().cmp(&b.len()).then_with(|| a.cmp(b))
+}
+
+fn add_magnitudes(a: &[u8], b: &[u8]) -> Vec<u8> {
+    let mut sum = Vec::with_capacity(a.len().max(b.len()) + 1);
+    let mut carry = 0;
+    let (mut a, mut b) = (a.iter().rev(), b.iter().rev());
+    loop {
+        let (x, y) = (a.next(), b.next());
+        if x.is_none() && y.is_none() {
+            break;
+        }
+        let digit = x.unwrap_or(&0) + y.unwrap_or(&0) + carry;
+        sum.push(digit % 10);
+        carry = digit / 10;
+    }
+    if carry > 0 {
+        sum.push(carry);
+    }
+    sum.reverse();
+    sum
+}
+
+/// `a - b` for magnitudes with `a >= b`
+fn sub_magnitudes(a: &[u8], b: &[u8]) -> Vec<u8> {
+    let mut difference = Vec::with_capacity(a.len());
+    let mut borrow = 0;
+    let mut b = b.iter().rev();
+    for &x in a.iter().rev() {
+        let y = b.next().unwrap_or(&0) + borrow;
+        borrow = u8::from(x < y);
+        difference.push(x + 10 * borrow - y);
+    }
+    while difference.last() == Some(&0) {
+        difference.pop();
+    }
+    difference.reverse();
+    difference
+}
+
+/// OEIS "Listen" page, which renders a sequence as music
+fn listen_url(id: &str) -> String {
+    format!("https://oeis.org/play?seq={}", id)
+}
+
+/// Lowercases a nickname and drops a trailing "numbers" / "sequence" and possessive, so
+/// "Catalan numbers", "catalan", and "Catalan's sequence" all match the same table entry
+fn normalize_nickname(nickname: &str) -> String {
+    let mut normalized = nickname.trim().to_lowercase();
+    for suffix in [" numbers", " sequence", "'s"] {
+        if let Some(stripped) = normalized.strip_suffix(suffix) {
+            normalized = stripped.trim_end().to_string();
+        }
+    }
+    normalized
+}
+
+/// Guesses the kind of a `formula` line from the prefixes OEIS editors conventionally use
+fn classify_formula(line: &str) -> FormulaKind {
+    let line = line.trim_start();
+    let compact: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+    if line.starts_with("E.g.f.") {
+        FormulaKind::ExponentialGeneratingFunction
+    } else if line.starts_with("G.f.") || line.starts_with("O.g.f.") {
+        FormulaKind::GeneratingFunction
+    } else if compact.contains("a(n-") || compact.contains("a(n+") {
+        FormulaKind::Recurrence
+    } else if compact.starts_with("a(n)=") {
+        FormulaKind::ClosedForm
+    } else {
+        FormulaKind::Other
+    }
+}
+
+/// Backslash-escapes the characters Markdown treats as emphasis or code spans
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '`') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn escape_markdown_lines(lines: &Option<Vec<String>>) -> Option<Vec<String>> {
+    lines
+        .as_ref()
+        .map(|lines| lines.iter().map(|line| escape_markdown(line)).collect())
+}
+
+/// `Mcp-Session-Id` header of the HTTP request behind a call; `None` over stdio
+fn session_id(extensions: &Extensions) -> Option<&str> {
+    extensions
+        .get::<axum::http::request::Parts>()?
+        .headers
+        .get("mcp-session-id")?
+        .to_str()
+        .ok()
+}
+
+/// Formats a decimal term with a comma between every three digits, keeping its sign
+fn group_thousands(term: &str) -> String {
+    let (sign, digits) = match term.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", term),
+    };
+    let mut grouped = String::with_capacity(term.len() + digits.len() / 3);
+    grouped.push_str(sign);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+/// Classifies the order of consecutive terms
+fn monotonicity<T: PartialOrd>(terms: &[T]) -> Monotonicity {
+    let non_decreasing = terms.windows(2).all(|w| w[0] <= w[1]);
+    let non_increasing = terms.windows(2).all(|w| w[0] >= w[1]);
+    match (non_decreasing, non_increasing) {
+        (true, true) => Monotonicity::Constant,
+        (true, false) => Monotonicity::NonDecreasing,
+        (false, true) => Monotonicity::NonIncreasing,
+        (false, false) => Monotonicity::None,
+    }
+}
+
+/// The difference shared by every pair of consecutive terms, if there is one.
+/// Needs at least two terms.
+fn common_difference(terms: &[i64]) -> Option<i128> {
+    let difference = terms[1] as i128 - terms[0] as i128;
+    terms
+        .windows(2)
+        .all(|w| w[1] as i128 - w[0] as i128 == difference)
+        .then_some(difference)
+}
+
+/// The ratio shared by every pair of consecutive terms, if there is one. Compared exactly
+/// as a(n)^2 = a(n-1) * a(n+1) over nonzero terms, so fractional ratios (e.g., 1/2) count too.
+/// Needs at least two terms.
+fn common_ratio(terms: &[i64]) -> Option<f64> {
+    if terms.contains(&0) {
+        return None;
+    }
+    terms
+        .windows(3)
+        .all(|w| w[1] as i128 * w[1] as i128 == w[0] as i128 * w[2] as i128)
+        .then(|| terms[1] as f64 / terms[0] as f64)
+}
+
+/// Evaluates `c0*a(i-1) + c1*a(i-2) + ...` for every index `i` with a full window of
+/// previous terms (`i >= coefficients.len()`); `None` on overflow
+fn recurrence_predictions(terms: &[i64], coefficients: &[i64]) -> Option<Vec<i128>> {
+    (coefficients.len()..terms.len())
+        .map(|i| {
+            coefficients
+                .iter()
+                .enumerate()
+                .try_fold(0i128, |sum, (k, &c)| {
+                    sum.checked_add((c as i128).checked_mul(terms[i - 1 - k] as i128)?)
+                })
+        })
+        .collect()
+}
+
+#[prompt_router]
+impl<C: OEISClient + Clone + 'static> OEIS<C> {
+    /// Provides a comprehensive analysis of an OEIS sequence
+    #[prompt(
+        description = "Analyzes an OEIS sequence in detail, providing mathematical context, patterns, and related sequences"
+    )]
+    #[instrument(skip_all, fields(request_id = %next_request_id(), id = %sequence_id))]
+    async fn sequence_analysis(
+        &self,
+        Parameters(SequenceAnalysisRequest { sequence_id }): Parameters<SequenceAnalysisRequest>,
+    ) -> Result<Vec<PromptMessage>, McpError> {
+        info!("Analyzing sequence: {:?}", sequence_id);
+        let sequence = self.find_sequence(&sequence_id).await?;
+        Ok(vec![
+            self.build_user_message(&sequence_id),
+            self.build_assistant_messages(&sequence),
+        ])
+    }
+
+    /// Explains an OEIS sequence to an audience of the given level
+    #[prompt(
+        description = "Explains an OEIS sequence at a chosen audience level, from a child to an expert mathematician"
+    )]
+    #[instrument(skip_all, fields(request_id = %next_request_id(), id = %sequence_id, level = ?level))]
+    async fn explain_sequence(
+        &self,
+        Parameters(ExplainSequenceRequest { sequence_id, level }): Parameters<
+            ExplainSequenceRequest,
+        >,
+    ) -> Result<Vec<PromptMessage>, McpError> {
+        info!(
+            "Explaining sequence: {:?} (level: {:?})",
+            sequence_id, level
+        );
+        let sequence = self.find_sequence(&sequence_id).await?;
+        Ok(vec![
+            self.build_explain_user_message(&sequence_id, level),
+            self.build_assistant_messages(&sequence),
+        ])
+    }
+
+    /// Asks for a program generating an OEIS sequence in a given language
+    #[prompt(
+        description = "Asks for code that generates an OEIS sequence in a chosen language, using the sequence's existing OEIS programs as reference"
+    )]
+    #[instrument(skip_all, fields(request_id = %next_request_id(), id = %sequence_id, language = %language))]
+    async fn generate_code(
+        &self,
+        Parameters(GenerateCodeRequest {
+            sequence_id,
+            language,
+        }): Parameters<GenerateCodeRequest>,
+    ) -> Result<Vec<PromptMessage>, McpError> {
+        info!(
+            "Generating code for sequence: {:?} (language: {:?})",
+            sequence_id, language
+        );
+        let language = language.trim();
+        if language.is_empty() {
+            return Err(McpError::new(
+                ErrorCode::INVALID_PARAMS,
+                "Language must not be empty".to_string(),
+                None,
+            ));
+        }
+
+        let sequence = self.find_sequence(&sequence_id).await?;
+        Ok(vec![
+            self.build_code_user_message(&sequence_id, language),
+            self.build_code_assistant_messages(&sequence),
+        ])
+    }
+
+    /// Compares two OEIS sequences side by side
+    #[prompt(
+        description = "Compares two OEIS sequences, highlighting shared cross-references, overlapping terms, and keyword differences"
+    )]
+    #[instrument(
+        skip_all,
+        fields(request_id = %next_request_id(), first_id = %first_id, second_id = %second_id)
+    )]
+    async fn compare_sequences(
+        &self,
+        Parameters(CompareSequencesRequest {
+            first_id,
+            second_id,
+        }): Parameters<CompareSequencesRequest>,
+    ) -> Result<Vec<PromptMessage>, McpError> {
+        info!("Comparing sequences: {:?} and {:?}", first_id, second_id);
+        let first = self
+            .find_sequence(&first_id)
+            .await
+            .map_err(|e| self.prefix_error("first sequence", e))?;
+        let second = self
+            .find_sequence(&second_id)
+            .await
+            .map_err(|e| self.prefix_error("second sequence", e))?;
+        Ok(vec![
+            self.build_compare_user_message(&first_id, &second_id),
+            self.build_compare_assistant_messages(&first, &second),
+        ])
+    }
+
+    fn prefix_error(&self, label: &str, error: McpError) -> McpError {
+        McpError::new(
+            error.code,
+            format!("{}: {}", label, error.message),
+            error.data,
+        )
+    }
+
+    fn build_user_message(&self, sequence_id: &str) -> PromptMessage {
+        PromptMessage::new_text(
+            Role::User,
+            format!(
+                "Please provide a comprehensive analysis of OEIS sequence {}. \
+                Include:\n\
+                1. The definition and meaning of this sequence\n\
+                2. Mathematical properties and patterns\n\
+                3. Real-world applications or significance\n\
+                4. Relationships to other sequences\n\
+                5. Interesting facts or observations",
+                sequence_id
+            ),
+        )
+    }
+
+    fn build_explain_user_message(&self, sequence_id: &str, level: Level) -> PromptMessage {
+        let (audience, guidelines) = level.instructions();
+        PromptMessage::new_text(
+            Role::User,
+            format!(
+                "Please explain OEIS sequence {} to {}. {}",
+                sequence_id, audience, guidelines
+            ),
+        )
+    }
+
+    fn build_code_user_message(&self, sequence_id: &str, language: &str) -> PromptMessage {
+        PromptMessage::new_text(
+            Role::User,
+            format!(
+                "Please write a {} program that generates OEIS sequence {}. \
+                Include:\n\
+                1. A function returning the first n terms, honoring the sequence's offset\n\
+                2. Arbitrary-precision arithmetic if the terms outgrow 64-bit integers\n\
+                3. A check that the output matches the known terms below\n\
+                4. A short explanation of the method, adapted from the OEIS programs below where they help",
+                language, sequence_id
+            ),
+        )
+    }
+
+    fn build_code_assistant_messages(&self, sequence: &OEISSequence) -> PromptMessage {
+        // Maple and Mathematica entries carry no language prefix, unlike `program` lines
+        let mut programs: Vec<String> = Vec::new();
+        for (label, lines) in [
+            ("Maple", &sequence.maple),
+            ("Mathematica", &sequence.mathematica),
+        ] {
+            if let Some(lines) = lines.as_deref().filter(|lines| !lines.is_empty()) {
+                programs.push(format!("({}) {}", label, lines.join("\n")));
+            }
+        }
+        programs.extend(sequence.program.iter().flatten().cloned());
+
+        let programs_section = if programs.is_empty() {
+            "**OEIS programs:** (none)\n\n".to_string()
+        } else {
+            format!("**OEIS programs:**\n```\n{}\n```\n\n", programs.join("\n"))
+        };
+
+        PromptMessage::new_text(
+            Role::Assistant,
+            format!(
+                "{}{}",
+                self.build_sequence_context(sequence),
+                programs_section
+            ),
+        )
+    }
+
+    fn build_assistant_messages(&self, sequence: &OEISSequence) -> PromptMessage {
+        PromptMessage::new_text(Role::Assistant, self.build_sequence_context(sequence))
+    }
+
+    fn build_compare_user_message(&self, first_id: &str, second_id: &str) -> PromptMessage {
+        PromptMessage::new_text(
+            Role::User,
+            format!(
+                "Please compare OEIS sequences {} and {}. \
+                Include:\n\
+                1. How the two definitions relate to each other\n\
+                2. Shared and differing mathematical properties\n\
+                3. Overlapping terms and whether the overlap is meaningful\n\
+                4. Common cross-references and related sequences\n\
+                5. Differences in classification (keywords)",
+                first_id, second_id
+            ),
+        )
+    }
+
+    fn build_compare_assistant_messages(
+        &self,
+        first: &OEISSequence,
+        second: &OEISSequence,
+    ) -> PromptMessage {
+        let second_xrefs = second.xref_ids();
+        let shared_xrefs: Vec<String> = first
+            .xref_ids()
+            .into_iter()
+            .filter(|id| second_xrefs.contains(id))
+            .collect();
+
+        // Compare the decimal strings so terms past i64 still count
+        let term_strings = |sequence: &OEISSequence| -> Vec<String> {
+            sequence
+                .data
+                .split(',')
+                .map(str::trim)
+                .filter(|term| !term.is_empty())
+                .map(str::to_string)
+                .collect()
+        };
+        let second_terms = term_strings(second);
+        let mut overlapping_terms: Vec<String> = Vec::new();
+        for term in term_strings(first) {
+            if second_terms.contains(&term) && !overlapping_terms.contains(&term) {
+                overlapping_terms.push(term);
+            }
+        }
+
+        let keywords = |sequence: &OEISSequence| -> Vec<String> {
+            sequence
+                .keyword
+                .split(',')
+                .map(str::trim)
+                .filter(|keyword| !keyword.is_empty())
+                .map(str::to_string)
+                .collect()
+        };
+        let first_keywords = keywords(first);
+        let second_keywords = keywords(second);
+        let only_in = |a: &[String], b: &[String]| -> Vec<String> {
+            a.iter().filter(|k| !b.contains(k)).cloned().collect()
+        };
+        let only_in_first = only_in(&first_keywords, &second_keywords);
+        let only_in_second = only_in(&second_keywords, &first_keywords);
+
+        let comparison_context = format!(
+            "{}---\n\n\
+            {}---\n\n\
+            # Comparison of {} and {}\n\n\
+            **Shared cross-references:** {}\n\n\
+            **Overlapping terms:** {}\n\n\
+            **Keywords only in {}:** {}\n\n\
+            **Keywords only in {}:** {}\n\n",
+            self.build_sequence_context(first),
+            self.build_sequence_context(second),
+            first.id(),
+            second.id(),
+            self.none_or_join(&shared_xrefs),
+            self.none_or_join(&overlapping_terms),
+            first.id(),
+            self.none_or_join(&only_in_first),
+            second.id(),
+            self.none_or_join(&only_in_second),
+        );
+
+        PromptMessage::new_text(Role::Assistant, comparison_context)
+    }
+
+    fn none_or_join(&self, items: &[String]) -> String {
+        if items.is_empty() {
+            "(none)".to_string()
+        } else {
+            items.join(", ")
+        }
+    }
+
+    fn build_sequence_context(&self, sequence: &OEISSequence) -> String {
+        let sequence_id_formatted = sequence.id();
+        let offset_section = self.empty_or_line("Offset", &sequence.offset);
+        let author_section = self.empty_or_line("Author", &sequence.author);
+        // Clients cache entries by revision, which OEIS bumps on every edit
+        let revision_section =
+            self.empty_or_line("Revision", &sequence.revision.map(|r| r.to_string()));
+        // Comments and formulas are free text full of `*` and `_`, which would otherwise
+        // render as emphasis
+        let comments_section =
+            self.empty_or_join("Comments", &escape_markdown_lines(&sequence.comment));
+        let formulas_section =
+            self.empty_or_join("Formulas", &escape_markdown_lines(&sequence.formula));
+        let examples_section = self.empty_or_join("Examples", &sequence.example);
+        let xref_section = self.empty_or_join("Cross-references", &sequence.xref);
+        let references_section = self.empty_or_join("References", &sequence.reference);
+        let links_section = self.links_section(&sequence.link);
+
+        format!(
+            "# OEIS Sequence {}\n\n\
+            **Name:** {}\n\n\
+            **Data (first few terms):** {}\n\n\
+            **Keywords:** {}\n\n\
+            {}{}{}{}{}{}{}{}{}",
+            sequence_id_formatted,
+            sequence.name,
+            sequence.data,
+            sequence.keyword,
+            offset_section,
+            author_section,
+            revision_section,
+            comments_section,
+            formulas_section,
+            examples_section,
+            xref_section,
+            references_section,
+            links_section,
+        )
+    }
+
+    fn empty_or_line(&self, title: &str, content: &Option<String>) -> String {
+        content
+            .as_ref()
+            .map(|c| format!("**{}:** {}\n\n", title, c))
+            .unwrap_or_default()
+    }
+
+    /// Render external links as a Markdown list, capped at `MAX_ANALYSIS_LINKS`
+    fn links_section(&self, links: &Option<Vec<String>>) -> String {
+        let links = extract_links(links.as_deref().unwrap_or_default());
+        if links.is_empty() {
+            return String::new();
+        }
+
+        let mut lines: Vec<String> = links
+            .iter()
+            .take(MAX_ANALYSIS_LINKS)
+            .map(|(label, url)| format!("- [{}]({})", label, url))
+            .collect();
+        if links.len() > MAX_ANALYSIS_LINKS {
+            lines.push(format!(
+                "- ... and {} more",
+                links.len() - MAX_ANALYSIS_LINKS
+            ));
+        }
+        format!("**Links:**\n{}\n\n", lines.join("\n"))
+    }
+
+    /// Render a titled section, treating `None` and an empty list identically
+    fn empty_or_join(&self, title: &str, contents: &Option<Vec<String>>) -> String {
+        match contents.as_deref() {
+            Some(lines) if !lines.is_empty() => {
+                format!("**{}:**\n{}\n\n", title, lines.join("\n"))
+            }
+            _ => String::new(),
+        }
+    }
+}
+
+/// Server instructions, split by capability so that disabled ones are left out
+const TOOL_INSTRUCTIONS: &str = "This server provides access to the OEIS (Online Encyclopedia of Integer Sequences) database. Tools: get_url (returns the OEIS homepage URL), find_by_id (search for a sequence by ID like 'A000045'), search_by_subsequence (search for sequences matching a given subsequence like [1,1,2,3,5]), get_terms (returns the first N terms of a sequence as integers), is_known_sequence (returns whether a subsequence matches any OEIS sequence, with the match count and first ID, without fetching the sequences), search_by_keyword (search for sequences tagged with an OEIS keyword like 'core' or 'nice'), search_text (search for sequences by free text like 'number of partitions'), resolve_xrefs (returns a sequence's cross-referenced IDs with their names), sequence_differences (computes the n-th finite difference of a sequence's terms), format_entry (renders a sequence as an OEIS-style text entry), find_many (fetches several sequences by ID concurrently), get_programs (returns the Maple, Mathematica, and other programs that generate a sequence), find_by_name (finds the sequence whose name exactly matches, ignoring case), sequence_plot_data (returns (n, a(n)) pairs of a sequence for plotting), random_sequence (returns a random sequence tagged with a keyword, 'nice' by default), verify_recurrence (checks whether a linear recurrence with given coefficients holds across a sequence's terms), neighbors (returns the existing sequences with adjacent A-numbers), sequence_keywords (returns a sequence's OEIS keywords as named booleans), search_generating_function (searches for sequences whose formulas mention a generating function like 'x/(1-x-x^2)'), transform_sequence (applies partial sums, differences, running products, or negation to a sequence's terms), search_ranked (searches by subsequence and ranks matches that start with the given terms first, with a relevance score), term_at (returns the term a(n) of a sequence, honoring its offset), list_keywords (lists the OEIS keywords with a short description of each), export_csv (exports a sequence as CSV text with index,value columns), sequence_stats (summarizes a sequence's terms: count, min, max, sum, and monotonicity), sequence_links (returns MCP resource links to a sequence's b-file, OEIS web page, and cross-referenced sequences), triangle_rows (splits a triangle sequence (keyword tabl) like Pascal's triangle into its rows), sequence_diff (compares two sequences term by term over their shared indices, honoring offsets), search_by_term_at (finds sequences whose term a(n) equals a value, honoring offsets), format_terms (returns a sequence's terms as strings, optionally with thousands separators like 1,234,567), search_by_pattern (searches by a subsequence with null wildcards for unknown terms, like [1,1,null,3,5]), get_comments (returns a sequence's comments exactly as OEIS stores them), consecutive_ratios (returns the ratios a(n+1)/a(n) of consecutive terms, e.g. to watch Fibonacci approach the golden ratio), contains_value (checks whether a value appears among a sequence's known terms, and at which indices), raw_lookup (returns the verbatim OEIS JSON response for a sequence ID, including fields this server does not model), suggest_sequences (suggests sequences for a natural-language description, ranking core and nice sequences first), extract_formulas (groups a sequence's formulas into generating functions, e.g.f.s, recurrences, closed forms, and other), lookup_by_nickname (resolves a sequence's A-number from a common nickname like 'Catalan numbers'), media_links (returns the URLs of a sequence's OEIS listen (play as music) and graph pages), validate_ids (checks whether each of several sequence IDs exists in OEIS), is_subsequence_of (checks whether the terms of one sequence appear in another, contiguously or scattered, and where), referencing_sequences (lists the sequences that mention a sequence (back-references), alongside the sequences it cross-references itself), get_bfile (pages through the b-file (all known terms) of a sequence as index/value entries), detect_progression (reports whether a sequence's terms form an arithmetic or geometric progression, with the common difference or ratio), terms_mod (returns a sequence's terms reduced modulo m as non-negative residues, e.g. Fibonacci mod 10), sequence_age_context (how old a sequence is, from its creation date and A-number relative to the newest entries), server_status (server name, version, uptime, and whether OEIS is reachable, for clients without HTTP access).";
+const PROMPT_INSTRUCTIONS: &str = "Prompts: sequence_analysis (provides comprehensive analysis of an OEIS sequence), compare_sequences (compares two OEIS sequences side by side), explain_sequence (explains an OEIS sequence for a child, high-school, undergrad, or expert audience), generate_code (asks for code generating a sequence in a given language, with its OEIS programs as reference).";
+const RESOURCE_INSTRUCTIONS: &str = "Resources: oeis://sequence/{id} (direct access to sequence data as JSON), oeis://bfile/{id} (all known terms from the sequence's b-file as JSON).";
+const USAGE_INSTRUCTIONS: &str = "Use this server to look up integer sequences, analyze their mathematical properties, and explore relationships between sequences.";
+
+// Tool calls are dispatched by hand rather than with `#[tool_handler]` to apply the timeout,
+// and prompts rather than with `#[prompt_handler]` so they can be disabled
+impl<C: OEISClient + Clone + 'static> ServerHandler for OEIS<C> {
+    // One span per tool call, enclosing the `request_id` spans of individual tools, so that
+    // exported traces (see `tracer.rs`) cover every tool
+    #[instrument(
+        name = "tool_call",
+        skip_all,
+        fields(mcp_request_id = ?context.id, tool = %request.name)
+    )]
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParams,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        self.session
+            .record_tool_call(session_id(&context.extensions));
+        let tool_context = ToolCallContext::new(self, request, context);
+        self.time_limited(self.tool_router.call(tool_context)).await
+    }
+
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, McpError> {
+        Ok(ListToolsResult {
+            tools: self.tool_router.list_all(),
+            meta: None,
+            next_cursor: None,
+        })
+    }
+
+    fn get_tool(&self, name: &str) -> Option<Tool> {
+        self.tool_router.get(name).cloned()
+    }
+
+    async fn get_prompt(
+        &self,
+        request: GetPromptRequestParams,
+        context: RequestContext<RoleServer>,
+    ) -> Result<GetPromptResult, McpError> {
+        self.check_enabled(self.prompts_enabled, "Prompts")?;
+        let prompt_context = PromptContext::new(self, request.name, request.arguments, context);
+        self.prompt_router.get_prompt(prompt_context).await
+    }
+
+    async fn list_prompts(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _: RequestContext<RoleServer>,
+    ) -> Result<ListPromptsResult, McpError> {
+        self.check_enabled(self.prompts_enabled, "Prompts")?;
+        Ok(ListPromptsResult {
+            prompts: self.prompt_router.list_all(),
+            meta: None,
+            next_cursor: None,
+        })
+    }
+
+    fn get_info(&self) -> ServerInfo {
+        let mut capabilities = ServerCapabilities::builder()
+            .enable_prompts()
+            .enable_resources()
+            .enable_tools()
+            .build();
+        if !self.prompts_enabled {
+            capabilities.prompts = None;
+        }
+        if !self.resources_enabled {
+            capabilities.resources = None;
+        }
+        ServerInfo::new(capabilities)
+            .with_protocol_version(ProtocolVersion::V_2025_06_18)
+            .with_server_info(server_implementation())
+            .with_instructions(self.instructions())
+    }
+
+    async fn list_resource_templates(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _: RequestContext<RoleServer>,
+    ) -> Result<ListResourceTemplatesResult, McpError> {
+        info!("Listing resource templates");
+        self.check_enabled(self.resources_enabled, "Resources")?;
+
+        Ok(ListResourceTemplatesResult {
+            resource_templates: vec![
+                ResourceTemplate::new("oeis://sequence/{id}", "OEIS Sequence")
+                    .with_description(
+                        "OEIS sequence data by ID (e.g., A000045); append ?format=markdown for a markdown entry",
+                    )
+                    .with_mime_type("application/json"),
+                ResourceTemplate::new("oeis://bfile/{id}", "OEIS b-file")
+                    .with_description(
+                        "All known terms of an OEIS sequence from its b-file, as index/value pairs",
+                    )
+                    .with_mime_type("application/json"),
+            ],
+            next_cursor: None,
+            meta: None,
+        })
+    }
+
+    #[instrument(
+        skip_all,
+        fields(request_id = %next_request_id(), mcp_request_id = ?context.id, uri = %uri)
+    )]
+    async fn read_resource(
+        &self,
+        ReadResourceRequestParams { uri, .. }: ReadResourceRequestParams,
+        context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, McpError> {
+        info!("Reading resource: {:?}", uri);
+        self.check_enabled(self.resources_enabled, "Resources")?;
+
+        let (text, format) = self.resource_text(&uri).await?;
+        let mut contents = ResourceContents::text(&text, uri);
+        if format == ResourceFormat::Markdown
+            && let ResourceContents::TextResourceContents { mime_type, .. } = &mut contents
+        {
+            *mime_type = Some("text/markdown".to_string());
+        }
+
+        Ok(ReadResourceResult::new(vec![contents]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oeis_client::OEISResult;
+    use async_trait::async_trait;
+    use std::collections::HashMap;
+
+    // Mock OEIS Client for testing
+    #[derive(Clone)]
+    #[allow(clippy::large_enum_variant)]
+    enum MockResponse {
+        // TODO: normalize Option / Vec
+        Success(Option<OEISSequence>),
+        SuccessMulti(SearchResult),
+        Error,
+        Timeout,
+        TooBroad(usize),
+    }
+
+    #[derive(Clone)]
+    struct MockOEISClient {
+        responses: HashMap<String, MockResponse>,
+        bfiles: HashMap<String, Vec<BFileEntry>>,
+        /// Verbatim bodies returned by `find_raw_by_id`
+        raw_bodies: HashMap<String, String>,
+        /// Delay before every `find_by_id` response
+        delay: Option<Duration>,
+    }
+
+    impl MockOEISClient {
+        fn new() -> Self {
+            Self {
+                responses: HashMap::new(),
+                bfiles: HashMap::new(),
+                raw_bodies: HashMap::new(),
+                delay: None,
+            }
+        }
+
+        fn with_delay(mut self, delay: Duration) -> Self {
+            self.delay = Some(delay);
+            self
+        }
+
+        fn with_raw_body(mut self, id: &str, body: &str) -> Self {
+            self.raw_bodies.insert(id.to_string(), body.to_string());
+            self
+        }
+
+        fn with_bfile(mut self, id: &str, entries: Vec<BFileEntry>) -> Self {
+            self.bfiles.insert(id.to_string(), entries);
+            self
+        }
+
+        fn with_sequence(mut self, id: &str, sequence: OEISSequence) -> Self {
+            self.responses
+                .insert(id.to_string(), MockResponse::Success(Some(sequence)));
+            self
+        }
+
+        fn with_sequences(self, subsequence: &[i64], sequences: Vec<OEISSequence>) -> Self {
+            let result = SearchResult {
+                count: sequences.len(),
+                start: 0,
+                sequences,
+            };
+            self.with_search_result(subsequence, result)
+        }
+
+        fn with_signed_sequences(
+            mut self,
+            subsequence: &[i64],
+            sequences: Vec<OEISSequence>,
+        ) -> Self {
+            let key = subsequence
+                .iter()
+                .map(|i| i.to_string())
+                .collect::<Vec<String>>()
+                .join(",");
+            let result = SearchResult {
+                count: sequences.len(),
+                start: 0,
+                sequences,
+            };
+            self.responses.insert(
+                format!("signed:{}", key),
+                MockResponse::SuccessMulti(result),
+            );
+            self
+        }
+
+        /// Register results for a wildcard pattern written as in the query (e.g., "1,_,3")
+        fn with_pattern_sequences(mut self, pattern: &str, sequences: Vec<OEISSequence>) -> Self {
+            let result = SearchResult {
+                count: sequences.len(),
+                start: 0,
+                sequences,
+            };
+            self.responses
+                .insert(pattern.to_string(), MockResponse::SuccessMulti(result));
+            self
+        }
+
+        fn with_search_result(mut self, subsequence: &[i64], result: SearchResult) -> Self {
+            self.responses.insert(
+                subsequence
+                    .iter()
+                    .map(|i| i.to_string())
+                    .collect::<Vec<String>>()
+                    .join(","),
+                MockResponse::SuccessMulti(result),
+            );
+            self
+        }
+
+        fn with_keyword_sequences(mut self, keyword: &str, sequences: Vec<OEISSequence>) -> Self {
+            let result = SearchResult {
+                count: sequences.len(),
+                start: 0,
+                sequences,
+            };
+            self.responses.insert(
+                format!("keyword:{}", keyword),
+                MockResponse::SuccessMulti(result),
+            );
+            self
+        }
+
+        fn with_search_result_for_keyword(
+            mut self,
+            keyword: &str,
+            count: usize,
+            sequences: Vec<OEISSequence>,
+        ) -> Self {
+            let result = SearchResult {
+                count,
+                start: 0,
+                sequences,
+            };
+            self.responses.insert(
+                format!("keyword:{}", keyword),
+                MockResponse::SuccessMulti(result),
+            );
+            self
+        }
+
+        fn with_keyword_page(mut self, keyword: &str, start: usize, result: SearchResult) -> Self {
+            self.responses.insert(
+                format!("keyword:{}@{}", keyword, start),
+                MockResponse::SuccessMulti(result),
+            );
+            self
+        }
+
+        fn with_text_sequences(mut self, query: &str, sequences: Vec<OEISSequence>) -> Self {
+            let result = SearchResult {
+                count: sequences.len(),
+                start: 0,
+                sequences,
+            };
+            self.responses.insert(
+                format!("text:{}", query),
+                MockResponse::SuccessMulti(result),
+            );
+            self
+        }
+
+        fn search_response(&self, key: &str, start: usize) -> OEISResult<SearchResult> {
+            match self.responses.get(key) {
+                Some(MockResponse::SuccessMulti(result)) => Ok(SearchResult {
+                    start,
+                    ..result.clone()
+                }),
+                Some(MockResponse::Success(_)) => Err(OEISError::Deserialize(
+                    "MockOEISClient: use SuccessMulti for searches".to_string(),
+                )),
+                Some(MockResponse::Error) => Err(OEISError::Request("Mock error".to_string())),
+                Some(MockResponse::Timeout) => Err(OEISError::Timeout),
+                Some(MockResponse::TooBroad(count)) => Err(OEISError::TooManyResults(*count)),
+                None => Ok(SearchResult {
+                    start,
+                    ..Default::default()
+                }),
+            }
+        }
+
+        fn with_too_broad(mut self, subsequence: &[i64], count: usize) -> Self {
+            let key = subsequence
+                .iter()
+                .map(|i| i.to_string())
+                .collect::<Vec<String>>()
+                .join(",");
+            self.responses.insert(key, MockResponse::TooBroad(count));
+            self
+        }
+
+        /// Registers the sequence returned by `latest_sequence`
+        fn with_latest_sequence(self, sequence: OEISSequence) -> Self {
+            self.with_sequence("latest", sequence)
+        }
+
+        fn with_named_sequence(mut self, name: &str, sequence: OEISSequence) -> Self {
+            self.responses.insert(
+                format!("name:{}", name),
+                MockResponse::Success(Some(sequence)),
+            );
+            self
+        }
+
+        fn with_not_found(mut self, id: &str) -> Self {
+            self.responses
+                .insert(id.to_string(), MockResponse::Success(None));
+            self
+        }
+
+        fn with_error(mut self, id: &str) -> Self {
+            self.responses.insert(id.to_string(), MockResponse::Error);
+            self
+        }
+
+        fn with_timeout(mut self, id: &str) -> Self {
+            self.responses.insert(id.to_string(), MockResponse::Timeout);
+            self
+        }
+
+        fn find_response(&self, key: &str) -> OEISResult<Option<OEISSequence>> {
+            match self.responses.get(key) {
+                Some(MockResponse::Success(sequence)) => Ok(sequence.clone()),
+                Some(MockResponse::SuccessMulti(_)) => Err(OEISError::Deserialize(
+                    "MockOEISClient: use Success for find lookups".to_string(),
+                )),
+                Some(MockResponse::Error) => Err(OEISError::Request("Mock error".to_string())),
+                Some(MockResponse::Timeout) => Err(OEISError::Timeout),
+                Some(MockResponse::TooBroad(count)) => Err(OEISError::TooManyResults(*count)),
+                None => Ok(None),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl OEISClient for MockOEISClient {
+        async fn find_by_id(&self, id: &str) -> OEISResult<Option<OEISSequence>> {
+            if let Some(delay) = self.delay {
+                tokio::time::sleep(delay).await;
+            }
+            self.find_response(id)
+        }
+
+        async fn find_raw_by_id(&self, id: &str) -> OEISResult<String> {
+            match self.responses.get(id) {
+                Some(MockResponse::Error) => Err(OEISError::Request("Mock error".to_string())),
+                _ => self.raw_bodies.get(id).cloned().ok_or(OEISError::NotFound),
+            }
+        }
+
+        async fn sequence_exists(&self, id: &str) -> OEISResult<bool> {
+            self.find_response(id).map(|result| result.is_some())
+        }
+
+        async fn find_by_name(&self, name: &str) -> OEISResult<Option<OEISSequence>> {
+            self.find_response(&format!("name:{}", name))
+        }
+
+        async fn search_by_subsequence(
+            &self,
+            subsequence: &[i64],
+            start: usize,
+            mode: SignMode,
+            max_results: usize,
+        ) -> OEISResult<SearchResult> {
+            let key = subsequence
+                .iter()
+                .map(|i| i.to_string())
+                .collect::<Vec<String>>()
+                .join(",");
+
+            // Exact-sign searches are keyed as "signed:1,-1" to tell them apart in tests
+            let mut result = match mode {
+                SignMode::Absolute => self.search_response(&key, start),
+                SignMode::Exact => self.search_response(&format!("signed:{}", key), start),
+            }?;
+            result.sequences.truncate(max_results);
+            Ok(result)
+        }
+
+        async fn search_by_pattern(
+            &self,
+            pattern: &[Option<i64>],
+            start: usize,
+            mode: SignMode,
+            max_results: usize,
+        ) -> OEISResult<SearchResult> {
+            let key = pattern
+                .iter()
+                .map(|term| term.map_or_else(|| "_".to_string(), |i| i.to_string()))
+                .collect::<Vec<String>>()
+                .join(",");
+
+            let mut result = match mode {
+                SignMode::Absolute => self.search_response(&key, start),
+                SignMode::Exact => self.search_response(&format!("signed:{}", key), start),
+            }?;
+            result.sequences.truncate(max_results);
+            Ok(result)
+        }
+
+        async fn count_subsequence_matches(&self, subsequence: &[i64]) -> OEISResult<MatchCount> {
+            let key = subsequence
+                .iter()
+                .map(|i| i.to_string())
+                .collect::<Vec<String>>()
+                .join(",");
+
+            // Counting only reads `count`, which OEIS reports even for too-broad queries
+            match self.responses.get(&key) {
+                Some(MockResponse::TooBroad(count)) => Ok(MatchCount {
+                    count: *count,
+                    first_id: None,
+                }),
+                _ => self.search_response(&key, 0).map(|result| MatchCount {
+                    count: result.count,
+                    first_id: result.sequences.first().map(OEISSequence::id),
+                }),
+            }
+        }
+
+        async fn search_by_keyword(
+            &self,
+            keyword: &str,
+            start: usize,
+            max_results: usize,
+        ) -> OEISResult<SearchResult> {
+            // Pages registered with `with_keyword_page` take precedence over the unpaged result
+            let page_key = format!("keyword:{}@{}", keyword, start);
+            let key = if self.responses.contains_key(&page_key) {
+                page_key
+            } else {
+                format!("keyword:{}", keyword)
+            };
+            let mut result = self.search_response(&key, start)?;
+            result.sequences.truncate(max_results);
+            Ok(result)
+        }
+
+        async fn search_text(&self, query: &str) -> OEISResult<SearchResult> {
+            self.search_response(&format!("text:{}", query), 0)
+        }
+
+        async fn fetch_bfile(&self, id: &str) -> OEISResult<Option<Vec<BFileEntry>>> {
+            match self.responses.get(id) {
+                Some(MockResponse::Error) => Err(OEISError::Request("Mock error".to_string())),
+                _ => Ok(self.bfiles.get(id).cloned()),
+            }
+        }
+
+        async fn latest_sequence(&self) -> OEISResult<Option<OEISSequence>> {
+            self.find_response("latest")
+        }
+    }
+
+    fn create_test_sequence(number: i64, name: &str) -> OEISSequence {
+        OEISSequence {
+            number,
+            data: "0, 1, 1, 2, 3, 5, 8".to_string(),
+            name: name.to_string(),
+            offset: Some("0,4".to_string()),
+            comment: Some(vec!["Test comment".to_string()]),
+            formula: Some(vec!["Test formula".to_string()]),
+            xref: Some(vec!["A000001".to_string()]),
+            keyword: "nonn".to_string(),
+            author: Some("_N. J. A. Sloane_".to_string()),
+            ..Default::default()
+        }
+    }
+
+    // test for mock client
+    #[tokio::test]
+    async fn test_mock_client() {
+        // Test that our mock client works correctly
+        let fibonacci = create_test_sequence(45, "Fibonacci numbers");
+        let client = MockOEISClient::new().with_sequence("A000045", fibonacci.clone());
+
+        let result = client.find_by_id("A000045").await.unwrap();
+        assert!(result.is_some());
+
+        let sequence = result.unwrap();
+        assert_eq!(sequence.number, 45);
+        assert_eq!(sequence.name, "Fibonacci numbers");
+    }
+
+    #[tokio::test]
+    async fn test_mock_client_not_found() {
+        let client = MockOEISClient::new().with_not_found("A999999");
+
+        let result = client.find_by_id("A999999").await.unwrap();
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_uri_parsing() {
+        let uri = "oeis://sequence/A000045";
+        let id = uri.strip_prefix("oeis://sequence/");
+        assert_eq!(id, Some("A000045"));
+    }
+
+    #[test]
+    fn test_uri_parsing_invalid() {
+        let invalid_uri = "invalid://uri";
+        let id = invalid_uri.strip_prefix("oeis://sequence/");
+        assert_eq!(id, None);
+    }
+
+    #[test]
+    fn test_normalize_id() {
+        assert_eq!(normalize_id("45").unwrap(), "A000045");
+        assert_eq!(normalize_id("A45").unwrap(), "A000045");
+        assert_eq!(normalize_id("a000045").unwrap(), "A000045");
+        assert_eq!(normalize_id(" A000045 ").unwrap(), "A000045");
+        assert_eq!(normalize_id("A1234567").unwrap(), "A1234567");
+    }
+
+    #[test]
+    fn test_normalize_id_invalid() {
+        for input in ["foo", "", "A", "A-45", "B000045", "A45x"] {
+            let error = normalize_id(input).unwrap_err();
+            assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+            assert!(error.message.contains("Invalid sequence ID"));
+        }
+    }
+
+    #[test]
+    fn test_client_error() {
+        let not_found = client_error(OEISError::NotFound);
+        assert_eq!(not_found.code, ErrorCode::INVALID_PARAMS);
+
+        let rate_limited = client_error(OEISError::RateLimited);
+        assert_eq!(rate_limited.code, ErrorCode::INTERNAL_ERROR);
+        assert_eq!(
+            rate_limited.message,
+            "OEIS rate limit exceeded, try again later"
+        );
+        assert_eq!(
+            rate_limited.data,
+            Some(json!({"status": 429, "retryable": true}))
+        );
+
+        let http = client_error(OEISError::Http(reqwest::StatusCode::SERVICE_UNAVAILABLE));
+        assert_eq!(http.code, ErrorCode::INTERNAL_ERROR);
+        assert_eq!(http.data, Some(json!({"status": 503})));
+
+        let timeout = client_error(OEISError::Timeout);
+        assert_eq!(timeout.message, "OEIS request timed out");
+    }
+
+    #[tokio::test]
+    async fn test_tool_timeout_triggers() {
+        let fibonacci = create_test_sequence(45, "Fibonacci numbers");
+        let oeis = OEIS::new(
+            MockOEISClient::new()
+                .with_sequence("A000045", fibonacci)
+                .with_delay(Duration::from_secs(5)),
+        )
+        .with_tool_timeout(Duration::from_millis(10));
+        let params = Parameters(FindRequest {
+            id: "A000045".to_string(),
+        });
+
+        let error = oeis
+            .time_limited(oeis.find_by_id(params))
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.code, ErrorCode::INTERNAL_ERROR);
+        assert_eq!(error.message, "tool execution timed out");
+    }
+
+    #[tokio::test]
+    async fn test_tool_timeout_passes_fast_calls() {
+        let fibonacci = create_test_sequence(45, "Fibonacci numbers");
+        let oeis = OEIS::new(
+            MockOEISClient::new()
+                .with_sequence("A000045", fibonacci)
+                .with_delay(Duration::from_millis(1)),
+        )
+        .with_tool_timeout(Duration::from_secs(5));
+        let params = Parameters(FindRequest {
+            id: "A000045".to_string(),
+        });
+
+        assert!(oeis.time_limited(oeis.find_by_id(params)).await.is_ok());
+    }
+
+    // test for find_sequence helper
+    #[tokio::test]
+    async fn test_find_sequence_success() {
+        let fibonacci = create_test_sequence(45, "Fibonacci numbers");
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000045", fibonacci.clone()));
+
+        let result = oeis.find_sequence("A000045").await;
+        assert!(result.is_ok());
+
+        let sequence = result.unwrap();
+        assert_eq!(sequence.number, 45);
+        assert_eq!(sequence.name, "Fibonacci numbers");
+    }
+
+    #[tokio::test]
+    async fn test_find_sequence_suggests_neighbors() {
+        let oeis = OEIS::new(
+            MockOEISClient::new().with_sequence("A000999", create_test_sequence(999, "Neighbor")),
+        )
+        .with_suggest_ids(true);
+
+        let error = oeis.find_sequence("A001000").await.unwrap_err();
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+        assert_eq!(
+            error.message,
+            "No sequence found (by id: A001000). Did you mean A000999?"
+        );
+        assert_eq!(error.data, Some(json!({"suggestions": ["A000999"]})));
+    }
+
+    #[tokio::test]
+    async fn test_find_sequence_suggestions_disabled_by_default() {
+        let oeis = OEIS::new(
+            MockOEISClient::new().with_sequence("A000999", create_test_sequence(999, "Neighbor")),
+        );
+
+        let error = oeis.find_sequence("A001000").await.unwrap_err();
+        assert_eq!(error.message, "No sequence found (by id: A001000)");
+        assert_eq!(error.data, None);
+    }
+
+    #[tokio::test]
+    async fn test_find_sequence_normalizes_id() {
+        let fibonacci = create_test_sequence(45, "Fibonacci numbers");
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000045", fibonacci));
+
+        let result = oeis.find_sequence("a45").await;
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().number, 45);
+    }
+
+    #[tokio::test]
+    async fn test_find_sequence_invalid_id() {
+        let oeis = OEIS::new(MockOEISClient::new());
+
+        let result = oeis.find_sequence("foo").await;
+        assert!(result.is_err());
+
+        let error = result.unwrap_err();
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+        assert!(error.message.contains("Invalid sequence ID"));
+    }
+
+    #[tokio::test]
+    async fn test_find_sequence_not_found() {
+        let oeis = OEIS::new(MockOEISClient::new().with_not_found("A999999"));
+
+        let result = oeis.find_sequence("A999999").await;
+        assert!(result.is_err());
+
+        let error = result.unwrap_err();
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+        assert!(error.message.contains("No sequence found"));
+    }
+
+    #[tokio::test]
+    async fn test_find_sequence_error() {
+        let oeis = OEIS::new(MockOEISClient::new().with_error("A000500"));
+
+        let result = oeis.find_sequence("A000500").await;
+        assert!(result.is_err());
+
+        let error = result.unwrap_err();
+        assert_eq!(error.code, ErrorCode::INTERNAL_ERROR);
+    }
+
+    #[tokio::test]
+    async fn test_find_sequence_timeout() {
+        let oeis = OEIS::new(MockOEISClient::new().with_timeout("A000045"));
+
+        let result = oeis.find_sequence("A000045").await;
+        assert!(result.is_err());
+
+        let error = result.unwrap_err();
+        assert_eq!(error.code, ErrorCode::INTERNAL_ERROR);
+        assert_eq!(error.message, "OEIS request timed out");
+    }
+
+    fn bfile_entries(values: &[i64]) -> Vec<BFileEntry> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(index, value)| BFileEntry {
+                index: index as i64,
+                value: value.to_string(),
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_get_bfile_tool_paging() {
+        let entries = bfile_entries(&[0, 1, 1, 2, 3, 5, 8]);
+        let oeis = OEIS::new(MockOEISClient::new().with_bfile("A000045", entries.clone()));
+        let page = |start, count| {
+            Parameters(GetBFileRequest {
+                id: "45".to_string(),
+                start,
+                count,
+            })
+        };
+
+        let result = oeis.get_bfile(page(0, 3)).await.unwrap();
+        assert_eq!(
+            result.content.first().unwrap(),
+            &ContentBlock::json(json!(GetBFileResponse {
+                id: "A000045".to_string(),
+                total: 7,
+                start: 0,
+                has_more: true,
+                entries: entries[0..3].to_vec(),
+            }))
+            .unwrap()
+        );
+
+        // The last page ends exactly at the end of the b-file
+        let structured = oeis.get_bfile(page(4, 3)).await.unwrap().structured_content;
+        let structured = structured.unwrap();
+        assert_eq!(structured["has_more"], false);
+        assert_eq!(structured["entries"], json!(entries[4..7]));
+
+        // A page running past the end is truncated
+        let structured = oeis.get_bfile(page(5, 3)).await.unwrap().structured_content;
+        let structured = structured.unwrap();
+        assert_eq!(structured["has_more"], false);
+        assert_eq!(structured["entries"], json!(entries[5..7]));
+    }
+
+    #[tokio::test]
+    async fn test_get_bfile_tool_start_past_end() {
+        let oeis =
+            OEIS::new(MockOEISClient::new().with_bfile("A000045", bfile_entries(&[0, 1, 1, 2])));
+
+        let result = oeis
+            .get_bfile(Parameters(GetBFileRequest {
+                id: "A000045".to_string(),
+                start: 10,
+                count: 5,
+            }))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            result.content.first().unwrap(),
+            &ContentBlock::json(json!(GetBFileResponse {
+                id: "A000045".to_string(),
+                total: 4,
+                start: 10,
+                has_more: false,
+                entries: vec![],
+            }))
+            .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_bfile_tool_clamps_count() {
+        let values: Vec<i64> = (0..MAX_BFILE_PAGE_SIZE as i64 + 5).collect();
+        let oeis = OEIS::new(MockOEISClient::new().with_bfile("A000027", bfile_entries(&values)));
+
+        let result = oeis
+            .get_bfile(Parameters(GetBFileRequest {
+                id: "A000027".to_string(),
+                start: 0,
+                count: usize::MAX,
+            }))
+            .await
+            .unwrap();
+
+        let structured = result.structured_content.unwrap();
+        assert_eq!(
+            structured["entries"].as_array().unwrap().len(),
+            MAX_BFILE_PAGE_SIZE
+        );
+        assert_eq!(structured["has_more"], true);
+    }
+
+    #[tokio::test]
+    async fn test_get_bfile_tool_zero_count() {
+        let oeis = OEIS::new(MockOEISClient::new().with_bfile("A000045", bfile_entries(&[0])));
+
+        let error = oeis
+            .get_bfile(Parameters(GetBFileRequest {
+                id: "A000045".to_string(),
+                start: 0,
+                count: 0,
+            }))
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+    }
+
+    // test for find_bfile helper
+    #[tokio::test]
+    async fn test_find_bfile_success() {
+        let entries = vec![
+            BFileEntry {
+                index: 0,
+                value: "0".to_string(),
+            },
+            BFileEntry {
+                index: 1,
+                value: "1".to_string(),
+            },
+        ];
+        let oeis = OEIS::new(MockOEISClient::new().with_bfile("A000045", entries.clone()));
+
+        let result = oeis.find_bfile("45").await;
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), entries);
+    }
+
+    #[tokio::test]
+    async fn test_find_bfile_not_found() {
+        let oeis = OEIS::new(MockOEISClient::new());
+
+        let result = oeis.find_bfile("A999999").await;
+        assert!(result.is_err());
+
+        let error = result.unwrap_err();
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+        assert!(error.message.contains("No b-file found"));
+    }
+
+    #[tokio::test]
+    async fn test_find_bfile_error() {
+        let oeis = OEIS::new(MockOEISClient::new().with_error("A000500"));
+
+        let result = oeis.find_bfile("A000500").await;
+        assert!(result.is_err());
+
+        let error = result.unwrap_err();
+        assert_eq!(error.code, ErrorCode::INTERNAL_ERROR);
+    }
+
+    // helpers for tool router definition checking
+    fn get_tool(tools: &[Tool], name: &str) -> Option<Tool> {
+        tools.iter().find(|t| t.name == name).cloned()
+    }
+
+    fn get_tool_description(tool: Tool) -> String {
+        tool.description.as_ref().unwrap().clone().into_owned()
+    }
+
+    #[test]
+    fn test_tool_output_schemas() {
+        let oeis = OEIS::new(MockOEISClient::new());
+        let tools = oeis.tool_router.list_all();
+
+        let find_by_id_tool = get_tool(&tools, "find_by_id").unwrap();
+        let schema = find_by_id_tool.output_schema.unwrap();
+        assert!(!schema.is_empty());
+        assert_eq!(schema, output_schema::<FindResponse>());
+        assert_eq!(schema["type"], "object");
+        assert!(schema["properties"]["result"].is_object());
+
+        // Tools answering with plain text advertise no schema
+        assert!(get_tool(&tools, "get_url").unwrap().output_schema.is_none());
+    }
+
+    #[test]
+    fn test_get_info_capabilities() {
+        let info = OEIS::new(MockOEISClient::new()).get_info();
+
+        assert!(info.capabilities.tools.is_some());
+        assert!(info.capabilities.prompts.is_some());
+        assert!(info.capabilities.resources.is_some());
+        let instructions = info.instructions.unwrap();
+        assert!(instructions.contains("Prompts: sequence_analysis"));
+        assert!(instructions.contains("Resources: oeis://sequence/{id}"));
+    }
+
+    #[test]
+    fn test_get_info_capabilities_disabled() {
+        let oeis = OEIS::new(MockOEISClient::new())
+            .with_prompts(false)
+            .with_resources(false);
+
+        let info = oeis.get_info();
+
+        assert!(info.capabilities.tools.is_some());
+        assert!(info.capabilities.prompts.is_none());
+        assert!(info.capabilities.resources.is_none());
+        let instructions = info.instructions.unwrap();
+        assert!(instructions.contains("Tools: get_url"));
+        assert!(!instructions.contains("Prompts:"));
+        assert!(!instructions.contains("Resources:"));
+    }
+
+    #[test]
+    fn test_check_enabled() {
+        let oeis = OEIS::new(MockOEISClient::new()).with_prompts(false);
+
+        assert!(
+            oeis.check_enabled(oeis.resources_enabled, "Resources")
+                .is_ok()
+        );
+
+        let error = oeis
+            .check_enabled(oeis.prompts_enabled, "Prompts")
+            .unwrap_err();
+        assert_eq!(error.code, ErrorCode::METHOD_NOT_FOUND);
+        assert_eq!(error.message, "Prompts are not supported by this server");
+    }
+
+    #[test]
+    fn test_tool_router_definition() {
+        let oeis = OEIS::new(MockOEISClient::new());
+
+        let tools = oeis.tool_router.list_all();
+        assert!(tools.len() == 47);
+
+        let get_url_tool = get_tool(&tools, "get_url");
+        assert!(get_url_tool.is_some());
+        assert!(get_tool_description(get_url_tool.unwrap()) == "Get a URL of OEIS entry.");
+
+        let find_by_id_tool = get_tool(&tools, "find_by_id");
+        assert!(find_by_id_tool.is_some());
+        assert!(get_tool_description(find_by_id_tool.unwrap()) == "Find a sequence by its ID.");
+
+        let search_by_subsequence_tool = get_tool(&tools, "search_by_subsequence");
+        assert!(search_by_subsequence_tool.is_some());
+        assert!(
+            get_tool_description(search_by_subsequence_tool.unwrap())
+                == "Search sequences by subsequence."
+        );
+
+        let is_known_sequence_tool = get_tool(&tools, "is_known_sequence");
+        assert!(is_known_sequence_tool.is_some());
+        assert!(
+            get_tool_description(is_known_sequence_tool.unwrap())
+                == "Check whether a subsequence matches any sequence in OEIS."
+        );
+
+        let get_terms_tool = get_tool(&tools, "get_terms");
+        assert!(get_terms_tool.is_some());
+        assert!(
+            get_tool_description(get_terms_tool.unwrap()) == "Get the first N terms of a sequence."
+        );
+
+        let search_by_keyword_tool = get_tool(&tools, "search_by_keyword");
+        assert!(search_by_keyword_tool.is_some());
+        assert!(
+            get_tool_description(search_by_keyword_tool.unwrap())
+                == "Search sequences by OEIS keyword."
+        );
+
+        let search_text_tool = get_tool(&tools, "search_text");
+        assert!(search_text_tool.is_some());
+        assert!(
+            get_tool_description(search_text_tool.unwrap()) == "Search sequences by free text."
+        );
+
+        let resolve_xrefs_tool = get_tool(&tools, "resolve_xrefs");
+        assert!(resolve_xrefs_tool.is_some());
+        assert!(
+            get_tool_description(resolve_xrefs_tool.unwrap())
+                == "Resolve a sequence's cross-references to sequence names."
+        );
+
+        let sequence_differences_tool = get_tool(&tools, "sequence_differences");
+        assert!(sequence_differences_tool.is_some());
+        assert!(
+            get_tool_description(sequence_differences_tool.unwrap())
+                == "Compute the n-th finite difference of an OEIS sequence's terms."
+        );
+
+        let format_entry_tool = get_tool(&tools, "format_entry");
+        assert!(format_entry_tool.is_some());
+        assert!(
+            get_tool_description(format_entry_tool.unwrap())
+                == "Render a sequence as an OEIS-style text entry (%S, %N, %C, %F, %Y, %K lines)."
+        );
+
+        let find_many_tool = get_tool(&tools, "find_many");
+        assert!(find_many_tool.is_some());
+        assert!(
+            get_tool_description(find_many_tool.unwrap())
+                == "Find several sequences by ID at once."
+        );
+
+        let get_programs_tool = get_tool(&tools, "get_programs");
+        assert!(get_programs_tool.is_some());
+        assert!(
+            get_tool_description(get_programs_tool.unwrap())
+                == "Get the Maple, Mathematica, and other programs that generate a sequence."
+        );
+
+        let find_by_name_tool = get_tool(&tools, "find_by_name");
+        assert!(find_by_name_tool.is_some());
+        assert!(
+            get_tool_description(find_by_name_tool.unwrap())
+                == "Find a sequence by its exact name."
+        );
+
+        let sequence_plot_data_tool = get_tool(&tools, "sequence_plot_data");
+        assert!(sequence_plot_data_tool.is_some());
+        assert!(
+            get_tool_description(sequence_plot_data_tool.unwrap())
+                == "Get (n, a(n)) pairs of a sequence, ready for plotting."
+        );
+
+        let random_sequence_tool = get_tool(&tools, "random_sequence");
+        assert!(random_sequence_tool.is_some());
+        assert!(
+            get_tool_description(random_sequence_tool.unwrap())
+                == "Get a random sequence tagged with an OEIS keyword (\"nice\" by default)."
+        );
+
+        let verify_recurrence_tool = get_tool(&tools, "verify_recurrence");
+        assert!(verify_recurrence_tool.is_some());
+        assert!(
+            get_tool_description(verify_recurrence_tool.unwrap())
+                == "Check whether a linear recurrence a(n) = c0*a(n-1) + c1*a(n-2) + ... holds across a sequence's terms."
+        );
+
+        let neighbors_tool = get_tool(&tools, "neighbors");
+        assert!(neighbors_tool.is_some());
+        assert!(
+            get_tool_description(neighbors_tool.unwrap())
+                == "Get the sequences with adjacent A-numbers (e.g., A000044 and A000046 for A000045)."
+        );
+
+        let sequence_keywords_tool = get_tool(&tools, "sequence_keywords");
+        assert!(sequence_keywords_tool.is_some());
+        assert!(
+            get_tool_description(sequence_keywords_tool.unwrap())
+                == "Get the OEIS keywords of a sequence as named booleans."
+        );
+
+        let search_generating_function_tool = get_tool(&tools, "search_generating_function");
+        assert!(search_generating_function_tool.is_some());
+        assert!(
+            get_tool_description(search_generating_function_tool.unwrap())
+                == "Search sequences whose formulas mention a generating function expression."
+        );
+
+        let transform_sequence_tool = get_tool(&tools, "transform_sequence");
+        assert!(transform_sequence_tool.is_some());
+        assert!(
+            get_tool_description(transform_sequence_tool.unwrap())
+                == "Apply partial sums, differences, running products, or negation to a sequence's terms."
+        );
+
+        let search_ranked_tool = get_tool(&tools, "search_ranked");
+        assert!(search_ranked_tool.is_some());
+        assert!(
+            get_tool_description(search_ranked_tool.unwrap())
+                == "Search sequences by subsequence, ranking those that start with the given terms first."
+        );
+
+        let term_at_tool = get_tool(&tools, "term_at");
+        assert!(term_at_tool.is_some());
+        assert!(
+            get_tool_description(term_at_tool.unwrap())
+                == "Get the term a(n) of a sequence, honoring its offset."
+        );
+
+        let list_keywords_tool = get_tool(&tools, "list_keywords");
+        assert!(list_keywords_tool.is_some());
+        assert!(
+            get_tool_description(list_keywords_tool.unwrap())
+                == "List the OEIS keywords with a short description of each."
+        );
+
+        let export_csv_tool = get_tool(&tools, "export_csv");
+        assert!(export_csv_tool.is_some());
+        assert!(
+            get_tool_description(export_csv_tool.unwrap())
+                == "Export a sequence as CSV with index,value columns, honoring its offset."
+        );
+
+        let sequence_stats_tool = get_tool(&tools, "sequence_stats");
+        assert!(sequence_stats_tool.is_some());
+        assert!(
+            get_tool_description(sequence_stats_tool.unwrap())
+                == "Get the count, min, max, sum, and monotonicity of a sequence's terms."
+        );
+
+        let sequence_links_tool = get_tool(&tools, "sequence_links");
+        assert!(sequence_links_tool.is_some());
+        assert!(
+            get_tool_description(sequence_links_tool.unwrap())
+                == "Get resource links for a sequence: its b-file, its OEIS web page, and its cross-referenced sequences."
+        );
+
+        let triangle_rows_tool = get_tool(&tools, "triangle_rows");
+        assert!(triangle_rows_tool.is_some());
+        assert!(
+            get_tool_description(triangle_rows_tool.unwrap())
+                == "Split a triangle sequence (OEIS keyword tabl, e.g., Pascal's triangle) into its rows."
+        );
+
+        let sequence_diff_tool = get_tool(&tools, "sequence_diff");
+        assert!(sequence_diff_tool.is_some());
+        assert!(
+            get_tool_description(sequence_diff_tool.unwrap())
+                == "Compare two sequences term by term over the indices n they share, honoring their offsets."
+        );
+
+        let search_by_term_at_tool = get_tool(&tools, "search_by_term_at");
+        assert!(search_by_term_at_tool.is_some());
+        assert!(
+            get_tool_description(search_by_term_at_tool.unwrap())
+                == "Find sequences whose term a(n) equals a value, honoring each sequence's offset."
+        );
+
+        let format_terms_tool = get_tool(&tools, "format_terms");
+        assert!(format_terms_tool.is_some());
+        assert!(
+            get_tool_description(format_terms_tool.unwrap())
+                == "Get a sequence's terms as strings, optionally with thousands separators (e.g., 1,234,567) for readability."
+        );
+
+        let search_by_pattern_tool = get_tool(&tools, "search_by_pattern");
+        assert!(search_by_pattern_tool.is_some());
+        assert!(
+            get_tool_description(search_by_pattern_tool.unwrap())
+                == "Search sequences by a subsequence pattern where null terms are wildcards, for when some terms are unknown."
+        );
+
+        let get_comments_tool = get_tool(&tools, "get_comments");
+        assert!(get_comments_tool.is_some());
+        assert!(
+            get_tool_description(get_comments_tool.unwrap())
+                == "Get the raw comments of a sequence, one entry per line, without any formatting."
+        );
+
+        let consecutive_ratios_tool = get_tool(&tools, "consecutive_ratios");
+        assert!(consecutive_ratios_tool.is_some());
+        assert!(
+            get_tool_description(consecutive_ratios_tool.unwrap())
+                == "Get the ratios a(n+1)/a(n) of consecutive terms of a sequence as floating-point values, for convergence analysis."
+        );
+
+        let contains_value_tool = get_tool(&tools, "contains_value");
+        assert!(contains_value_tool.is_some());
+        assert!(
+            get_tool_description(contains_value_tool.unwrap())
+                == "Check whether a value appears among a sequence's known terms and at which indices. Only the terms in the sequence's data are checked, or its b-file when bfile is true."
+        );
+
+        let raw_lookup_tool = get_tool(&tools, "raw_lookup");
+        assert!(raw_lookup_tool.is_some());
+        assert!(
+            get_tool_description(raw_lookup_tool.unwrap())
+                == "Get the verbatim OEIS JSON response for a sequence ID, for debugging or reading fields this server does not model."
+        );
+
+        let suggest_sequences_tool = get_tool(&tools, "suggest_sequences");
+        assert!(suggest_sequences_tool.is_some());
+        assert!(
+            get_tool_description(suggest_sequences_tool.unwrap())
+                == "Suggest sequences matching a natural-language description, ranking well-known (core, nice) sequences first."
+        );
+
+        let extract_formulas_tool = get_tool(&tools, "extract_formulas");
+        assert!(extract_formulas_tool.is_some());
+        assert!(
+            get_tool_description(extract_formulas_tool.unwrap())
+                == "Get a sequence's formulas grouped into generating functions, exponential generating functions, recurrences, closed forms, and other."
+        );
+
+        let lookup_by_nickname_tool = get_tool(&tools, "lookup_by_nickname");
+        assert!(lookup_by_nickname_tool.is_some());
+        assert!(
+            get_tool_description(lookup_by_nickname_tool.unwrap())
+                == "Resolve the A-number of a sequence from a common nickname like \"Catalan numbers\", falling back to an OEIS name search."
+        );
+
+        let media_links_tool = get_tool(&tools, "media_links");
+        assert!(media_links_tool.is_some());
+        assert!(
+            get_tool_description(media_links_tool.unwrap())
+                == "Get the URLs of a sequence's OEIS listen (play as music) and graph pages."
+        );
+
+        let validate_ids_tool = get_tool(&tools, "validate_ids");
+        assert!(validate_ids_tool.is_some());
+        assert!(
+            get_tool_description(validate_ids_tool.unwrap())
+                == "Check whether each of several sequence IDs exists in OEIS."
+        );
+
+        let is_subsequence_of_tool = get_tool(&tools, "is_subsequence_of");
+        assert!(is_subsequence_of_tool.is_some());
+        assert!(
+            get_tool_description(is_subsequence_of_tool.unwrap())
+                == "Check whether the terms of one sequence appear in another, contiguously or scattered, and where."
+        );
+
+        let referencing_sequences_tool = get_tool(&tools, "referencing_sequences");
+        assert!(referencing_sequences_tool.is_some());
+        assert!(
+            get_tool_description(referencing_sequences_tool.unwrap())
+                == "List the sequences that mention a sequence (back-references), alongside the sequences it cross-references itself."
+        );
+
+        let get_bfile_tool = get_tool(&tools, "get_bfile");
+        assert!(get_bfile_tool.is_some());
+        assert!(
+            get_tool_description(get_bfile_tool.unwrap())
+                == "Page through the b-file (all known terms) of a sequence as index/value entries."
+        );
+
+        let detect_progression_tool = get_tool(&tools, "detect_progression");
+        assert!(detect_progression_tool.is_some());
+        assert!(
+            get_tool_description(detect_progression_tool.unwrap())
+                == "Detect whether a sequence's terms form an arithmetic or geometric progression, with its common difference or ratio."
+        );
+
+        let terms_mod_tool = get_tool(&tools, "terms_mod");
+        assert!(terms_mod_tool.is_some());
+        assert!(
+            get_tool_description(terms_mod_tool.unwrap())
+                == "Get a sequence's terms reduced modulo m, as non-negative residues (e.g., Fibonacci mod 10)."
+        );
+
+        let sequence_age_context_tool = get_tool(&tools, "sequence_age_context");
+        assert!(sequence_age_context_tool.is_some());
+        assert!(
+            get_tool_description(sequence_age_context_tool.unwrap())
+                == "Describe how old a sequence is: its creation date and where its A-number falls relative to the newest OEIS entries."
+        );
+
+        let server_status_tool = get_tool(&tools, "server_status");
+        assert!(server_status_tool.is_some());
+        assert!(
+            get_tool_description(server_status_tool.unwrap())
+                == "Get the server's name, version, and uptime, and whether OEIS is reachable."
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_url_tool() {
+        let oeis = OEIS::new(MockOEISClient::new());
+
+        let result = oeis.get_url(Parameters(EmptyRequest {})).await;
+        assert!(result.is_ok());
+
+        let content = result.unwrap().content;
+        assert_eq!(content.len(), 1);
+        assert_eq!(
+            content.first().unwrap(),
+            &ContentBlock::text("https://oeis.org")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_find_by_id_tool_found() {
+        let fibonacci = create_test_sequence(45, "Fibonacci numbers");
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000045", fibonacci.clone()));
+        let params = Parameters(FindRequest {
+            id: "A000045".to_string(),
+        });
+
+        let result = oeis.find_by_id(params).await;
+        assert!(result.is_ok());
+
+        let content = result.unwrap().content;
+        assert_eq!(content.len(), 1);
+
+        assert_eq!(
+            content.first().unwrap(),
+            &ContentBlock::json(json!(FindResponse { result: fibonacci })).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_find_by_id_tool_not_found() {
+        let oeis = OEIS::new(MockOEISClient::new().with_not_found("A999999"));
+        let params = Parameters(FindRequest {
+            id: "A999999".to_string(),
+        });
+
+        let result = oeis.find_by_id(params).await;
+        assert!(result.is_err());
+
+        let error = result.unwrap_err();
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+        assert!(error.message.contains("No sequence found"));
+    }
+
+    #[tokio::test]
+    async fn test_find_by_id_tool_error() {
+        let oeis = OEIS::new(MockOEISClient::new().with_error("A000500"));
+        let params = Parameters(FindRequest {
+            id: "A000500".to_string(),
+        });
+
+        let result = oeis.find_by_id(params).await;
+        assert!(result.is_err());
+
+        let error = result.unwrap_err();
+        assert_eq!(error.code, ErrorCode::INTERNAL_ERROR);
+    }
+
+    #[tokio::test]
+    async fn test_find_by_name_tool_found() {
+        let fibonacci = create_test_sequence(45, "Fibonacci numbers");
+        let oeis = OEIS::new(
+            MockOEISClient::new().with_named_sequence("Fibonacci numbers", fibonacci.clone()),
+        );
+        let params = Parameters(FindByNameRequest {
+            name: "  Fibonacci numbers ".to_string(),
+        });
+
+        let result = oeis.find_by_name(params).await;
+        assert!(result.is_ok());
+
+        let content = result.unwrap().content;
+        assert_eq!(
+            content.first().unwrap(),
+            &ContentBlock::json(json!(FindByNameResponse {
+                result: Some(fibonacci)
+            }))
+            .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_find_by_name_tool_no_match() {
+        let oeis = OEIS::new(MockOEISClient::new());
+        let params = Parameters(FindByNameRequest {
+            name: "Not a sequence name".to_string(),
+        });
+
+        let result = oeis.find_by_name(params).await;
+        assert!(result.is_ok());
+
+        let content = result.unwrap().content;
+        assert_eq!(
+            content.first().unwrap(),
+            &ContentBlock::json(json!(FindByNameResponse { result: None })).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_find_by_name_tool_empty_name() {
+        let oeis = OEIS::new(MockOEISClient::new());
+        let params = Parameters(FindByNameRequest {
+            name: "   ".to_string(),
+        });
+
+        let result = oeis.find_by_name(params).await;
+        assert!(result.is_err());
+
+        let error = result.unwrap_err();
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+    }
+
+    #[tokio::test]
+    async fn test_find_many_tool_partial_failures() {
+        let fibonacci = create_test_sequence(45, "Fibonacci numbers");
+        let lucas = create_test_sequence(32, "Lucas numbers");
+        let oeis = OEIS::new(
+            MockOEISClient::new()
+                .with_sequence("A000045", fibonacci.clone())
+                .with_sequence("A000032", lucas.clone())
+                .with_not_found("A999999")
+                .with_error("A000500"),
+        );
+        let params = Parameters(FindManyRequest {
+            ids: vec![
+                "A000045".to_string(),
+                "A999999".to_string(),
+                "A000500".to_string(),
+                "not-an-id".to_string(),
+                "32".to_string(),
+            ],
+            concurrency: Some(2),
+        });
+
+        let result = oeis.find_many(params).await;
+        assert!(result.is_ok());
+
+        let content = result.unwrap().content;
+        assert_eq!(
+            content.first().unwrap(),
+            &ContentBlock::json(json!(FindManyResponse {
+                results: vec![
+                    FindManyEntry {
+                        id: "A000045".to_string(),
+                        result: Some(fibonacci),
+                        error: None,
+                    },
+                    FindManyEntry {
+                        id: "A999999".to_string(),
+                        result: None,
+                        error: None,
+                    },
+                    FindManyEntry {
+                        id: "A000500".to_string(),
+                        result: None,
+                        error: Some(OEISError::Request("Mock error".to_string()).to_string(),),
+                    },
+                    FindManyEntry {
+                        id: "not-an-id".to_string(),
+                        result: None,
+                        error: Some(invalid_id_error("not-an-id").message.to_string()),
+                    },
+                    FindManyEntry {
+                        id: "32".to_string(),
+                        result: Some(lucas),
+                        error: None,
+                    },
+                ],
+            }))
+            .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_find_many_tool_too_many_ids() {
+        let oeis = OEIS::new(MockOEISClient::new());
+        let params = Parameters(FindManyRequest {
+            ids: vec!["A000045".to_string(); DEFAULT_MAX_FIND_MANY_IDS + 1],
+            concurrency: None,
+        });
+
+        let result = oeis.find_many(params).await;
+        assert!(result.is_err());
+
+        let error = result.unwrap_err();
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+    }
+
+    #[tokio::test]
+    async fn test_find_many_tool_configured_limit_boundary() {
+        let oeis = OEIS::new(MockOEISClient::new()).with_limits(InputLimits {
+            max_find_many_ids: 2,
+            ..Default::default()
+        });
+        let request = |count| {
+            Parameters(FindManyRequest {
+                ids: vec!["A000045".to_string(); count],
+                concurrency: None,
+            })
+        };
+
+        assert!(oeis.find_many(request(2)).await.is_ok());
+
+        let error = oeis.find_many(request(3)).await.unwrap_err();
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+        assert_eq!(error.message, "Too many IDs: 3 (max: 2)");
+        assert_eq!(error.data, Some(json!({"count": 3, "max": 2})));
+    }
+
+    #[tokio::test]
+    async fn test_validate_ids_tool() {
+        let oeis = OEIS::new(
+            MockOEISClient::new()
+                .with_sequence("A000045", create_test_sequence(45, "Fibonacci numbers"))
+                .with_not_found("A999999")
+                .with_error("A000500"),
+        );
+
+        let result = oeis
+            .validate_ids(Parameters(ValidateIdsRequest {
+                ids: vec![
+                    "45".to_string(),
+                    "not-an-id".to_string(),
+                    "A999999".to_string(),
+                    "A000500".to_string(),
+                ],
+            }))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            result.content.first().unwrap(),
+            &ContentBlock::json(json!(ValidateIdsResponse {
+                results: vec![
+                    IdValidity {
+                        id: "45".to_string(),
+                        exists: true,
+                        error: None,
+                    },
+                    IdValidity {
+                        id: "not-an-id".to_string(),
+                        exists: false,
+                        error: Some(invalid_id_error("not-an-id").message.to_string()),
+                    },
+                    IdValidity {
+                        id: "A999999".to_string(),
+                        exists: false,
+                        error: None,
+                    },
+                    IdValidity {
+                        id: "A000500".to_string(),
+                        exists: false,
+                        error: Some(OEISError::Request("Mock error".to_string()).to_string()),
+                    },
+                ],
+            }))
+            .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_validate_ids_tool_too_many_ids() {
+        let oeis = OEIS::new(MockOEISClient::new()).with_limits(InputLimits {
+            max_find_many_ids: 2,
+            ..Default::default()
+        });
+
+        let error = oeis
+            .validate_ids(Parameters(ValidateIdsRequest {
+                ids: vec!["A000045".to_string(); 3],
+            }))
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+        assert_eq!(error.message, "Too many IDs: 3 (max: 2)");
+    }
+
+    #[tokio::test]
+    async fn test_search_by_subsequence_tool_found() {
+        let fibonacci = create_test_sequence(45, "Fibonacci numbers");
+        let catalan = create_test_sequence(108, "Catalan numbers");
+        let sequences = vec![fibonacci.clone(), catalan.clone()];
+
+        let oeis = OEIS::new(
+            MockOEISClient::new().with_sequences(&[0, 1, 1, 2, 3, 5, 8], sequences.clone()),
+        );
+        let params = Parameters(SearchRequest {
+            subsequence: vec![0, 1, 1, 2, 3, 5, 8],
+            start: 0,
+            mode: SignMode::Absolute,
+            max_results: None,
+        });
+
+        let result = oeis.search_by_subsequence(params).await;
+        assert!(result.is_ok());
+
+        let content = result.unwrap().content;
+        assert_eq!(content.len(), 1);
+
+        assert_eq!(
+            content.first().unwrap(),
+            &ContentBlock::json(json!(SearchResponse {
+                count: 2,
+                start: 0,
+                has_more: false,
+                results: sequences
+            }))
+            .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_by_subsequence_tool_exact_mode() {
+        let alternating = create_test_sequence(33999, "a(n) = (-1)^n.");
+        let oeis = OEIS::new(
+            MockOEISClient::new()
+                .with_signed_sequences(&[1, -1, 1], vec![alternating.clone()])
+                .with_sequences(&[1, -1, 1], vec![]),
+        );
+        let params = Parameters(SearchRequest {
+            subsequence: vec![1, -1, 1],
+            start: 0,
+            mode: SignMode::Exact,
+            max_results: None,
+        });
+
+        let result = oeis.search_by_subsequence(params).await.unwrap();
+
+        let structured = result.structured_content.unwrap();
+        assert_eq!(structured["count"], 1);
+        assert_eq!(structured["results"][0]["number"], 33999);
+    }
+
+    #[tokio::test]
+    async fn test_search_by_subsequence_tool_default_max_results() {
+        let sequences: Vec<OEISSequence> = (1..=25)
+            .map(|n| create_test_sequence(n, "Test sequence"))
+            .collect();
+        let oeis = OEIS::new(MockOEISClient::new().with_sequences(&[1, 2, 3], sequences));
+        let params = Parameters(SearchRequest {
+            subsequence: vec![1, 2, 3],
+            start: 0,
+            mode: SignMode::Absolute,
+            max_results: None,
+        });
+
+        let result = oeis.search_by_subsequence(params).await.unwrap();
+
+        let structured = result.structured_content.unwrap();
+        assert_eq!(
+            structured["results"].as_array().unwrap().len(),
+            DEFAULT_MAX_RESULTS
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_by_subsequence_tool_max_results_clamped() {
+        let sequences: Vec<OEISSequence> = (1..=150)
+            .map(|n| create_test_sequence(n, "Test sequence"))
+            .collect();
+        let oeis = OEIS::new(MockOEISClient::new().with_sequences(&[1, 2, 3], sequences));
+        let params = Parameters(SearchRequest {
+            subsequence: vec![1, 2, 3],
+            start: 0,
+            mode: SignMode::Absolute,
+            max_results: Some(500),
+        });
+
+        let result = oeis.search_by_subsequence(params).await.unwrap();
+
+        let structured = result.structured_content.unwrap();
+        assert_eq!(structured["count"], 150);
+        assert_eq!(
+            structured["results"].as_array().unwrap().len(),
+            MAX_SEARCH_RESULTS
+        );
+        assert_eq!(structured["has_more"], true);
+    }
+
+    #[test]
+    fn test_search_request_mode_defaults_to_absolute() {
+        let request: SearchRequest =
+            serde_json::from_value(json!({"subsequence": [1, 2, 3]})).unwrap();
+        assert_eq!(request.mode, SignMode::Absolute);
+
+        let request: SearchRequest =
+            serde_json::from_value(json!({"subsequence": [1, -2], "mode": "exact"})).unwrap();
+        assert_eq!(request.mode, SignMode::Exact);
+    }
+
+    #[tokio::test]
+    async fn test_search_by_subsequence_tool_total_count() {
+        let fibonacci = create_test_sequence(45, "Fibonacci numbers");
+        let oeis = OEIS::new(MockOEISClient::new().with_search_result(
+            &[1, 2, 3],
+            SearchResult {
+                count: 1234,
+                start: 0,
+                sequences: vec![fibonacci.clone()],
+            },
+        ));
+        let params = Parameters(SearchRequest {
+            subsequence: vec![1, 2, 3],
+            start: 10,
+            mode: SignMode::Absolute,
+            max_results: None,
+        });
+
+        let result = oeis.search_by_subsequence(params).await;
+        assert!(result.is_ok());
+
+        let content = result.unwrap().content;
+        assert_eq!(
+            content.first().unwrap(),
+            &ContentBlock::json(json!(SearchResponse {
+                count: 1234,
+                start: 10,
+                has_more: true,
+                results: vec![fibonacci]
+            }))
+            .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_by_subsequence_tool_too_broad() {
+        let oeis = OEIS::new(MockOEISClient::new().with_too_broad(&[1, 2], 183412));
+        let params = Parameters(SearchRequest {
+            subsequence: vec![1, 2],
+            start: 0,
+            mode: SignMode::Absolute,
+            max_results: None,
+        });
+
+        let error = oeis.search_by_subsequence(params).await.unwrap_err();
+
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+        assert!(error.message.contains("too many sequences"));
+        assert!(error.message.contains("add more terms"));
+        assert_eq!(
+            error.data,
+            Some(json!({"count": 183412, "too_broad": true}))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_by_subsequence_tool_not_found() {
+        let oeis = OEIS::new(MockOEISClient::new());
+        let params = Parameters(SearchRequest {
+            subsequence: vec![999, 888, 777],
+            start: 0,
+            mode: SignMode::Absolute,
+            max_results: None,
+        });
+
+        let result = oeis.search_by_subsequence(params).await;
+        assert!(result.is_ok());
+
+        let content = result.unwrap().content;
+        assert_eq!(content.len(), 1);
+
+        assert_eq!(
+            content.first().unwrap(),
+            &ContentBlock::json(json!(SearchResponse {
+                count: 0,
+                start: 0,
+                has_more: false,
+                results: vec![]
+            }))
+            .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_by_subsequence_tool_empty_input() {
+        let oeis = OEIS::new(MockOEISClient::new().with_error(""));
+        let params = Parameters(SearchRequest {
+            subsequence: vec![],
+            start: 0,
+            mode: SignMode::Absolute,
+            max_results: None,
+        });
+
+        let result = oeis.search_by_subsequence(params).await;
+        assert!(result.is_ok());
+
+        let content = result.unwrap().content;
+        assert_eq!(
+            content.first().unwrap(),
+            &ContentBlock::json(json!(SearchResponse {
+                count: 0,
+                start: 0,
+                has_more: false,
+                results: vec![]
+            }))
+            .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_by_subsequence_tool_configured_limit_boundary() {
+        let oeis = OEIS::new(MockOEISClient::new()).with_limits(InputLimits {
+            max_subsequence_length: 3,
+            ..Default::default()
+        });
+        let request = |length| {
+            Parameters(SearchRequest {
+                subsequence: vec![1; length],
+                start: 0,
+                mode: SignMode::Absolute,
+                max_results: None,
+            })
+        };
+
+        assert!(oeis.search_by_subsequence(request(3)).await.is_ok());
+
+        let error = oeis.search_by_subsequence(request(4)).await.unwrap_err();
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+        assert_eq!(error.message, "Subsequence too long: 4 terms (max: 3)");
+    }
+
+    #[tokio::test]
+    async fn test_search_by_pattern_tool_wildcard() {
+        let fibonacci = create_test_sequence(45, "Fibonacci numbers");
+        let oeis = OEIS::new(
+            MockOEISClient::new().with_pattern_sequences("1,1,_,3,5", vec![fibonacci.clone()]),
+        );
+        let params = Parameters(PatternSearchRequest {
+            pattern: vec![Some(1), Some(1), None, Some(3), Some(5)],
+            start: 0,
+            mode: SignMode::Absolute,
+            max_results: None,
+        });
+
+        let result = oeis.search_by_pattern(params).await.unwrap();
+
+        assert_eq!(
+            result.content.first().unwrap(),
+            &ContentBlock::json(json!(SearchResponse {
+                count: 1,
+                start: 0,
+                has_more: false,
+                results: vec![fibonacci]
+            }))
+            .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_by_pattern_tool_only_wildcards() {
+        let oeis = OEIS::new(MockOEISClient::new());
+        let params = Parameters(PatternSearchRequest {
+            pattern: vec![None, None],
+            start: 0,
+            mode: SignMode::Absolute,
+            max_results: None,
+        });
+
+        let error = oeis.search_by_pattern(params).await.unwrap_err();
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+        assert!(error.message.contains("at least one known term"));
+    }
+
+    #[tokio::test]
+    async fn test_search_by_subsequence_tool_too_long() {
+        let oeis = OEIS::new(MockOEISClient::new());
+        let params = Parameters(SearchRequest {
+            subsequence: vec![1; DEFAULT_MAX_SUBSEQUENCE_LENGTH + 1],
+            start: 0,
+            mode: SignMode::Absolute,
+            max_results: None,
+        });
+
+        let result = oeis.search_by_subsequence(params).await;
+        assert!(result.is_err());
+
+        let error = result.unwrap_err();
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+        assert!(error.message.contains("Subsequence too long"));
+    }
+
+    #[tokio::test]
+    async fn test_search_by_subsequence_tool_error() {
+        let subsequence = vec![1, 2, 3];
+        let oeis = OEIS::new(
+            MockOEISClient::new().with_error(
+                &subsequence
+                    .iter()
+                    .map(|i| i.to_string())
+                    .collect::<Vec<String>>()
+                    .join(","),
+            ),
+        );
+        let params = Parameters(SearchRequest {
+            subsequence: subsequence.clone(),
+            start: 0,
+            mode: SignMode::Absolute,
+            max_results: None,
+        });
+
+        let result = oeis.search_by_subsequence(params).await;
+        assert!(result.is_err());
+
+        let error = result.unwrap_err();
+        assert_eq!(error.code, ErrorCode::INTERNAL_ERROR);
+        assert!(error.message.contains("Mock error"));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_xrefs_tool() {
+        let fibonacci = OEISSequence {
+            xref: Some(vec![
+                "Cf. A000032, A001519, A000045.".to_string(),
+                "Cf. A999999, A000500.".to_string(),
+            ]),
+            ..create_test_sequence(45, "Fibonacci numbers")
+        };
+        let oeis = OEIS::new(
+            MockOEISClient::new()
+                .with_sequence("A000045", fibonacci)
+                .with_sequence("A000032", create_test_sequence(32, "Lucas numbers"))
+                .with_sequence("A001519", create_test_sequence(1519, "Bisection"))
+                .with_not_found("A999999")
+                .with_error("A000500"),
+        );
+        let params = Parameters(FindRequest {
+            id: "A000045".to_string(),
+        });
+
+        let result = oeis.resolve_xrefs(params).await;
+        assert!(result.is_ok());
+
+        let content = result.unwrap().content;
+        assert_eq!(
+            content.first().unwrap(),
+            &ContentBlock::json(json!(ResolveXrefsResponse {
+                id: "A000045".to_string(),
+                total: 4,
+                xrefs: vec![
+                    ResolvedXref {
+                        id: "A000032".to_string(),
+                        name: Some("Lucas numbers".to_string()),
+                    },
+                    ResolvedXref {
+                        id: "A001519".to_string(),
+                        name: Some("Bisection".to_string()),
+                    },
+                    ResolvedXref {
+                        id: "A999999".to_string(),
+                        name: None,
+                    },
+                    ResolvedXref {
+                        id: "A000500".to_string(),
+                        name: None,
+                    },
+                ],
+            }))
+            .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resolve_xrefs_tool_ignores_prose() {
+        // Real `%Y` lines interleave IDs with names, notes, and M-/N-numbers
+        let fibonacci = OEISSequence {
+            xref: Some(vec![
+                "Cf. A000032 (Lucas numbers), A001519 (bisection).".to_string(),
+                "Partial sums: A000071; first differences give A000045 itself.".to_string(),
+                "Sequence in context: A236191 A226426 * A212804".to_string(),
+                "(Formerly M0692 N0256); not IDs: XA000108, A12345, FA000110.".to_string(),
+            ]),
+            ..create_test_sequence(45, "Fibonacci numbers")
+        };
+        let oeis = OEIS::new(
+            MockOEISClient::new()
+                .with_sequence("A000045", fibonacci)
+                .with_sequence("A000032", create_test_sequence(32, "Lucas numbers"))
+                .with_sequence("A001519", create_test_sequence(1519, "Bisection"))
+                .with_sequence("A000071", create_test_sequence(71, "Partial sums"))
+                .with_not_found("A236191")
+                .with_not_found("A226426")
+                .with_not_found("A212804"),
+        );
+
+        let result = oeis
+            .resolve_xrefs(Parameters(FindRequest {
+                id: "A000045".to_string(),
+            }))
+            .await
+            .unwrap();
+
+        let structured = result.structured_content.unwrap();
+        assert_eq!(structured["total"], 6);
+        assert_eq!(
+            structured["xrefs"],
+            json!([
+                {"id": "A000032", "name": "Lucas numbers"},
+                {"id": "A001519", "name": "Bisection"},
+                {"id": "A000071", "name": "Partial sums"},
+                {"id": "A236191", "name": null},
+                {"id": "A226426", "name": null},
+                {"id": "A212804", "name": null},
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_keywords_tool() {
+        let oeis = OEIS::new(MockOEISClient::new());
+
+        let result = oeis
+            .list_keywords(Parameters(EmptyRequest {}))
+            .await
+            .unwrap();
+
+        let structured = result.structured_content.unwrap();
+        let keywords = structured["keywords"].as_array().unwrap();
+        assert_eq!(keywords.len(), OEIS_KEYWORDS.len());
+        assert!(keywords.contains(&json!({
+            "keyword": "nonn",
+            "description": "All terms are nonnegative"
+        })));
+        assert!(keywords.contains(&json!({
+            "keyword": "core",
+            "description": "A fundamental sequence"
+        })));
+        assert!(keywords.contains(&json!({
+            "keyword": "tabl",
+            "description": "A regular triangle read by rows"
+        })));
+    }
+
+    #[tokio::test]
+    async fn test_sequence_keywords_tool() {
+        let fibonacci = OEISSequence {
+            keyword: "core,nonn,nice,easy,sparkly".to_string(),
+            ..create_test_sequence(45, "Fibonacci numbers")
+        };
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000045", fibonacci));
+        let params = Parameters(FindRequest {
+            id: "A000045".to_string(),
+        });
+
+        let result = oeis.sequence_keywords(params).await.unwrap();
+
+        let structured = result.structured_content.unwrap();
+        assert_eq!(structured["id"], "A000045");
+        assert_eq!(structured["core"], true);
+        assert_eq!(structured["nice"], true);
+        assert_eq!(structured["sign"], false);
+        assert_eq!(structured["unknown"], json!(["sparkly"]));
+    }
+
+    #[tokio::test]
+    async fn test_neighbors_tool_skips_missing() {
+        let oeis = OEIS::new(
+            MockOEISClient::new()
+                .with_sequence("A000043", create_test_sequence(43, "Mersenne exponents"))
+                .with_sequence("A000044", create_test_sequence(44, "Dying rabbits"))
+                .with_not_found("A000046")
+                .with_error("A000047"),
+        );
+        let params = Parameters(NeighborsRequest {
+            id: "45".to_string(),
+            radius: 2,
+        });
+
+        let result = oeis.neighbors(params).await.unwrap();
+
+        assert_eq!(
+            result.content.first().unwrap(),
+            &ContentBlock::json(json!(NeighborsResponse {
+                id: "A000045".to_string(),
+                neighbors: vec![
+                    Neighbor {
+                        id: "A000043".to_string(),
+                        name: "Mersenne exponents".to_string(),
+                    },
+                    Neighbor {
+                        id: "A000044".to_string(),
+                        name: "Dying rabbits".to_string(),
+                    },
+                ],
+            }))
+            .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_neighbors_tool_stops_at_first_a_number() {
+        let oeis = OEIS::new(
+            MockOEISClient::new()
+                .with_sequence("A000001", create_test_sequence(1, "Groups"))
+                .with_sequence("A000003", create_test_sequence(3, "Classes")),
+        );
+        let params = Parameters(NeighborsRequest {
+            id: "A000002".to_string(),
+            radius: 3,
+        });
+
+        let result = oeis.neighbors(params).await.unwrap();
+
+        let structured = result.structured_content.unwrap();
+        assert_eq!(structured["neighbors"][0]["id"], "A000001");
+        assert_eq!(structured["neighbors"][1]["id"], "A000003");
+        assert_eq!(structured["neighbors"].as_array().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_neighbors_tool_radius_too_large() {
+        let oeis = OEIS::new(MockOEISClient::new());
+        let params = Parameters(NeighborsRequest {
+            id: "A000045".to_string(),
+            radius: MAX_NEIGHBOR_RADIUS + 1,
+        });
+
+        let error = oeis.neighbors(params).await.unwrap_err();
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+    }
+
+    #[tokio::test]
+    async fn test_search_by_term_at_tool() {
+        // a(4) = 3 for the Fibonacci numbers (offset 0) but a(4) = 4 for the naturals (offset 1)
+        let fibonacci = create_test_sequence(45, "Fibonacci numbers");
+        let naturals = OEISSequence {
+            data: "1, 2, 3, 4, 5".to_string(),
+            offset: Some("1,2".to_string()),
+            ..create_test_sequence(27, "The positive integers.")
+        };
+        let oeis =
+            OEIS::new(MockOEISClient::new().with_signed_sequences(&[3], vec![fibonacci, naturals]));
+
+        let result = oeis
+            .search_by_term_at(Parameters(SearchByTermAtRequest { n: 4, value: 3 }))
+            .await
+            .unwrap();
+
+        let structured = result.structured_content.unwrap();
+        assert_eq!(structured["scanned"], 2);
+        assert_eq!(structured["count"], 2);
+        let results = structured["results"].as_array().unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0]["number"], 45);
+    }
+
+    #[tokio::test]
+    async fn test_search_by_term_at_tool_matches_sign() {
+        let alternating = OEISSequence {
+            data: "1, -1, 1, -1".to_string(),
+            ..create_test_sequence(33999, "a(n) = (-1)^n.")
+        };
+        let oeis = OEIS::new(
+            MockOEISClient::new()
+                .with_sequences(&[-1], vec![create_test_sequence(1, "Unsigned match")])
+                .with_signed_sequences(&[-1], vec![alternating]),
+        );
+
+        let result = oeis
+            .search_by_term_at(Parameters(SearchByTermAtRequest { n: 1, value: -1 }))
+            .await
+            .unwrap();
+
+        let structured = result.structured_content.unwrap();
+        assert_eq!(structured["results"][0]["number"], 33999);
+    }
+
+    #[test]
+    fn test_term_at_index() {
+        let naturals = OEISSequence {
+            data: "1, 2, 3".to_string(),
+            offset: Some("1,1".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(term_at_index(&naturals, 1), Some("1"));
+        assert_eq!(term_at_index(&naturals, 3), Some("3"));
+        assert_eq!(term_at_index(&naturals, 0), None);
+        assert_eq!(term_at_index(&naturals, 4), None);
+        assert_eq!(term_at_index(&naturals, i64::MIN), None);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_xrefs_tool_caps_fan_out() {
+        let xrefs = (1..=MAX_RESOLVED_XREFS + 5)
+            .map(|n| format!("A{:06}", 100000 + n))
+            .collect::<Vec<String>>()
+            .join(", ");
+        let sequence = OEISSequence {
+            xref: Some(vec![xrefs]),
+            ..create_test_sequence(45, "Fibonacci numbers")
+        };
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000045", sequence));
+        let params = Parameters(FindRequest {
+            id: "A000045".to_string(),
+        });
+
+        let result = oeis.resolve_xrefs(params).await.unwrap();
+
+        let structured = result.structured_content.unwrap();
+        assert_eq!(structured["total"], MAX_RESOLVED_XREFS + 5);
+        assert_eq!(
+            structured["xrefs"].as_array().unwrap().len(),
+            MAX_RESOLVED_XREFS
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resolve_xrefs_tool_not_found() {
+        let oeis = OEIS::new(MockOEISClient::new().with_not_found("A999999"));
+        let params = Parameters(FindRequest {
+            id: "A999999".to_string(),
+        });
+
+        let result = oeis.resolve_xrefs(params).await;
+        assert!(result.is_err());
+
+        let error = result.unwrap_err();
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+    }
+
+    #[tokio::test]
+    async fn test_random_sequence_tool_fetches_random_page() {
+        let seed = 42;
+        let count = 1000;
+        let index = random_index(count, seed);
+        assert!(
+            index >= 2,
+            "seed should pick an index outside the first page"
+        );
+
+        let fibonacci = create_test_sequence(45, "Fibonacci numbers");
+        let catalan = create_test_sequence(108, "Catalan numbers");
+        let picked = create_test_sequence(290, "The squares");
+        let oeis = OEIS::new(
+            MockOEISClient::new()
+                .with_search_result_for_keyword("nice", count, vec![fibonacci, catalan])
+                .with_keyword_page(
+                    "nice",
+                    index,
+                    SearchResult {
+                        count,
+                        start: index,
+                        sequences: vec![picked.clone()],
+                    },
+                ),
+        )
+        .with_rng_seed(seed);
+        let params = Parameters(RandomSequenceRequest { keyword: None });
+
+        let result = oeis.random_sequence(params).await;
+        assert!(result.is_ok());
+
+        let content = result.unwrap().content;
+        assert_eq!(
+            content.first().unwrap(),
+            &ContentBlock::json(json!(FindResponse { result: picked })).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_random_sequence_tool_picks_from_first_page() {
+        let fibonacci = create_test_sequence(45, "Fibonacci numbers");
+        let catalan = create_test_sequence(108, "Catalan numbers");
+        let sequences = vec![fibonacci, catalan];
+        let oeis =
+            OEIS::new(MockOEISClient::new().with_keyword_sequences("core", sequences.clone()))
+                .with_rng_seed(7);
+        let params = Parameters(RandomSequenceRequest {
+            keyword: Some("core".to_string()),
+        });
+
+        let result = oeis.random_sequence(params).await.unwrap();
+
+        let expected = &sequences[random_index(2, 7)];
+        assert_eq!(
+            result.structured_content.unwrap()["result"]["number"],
+            expected.number
+        );
+    }
+
+    #[tokio::test]
+    async fn test_random_sequence_tool_no_matches() {
+        let oeis = OEIS::new(MockOEISClient::new()).with_rng_seed(1);
+        let params = Parameters(RandomSequenceRequest {
+            keyword: Some("nice".to_string()),
+        });
+
+        let result = oeis.random_sequence(params).await;
+        assert!(result.is_err());
+
+        let error = result.unwrap_err();
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+    }
+
+    #[test]
+    fn test_random_index_is_deterministic_and_in_range() {
+        for seed in 0..100 {
+            let index = random_index(10, seed);
+            assert!(index < 10);
+            assert_eq!(index, random_index(10, seed));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_by_keyword_tool() {
+        let fibonacci = create_test_sequence(45, "Fibonacci numbers");
+        let catalan = create_test_sequence(108, "Catalan numbers");
+        let oeis = OEIS::new(
+            MockOEISClient::new()
+                .with_keyword_sequences("core", vec![fibonacci.clone(), catalan.clone()]),
+        );
+        let params = Parameters(KeywordSearchRequest {
+            keyword: "Core".to_string(),
+            limit: None,
+        });
+
+        let result = oeis.search_by_keyword(params).await;
+        assert!(result.is_ok());
+
+        let content = result.unwrap().content;
+        assert_eq!(
+            content.first().unwrap(),
+            &ContentBlock::json(json!(SearchResponse {
+                count: 2,
+                start: 0,
+                has_more: false,
+                results: vec![fibonacci, catalan]
+            }))
+            .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_by_keyword_tool_limit() {
+        let fibonacci = create_test_sequence(45, "Fibonacci numbers");
+        let catalan = create_test_sequence(108, "Catalan numbers");
+        let oeis = OEIS::new(
+            MockOEISClient::new().with_keyword_sequences("nice", vec![fibonacci.clone(), catalan]),
+        );
+        let params = Parameters(KeywordSearchRequest {
+            keyword: "nice".to_string(),
+            limit: Some(1),
+        });
+
+        let result = oeis.search_by_keyword(params).await;
+        assert!(result.is_ok());
+
+        let content = result.unwrap().content;
+        assert_eq!(
+            content.first().unwrap(),
+            &ContentBlock::json(json!(SearchResponse {
+                count: 2,
+                start: 0,
+                has_more: true,
+                results: vec![fibonacci]
+            }))
+            .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_by_keyword_tool_limit_clamped() {
+        let sequences: Vec<OEISSequence> = (1..=150)
+            .map(|n| create_test_sequence(n, "Test sequence"))
+            .collect();
+        let oeis = OEIS::new(MockOEISClient::new().with_keyword_sequences("nice", sequences));
+        let params = Parameters(KeywordSearchRequest {
+            keyword: "nice".to_string(),
+            limit: Some(500),
+        });
+
+        let result = oeis.search_by_keyword(params).await.unwrap();
+
+        let structured = result.structured_content.unwrap();
+        assert_eq!(structured["count"], 150);
+        assert_eq!(
+            structured["results"].as_array().unwrap().len(),
+            MAX_SEARCH_RESULTS
+        );
+        assert_eq!(structured["has_more"], true);
+    }
+
+    #[tokio::test]
+    async fn test_search_by_keyword_tool_unknown_keyword() {
+        let oeis = OEIS::new(MockOEISClient::new());
+        let params = Parameters(KeywordSearchRequest {
+            keyword: "bogus".to_string(),
+            limit: None,
+        });
+
+        let result = oeis.search_by_keyword(params).await;
+        assert!(result.is_err());
+
+        let error = result.unwrap_err();
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+        assert!(error.message.contains("Unknown keyword"));
+    }
+
+    #[tokio::test]
+    async fn test_search_ranked_tool_prefers_exact_prefix() {
+        let longer_partial = OEISSequence {
+            data: "1, 2, 3, 5, 8, 13, 21, 34, 55, 89, 144".to_string(),
+            ..create_test_sequence(1000, "Longer partial match")
+        };
+        let shorter_exact = OEISSequence {
+            data: "1, 2, 3, 4".to_string(),
+            ..create_test_sequence(2000, "Shorter exact match")
+        };
+        let oeis = OEIS::new(
+            MockOEISClient::new()
+                .with_sequences(&[1, 2, 3, 4], vec![longer_partial, shorter_exact]),
+        );
+        let params = Parameters(RankedSearchRequest {
+            subsequence: vec![1, 2, 3, 4],
+            max_results: None,
+        });
+
+        let result = oeis.search_ranked(params).await.unwrap();
+
+        let structured = result.structured_content.unwrap();
+        assert_eq!(structured["count"], 2);
+        assert_eq!(structured["results"][0]["sequence"]["number"], 2000);
+        assert_eq!(structured["results"][0]["score"], 1.0);
+        assert_eq!(structured["results"][1]["sequence"]["number"], 1000);
+        assert_eq!(structured["results"][1]["score"], 0.75);
+    }
+
+    #[test]
+    fn test_prefix_score() {
+        assert_eq!(prefix_score(&[1, 1, 2], "1, 1, 2, 3, 5"), 1.0);
+        assert_eq!(prefix_score(&[1, 1, 2], "0, 1, 1, 2"), 0.0);
+        assert_eq!(prefix_score(&[-1, 2], "-1, 3"), 0.5);
+        assert_eq!(prefix_score(&[], "1, 2"), 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_search_generating_function_tool() {
+        let fibonacci = create_test_sequence(45, "Fibonacci numbers");
+        let oeis = OEIS::new(
+            MockOEISClient::new().with_text_sequences("formula:\"x/(1-x-x^2)\"", vec![fibonacci]),
+        );
+        let params = Parameters(GeneratingFunctionSearchRequest {
+            expression: "  x/(1-x-x^2) ".to_string(),
+        });
+
+        let result = oeis.search_generating_function(params).await.unwrap();
+
+        let structured = result.structured_content.unwrap();
+        assert_eq!(structured["count"], 1);
+        assert_eq!(structured["results"][0]["number"], 45);
+    }
+
+    #[tokio::test]
+    async fn test_search_generating_function_tool_empty() {
+        let oeis = OEIS::new(MockOEISClient::new());
+        let params = Parameters(GeneratingFunctionSearchRequest {
+            expression: "   ".to_string(),
+        });
+
+        let error = oeis.search_generating_function(params).await.unwrap_err();
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+    }
+
+    #[tokio::test]
+    async fn test_search_text_tool() {
+        let partitions = create_test_sequence(41, "a(n) = number of partitions of n");
+        let oeis = OEIS::new(
+            MockOEISClient::new()
+                .with_text_sequences("number of partitions", vec![partitions.clone()]),
+        );
+        let params = Parameters(TextSearchRequest {
+            query: " number of partitions ".to_string(),
+        });
+
+        let result = oeis.search_text(params).await;
+        assert!(result.is_ok());
+
+        let content = result.unwrap().content;
+        assert_eq!(
+            content.first().unwrap(),
+            &ContentBlock::json(json!(SearchResponse {
+                count: 1,
+                start: 0,
+                has_more: false,
+                results: vec![partitions]
+            }))
+            .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_referencing_sequences_tool() {
+        // The Fibonacci test entry cross-references A000001 only
+        let fibonacci = create_test_sequence(45, "Fibonacci numbers");
+        let oeis = OEIS::new(
+            MockOEISClient::new()
+                .with_sequence("A000045", fibonacci.clone())
+                .with_text_sequences(
+                    "A000045",
+                    vec![
+                        fibonacci,
+                        create_test_sequence(1, "Number of groups of order n."),
+                        create_test_sequence(32, "Lucas numbers"),
+                    ],
+                ),
+        );
+
+        let result = oeis
+            .referencing_sequences(Parameters(FindRequest {
+                id: "45".to_string(),
+            }))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            result.content.first().unwrap(),
+            &ContentBlock::json(json!(ReferencingSequencesResponse {
+                id: "A000045".to_string(),
+                count: 3,
+                referenced_by: vec![
+                    BackReference {
+                        id: "A000001".to_string(),
+                        name: "Number of groups of order n.".to_string(),
+                        mutual: true,
+                    },
+                    BackReference {
+                        id: "A000032".to_string(),
+                        name: "Lucas numbers".to_string(),
+                        mutual: false,
+                    },
+                ],
+                references: vec!["A000001".to_string()],
+            }))
+            .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_referencing_sequences_tool_not_found() {
+        let oeis = OEIS::new(
+            MockOEISClient::new()
+                .with_not_found("A999999")
+                .with_text_sequences("A999999", vec![]),
+        );
+
+        let error = oeis
+            .referencing_sequences(Parameters(FindRequest {
+                id: "A999999".to_string(),
+            }))
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+        assert!(error.message.contains("No sequence found"));
+    }
+
+    #[tokio::test]
+    async fn test_suggest_sequences_tool_ranks_core_first() {
+        let plain = OEISSequence {
+            keyword: "nonn".to_string(),
+            ..create_test_sequence(
+                93392,
+                "Number of domino tilings of a 2 X n strip with one square removed",
+            )
+        };
+        let dead = OEISSequence {
+            keyword: "nonn,dead".to_string(),
+            ..create_test_sequence(12345, "Duplicate of A000045")
+        };
+        let fibonacci = OEISSequence {
+            keyword: "core,nonn,nice,easy".to_string(),
+            ..create_test_sequence(45, "Fibonacci numbers")
+        };
+        let oeis = OEIS::new(
+            MockOEISClient::new()
+                .with_text_sequences("domino tilings", vec![plain, dead, fibonacci]),
+        );
+
+        let result = oeis
+            .suggest_sequences(Parameters(SuggestSequencesRequest {
+                description: "domino tilings".to_string(),
+            }))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            result.content.first().unwrap(),
+            &ContentBlock::json(json!(SuggestSequencesResponse {
+                count: 3,
+                suggestions: vec![
+                    SequenceSuggestion {
+                        id: "A000045".to_string(),
+                        name: "Fibonacci numbers".to_string(),
+                        score: 6,
+                        keywords: vec!["core".to_string(), "nice".to_string(), "easy".to_string()],
+                    },
+                    SequenceSuggestion {
+                        id: "A093392".to_string(),
+                        name: "Number of domino tilings of a 2 X n strip with one square removed"
+                            .to_string(),
+                        score: 0,
+                        keywords: vec![],
+                    },
+                    SequenceSuggestion {
+                        id: "A012345".to_string(),
+                        name: "Duplicate of A000045".to_string(),
+                        score: -3,
+                        keywords: vec!["dead".to_string()],
+                    },
+                ],
+            }))
+            .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_text_tool_empty_query() {
+        let oeis = OEIS::new(MockOEISClient::new());
+        let params = Parameters(TextSearchRequest {
+            query: "   ".to_string(),
+        });
+
+        let result = oeis.search_text(params).await;
+        assert!(result.is_err());
+
+        let error = result.unwrap_err();
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+    }
+
+    #[tokio::test]
+    async fn test_is_known_sequence_tool_found() {
+        let fibonacci = create_test_sequence(45, "Fibonacci numbers");
+        let catalan = create_test_sequence(108, "Catalan numbers");
+        let oeis = OEIS::new(
+            MockOEISClient::new().with_sequences(&[1, 1, 2, 5], vec![fibonacci, catalan]),
+        );
+        let params = Parameters(IsKnownSequenceRequest {
+            subsequence: vec![1, 1, 2, 5],
+        });
+
+        let result = oeis.is_known_sequence(params).await;
+        assert!(result.is_ok());
+
+        let content = result.unwrap().content;
+        assert_eq!(
+            content.first().unwrap(),
+            &ContentBlock::json(json!(IsKnownSequenceResponse {
+                found: true,
+                count: 2,
+                first_id: Some("A000045".to_string()),
+            }))
+            .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_is_known_sequence_tool_too_broad_is_found() {
+        let oeis = OEIS::new(MockOEISClient::new().with_too_broad(&[1, 2], 183412));
+        let params = Parameters(IsKnownSequenceRequest {
+            subsequence: vec![1, 2],
+        });
+
+        let result = oeis.is_known_sequence(params).await.unwrap();
+
+        let structured = result.structured_content.unwrap();
+        assert_eq!(
+            structured,
+            json!({"found": true, "count": 183412, "first_id": null})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_is_known_sequence_tool_not_found() {
+        let oeis = OEIS::new(MockOEISClient::new());
+        let params = Parameters(IsKnownSequenceRequest {
+            subsequence: vec![999, 888, 777],
+        });
+
+        let result = oeis.is_known_sequence(params).await;
+        assert!(result.is_ok());
+
+        let content = result.unwrap().content;
+        assert_eq!(
+            content.first().unwrap(),
+            &ContentBlock::json(json!(IsKnownSequenceResponse {
+                found: false,
+                count: 0,
+                first_id: None,
+            }))
+            .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_terms_tool() {
+        let fibonacci = create_test_sequence(45, "Fibonacci numbers");
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000045", fibonacci));
+        let params = Parameters(GetTermsRequest {
+            id: "A000045".to_string(),
+            count: 1,
+        });
+
+        let result = oeis.get_terms(params).await;
+        assert!(result.is_ok());
+
+        let content = result.unwrap().content;
+        assert_eq!(
+            content.first().unwrap(),
+            &ContentBlock::json(json!(GetTermsResponse {
+                id: "A000045".to_string(),
+                terms: vec![0]
+            }))
+            .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_terms_tool_count_exceeds_available() {
+        let fibonacci = create_test_sequence(45, "Fibonacci numbers");
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000045", fibonacci));
+        let params = Parameters(GetTermsRequest {
+            id: "A000045".to_string(),
+            count: 100,
+        });
+
+        let result = oeis.get_terms(params).await;
+        assert!(result.is_ok());
+
+        let content = result.unwrap().content;
+        assert_eq!(
+            content.first().unwrap(),
+            &ContentBlock::json(json!(GetTermsResponse {
+                id: "A000045".to_string(),
+                terms: vec![0, 1, 1, 2, 3, 5, 8]
+            }))
+            .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_terms_tool_prefix_before_large_terms() {
+        let factorials = OEISSequence {
+            data: "1, 1, 2, 6, 51090942171709440000".to_string(),
+            ..create_test_sequence(142, "Factorial numbers")
+        };
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000142", factorials));
+        let params = Parameters(GetTermsRequest {
+            id: "a142".to_string(),
+            count: 3,
+        });
+
+        let result = oeis.get_terms(params).await.unwrap();
+
+        let structured = result.structured_content.unwrap();
+        assert_eq!(structured["id"], "A000142");
+        assert_eq!(structured["terms"], json!([1, 1, 2]));
+    }
+
+    #[tokio::test]
+    async fn test_format_entry_tool() {
+        let fibonacci = OEISSequence {
+            comment: Some(vec![
+                "Also called Lamé's sequence.".to_string(),
+                "F(n+2) = number of binary sequences of length n that have no consecutive 0's."
+                    .to_string(),
+            ]),
+            formula: Some(vec![
+                "F(n) = F(n-1) + F(n-2) with F(0) = 0 and F(1) = 1.".to_string(),
+            ]),
+            xref: Some(vec!["Cf. A000032, A001519.".to_string()]),
+            keyword: "core,nonn,nice,easy".to_string(),
+            ..create_test_sequence(45, "Fibonacci numbers: F(n) = F(n-1) + F(n-2).")
+        };
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000045", fibonacci));
+        let params = Parameters(FindRequest {
+            id: "A000045".to_string(),
+        });
+
+        let result = oeis.format_entry(params).await;
+        assert!(result.is_ok());
+
+        let content = result.unwrap().content;
+        assert_eq!(
+            content.first().unwrap(),
+            &ContentBlock::text(
+                "%S A000045 0,1,1,2,3,5,8\n\
+                 %N A000045 Fibonacci numbers: F(n) = F(n-1) + F(n-2).\n\
+                 %C A000045 Also called Lamé's sequence.\n\
+                 %C A000045 F(n+2) = number of binary sequences of length n that have no consecutive 0's.\n\
+                 %F A000045 F(n) = F(n-1) + F(n-2) with F(0) = 0 and F(1) = 1.\n\
+                 %Y A000045 Cf. A000032, A001519.\n\
+                 %K A000045 core,nonn,nice,easy\n\
+                 %O A000045 0,4\n\
+                 %A A000045 _N. J. A. Sloane_"
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_format_entry_tool_not_found() {
+        let oeis = OEIS::new(MockOEISClient::new().with_not_found("A999999"));
+        let params = Parameters(FindRequest {
+            id: "A999999".to_string(),
+        });
+
+        let result = oeis.format_entry(params).await;
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_wrap_data_splits_long_data_across_lines() {
+        let data = (1..=40)
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let lines = wrap_data(&data, 20, 3);
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "1,2,3,4,5,6,7,8,9,");
+        assert_eq!(lines[1], "10,11,12,13,14,15,");
+        // The last line is not truncated
+        assert_eq!(lines.concat(), data.replace(", ", ","));
+    }
+
+    #[tokio::test]
+    async fn test_sequence_plot_data_tool_honors_offset() {
+        let primes = OEISSequence {
+            data: "2, 3, 5, 7, 11".to_string(),
+            offset: Some("1,1".to_string()),
+            ..create_test_sequence(40, "The prime numbers.")
+        };
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000040", primes));
+        let params = Parameters(SequencePlotDataRequest {
+            id: "A000040".to_string(),
+            count: Some(3),
+        });
+
+        let result = oeis.sequence_plot_data(params).await;
+        assert!(result.is_ok());
+
+        let content = result.unwrap().content;
+        assert_eq!(
+            content.first().unwrap(),
+            &ContentBlock::json(json!(SequencePlotDataResponse {
+                id: "A000040".to_string(),
+                points: vec![
+                    PlotPoint {
+                        n: 1,
+                        value: "2".to_string()
+                    },
+                    PlotPoint {
+                        n: 2,
+                        value: "3".to_string()
+                    },
+                    PlotPoint {
+                        n: 3,
+                        value: "5".to_string()
+                    },
+                ],
+            }))
+            .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sequence_plot_data_tool_all_terms() {
+        let fibonacci = create_test_sequence(45, "Fibonacci numbers");
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000045", fibonacci));
+        let params = Parameters(SequencePlotDataRequest {
+            id: "A000045".to_string(),
+            count: None,
+        });
+
+        let result = oeis.sequence_plot_data(params).await.unwrap();
+
+        let points = result.structured_content.unwrap()["points"].clone();
+        assert_eq!(points.as_array().unwrap().len(), 7);
+        assert_eq!(points[0], json!({"n": 0, "value": "0"}));
+        assert_eq!(points[6], json!({"n": 6, "value": "8"}));
+    }
+
+    #[tokio::test]
+    async fn test_get_programs_tool() {
+        let fibonacci = OEISSequence {
+            program: Some(vec![
+                "(PARI) a(n)=fibonacci(n)".to_string(),
+                "(Python) from sympy import fibonacci; a = fibonacci".to_string(),
+            ]),
+            ..create_test_sequence(45, "Fibonacci numbers")
+        };
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000045", fibonacci));
+        let params = Parameters(FindRequest {
+            id: "45".to_string(),
+        });
+
+        let result = oeis.get_programs(params).await;
+        assert!(result.is_ok());
+
+        let content = result.unwrap().content;
+        assert_eq!(
+            content.first().unwrap(),
+            &ContentBlock::json(json!(GetProgramsResponse {
+                id: "A000045".to_string(),
+                maple: None,
+                mathematica: None,
+                program: Some(vec![
+                    "(PARI) a(n)=fibonacci(n)".to_string(),
+                    "(Python) from sympy import fibonacci; a = fibonacci".to_string(),
+                ]),
+            }))
+            .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_comments_tool_returns_raw_comments() {
+        let fibonacci = OEISSequence {
+            comment: Some(vec!["See *emphasis* and _underscore_.".to_string()]),
+            ..create_test_sequence(45, "Fibonacci numbers")
+        };
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000045", fibonacci));
+
+        let result = oeis
+            .get_comments(Parameters(FindRequest {
+                id: "45".to_string(),
+            }))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            result.content.first().unwrap(),
+            &ContentBlock::json(json!(GetCommentsResponse {
+                id: "A000045".to_string(),
+                comments: vec!["See *emphasis* and _underscore_.".to_string()],
+            }))
+            .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_contains_value_tool_present() {
+        let fibonacci = OEISSequence {
+            data: "0, 1, 1, 2, 3, 5, 8, 13, 21, 34, 55, 89, 144, 233".to_string(),
+            ..create_test_sequence(45, "Fibonacci numbers")
+        };
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000045", fibonacci));
+
+        let result = oeis
+            .contains_value(Parameters(ContainsValueRequest {
+                id: "A000045".to_string(),
+                value: 144,
+                bfile: false,
+            }))
+            .await
+            .unwrap();
+
+        let structured = result.structured_content.unwrap();
+        assert_eq!(structured["found"], true);
+        assert_eq!(structured["indices"], json!([12]));
+        assert_eq!(structured["checked_terms"], 14);
+        assert!(
+            structured["note"]
+                .as_str()
+                .unwrap()
+                .starts_with("Only the 14 terms in the data (n = 0..=13) were checked")
+        );
+
+        let result = oeis
+            .contains_value(Parameters(ContainsValueRequest {
+                id: "A000045".to_string(),
+                value: 1,
+                bfile: false,
+            }))
+            .await
+            .unwrap();
+        assert_eq!(result.structured_content.unwrap()["indices"], json!([1, 2]));
+    }
+
+    #[tokio::test]
+    async fn test_contains_value_tool_absent() {
+        let fibonacci = create_test_sequence(45, "Fibonacci numbers");
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000045", fibonacci));
+
+        let result = oeis
+            .contains_value(Parameters(ContainsValueRequest {
+                id: "A000045".to_string(),
+                value: 4,
+                bfile: false,
+            }))
+            .await
+            .unwrap();
+
+        let structured = result.structured_content.unwrap();
+        assert_eq!(structured["found"], false);
+        assert_eq!(structured["indices"], json!([]));
+        assert_eq!(structured["checked_terms"], 7);
+    }
+
+    #[tokio::test]
+    async fn test_contains_value_tool_bfile() {
+        let fibonacci = create_test_sequence(45, "Fibonacci numbers");
+        let bfile = vec![
+            BFileEntry {
+                index: 11,
+                value: "89".to_string(),
+            },
+            BFileEntry {
+                index: 12,
+                value: "144".to_string(),
+            },
+        ];
+        let oeis = OEIS::new(
+            MockOEISClient::new()
+                .with_sequence("A000045", fibonacci)
+                .with_bfile("A000045", bfile),
+        );
+
+        let result = oeis
+            .contains_value(Parameters(ContainsValueRequest {
+                id: "A000045".to_string(),
+                value: 144,
+                bfile: true,
+            }))
+            .await
+            .unwrap();
+
+        let structured = result.structured_content.unwrap();
+        assert_eq!(structured["found"], true);
+        assert_eq!(structured["indices"], json!([12]));
+        assert!(
+            structured["note"]
+                .as_str()
+                .unwrap()
+                .contains("in the b-file")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_raw_lookup_tool_passes_body_through() {
+        let body = r#"{"count": 1, "results": [{"number": 45, "unmodeled": {"x": 1}}]}"#;
+        let oeis = OEIS::new(MockOEISClient::new().with_raw_body("A000045", body));
+
+        let result = oeis
+            .raw_lookup(Parameters(FindRequest {
+                id: "45".to_string(),
+            }))
+            .await
+            .unwrap();
+
+        assert_eq!(result.content, vec![ContentBlock::text(body)]);
+        assert!(result.structured_content.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_extract_formulas_tool() {
+        let fibonacci = OEISSequence {
+            formula: Some(vec![
+                "G.f.: x/(1-x-x^2).".to_string(),
+                "a(n) = (phi^n - (-phi)^(-n))/sqrt(5).".to_string(),
+                "a(n) = a(n-1) + a(n-2) with a(0) = 0, a(1) = 1.".to_string(),
+                "E.g.f.: (exp(phi*x) - exp(-x/phi))/sqrt(5).".to_string(),
+                "Sum_{k=0..n} a(k) = a(n+2) - 1.".to_string(),
+                "a(n) ~ phi^n/sqrt(5).".to_string(),
+            ]),
+            ..create_test_sequence(45, "Fibonacci numbers")
+        };
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000045", fibonacci));
+
+        let result = oeis
+            .extract_formulas(Parameters(FindRequest {
+                id: "A000045".to_string(),
+            }))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            result.content.first().unwrap(),
+            &ContentBlock::json(json!(ExtractFormulasResponse {
+                id: "A000045".to_string(),
+                generating_functions: vec!["G.f.: x/(1-x-x^2).".to_string()],
+                exponential_generating_functions: vec![
+                    "E.g.f.: (exp(phi*x) - exp(-x/phi))/sqrt(5).".to_string()
+                ],
+                recurrences: vec![
+                    "a(n) = a(n-1) + a(n-2) with a(0) = 0, a(1) = 1.".to_string(),
+                    "Sum_{k=0..n} a(k) = a(n+2) - 1.".to_string(),
+                ],
+                closed_forms: vec!["a(n) = (phi^n - (-phi)^(-n))/sqrt(5).".to_string()],
+                other: vec!["a(n) ~ phi^n/sqrt(5).".to_string()],
+            }))
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_classify_formula() {
+        assert_eq!(
+            classify_formula("O.g.f.: 1/(1-x)^2."),
+            FormulaKind::GeneratingFunction
+        );
+        assert_eq!(
+            classify_formula("G.f. A(x) satisfies A(x) = 1 + x*A(x)^2."),
+            FormulaKind::GeneratingFunction
+        );
+        assert_eq!(classify_formula("a(n)=n^2."), FormulaKind::ClosedForm);
+        assert_eq!(
+            classify_formula("a(n+1) = 2*a(n) + 1."),
+            FormulaKind::Recurrence
+        );
+        assert_eq!(classify_formula("Limit of a(n)/n = 1."), FormulaKind::Other);
+    }
+
+    #[tokio::test]
+    async fn test_lookup_by_nickname_tool_table_hit() {
+        let fibonacci = create_test_sequence(45, "Fibonacci numbers");
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000045", fibonacci));
+
+        let result = oeis
+            .lookup_by_nickname(Parameters(NicknameRequest {
+                nickname: "Fibonacci".to_string(),
+            }))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            result.content.first().unwrap(),
+            &ContentBlock::json(json!(NicknameLookupResponse {
+                id: "A000045".to_string(),
+                name: "Fibonacci numbers".to_string(),
+                matched_by: NicknameMatch::Table,
+            }))
+            .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_lookup_by_nickname_tool_name_search_fallback() {
+        let schroeder = create_test_sequence(6318, "Large Schroeder numbers");
+        let oeis = OEIS::new(
+            MockOEISClient::new()
+                .with_text_sequences("name:\"Schroeder numbers\"", vec![schroeder]),
+        );
+
+        let result = oeis
+            .lookup_by_nickname(Parameters(NicknameRequest {
+                nickname: " Schroeder numbers ".to_string(),
+            }))
+            .await
+            .unwrap();
+
+        let structured = result.structured_content.unwrap();
+        assert_eq!(structured["id"], "A006318");
+        assert_eq!(structured["matched_by"], "name_search");
+    }
+
+    #[tokio::test]
+    async fn test_lookup_by_nickname_tool_no_match() {
+        let oeis = OEIS::new(
+            MockOEISClient::new().with_text_sequences("name:\"Nonexistent numbers\"", vec![]),
+        );
+
+        let error = oeis
+            .lookup_by_nickname(Parameters(NicknameRequest {
+                nickname: "Nonexistent numbers".to_string(),
+            }))
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+        assert!(error.message.contains("by nickname: Nonexistent numbers"));
+    }
+
+    #[test]
+    fn test_normalize_nickname() {
+        assert_eq!(normalize_nickname("Catalan numbers"), "catalan");
+        assert_eq!(normalize_nickname(" Catalan's sequence "), "catalan");
+        assert_eq!(normalize_nickname("Powers of 2"), "powers of 2");
+        // Every table entry must be reachable through normalization
+        for (nickname, _) in SEQUENCE_NICKNAMES {
+            assert_eq!(normalize_nickname(nickname), *nickname);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_programs_tool_not_found() {
+        let oeis = OEIS::new(MockOEISClient::new().with_not_found("A999999"));
+        let params = Parameters(FindRequest {
+            id: "A999999".to_string(),
+        });
+
+        let result = oeis.get_programs(params).await;
+        assert!(result.is_err());
+
+        let error = result.unwrap_err();
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+    }
+
+    #[tokio::test]
+    async fn test_sequence_differences_tool_squares() {
+        let squares = OEISSequence {
+            data: "0, 1, 4, 9, 16, 25, 36".to_string(),
+            ..create_test_sequence(290, "The squares: a(n) = n^2.")
+        };
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000290", squares));
+
+        let first = oeis
+            .sequence_differences(Parameters(SequenceDifferencesRequest {
+                id: "A000290".to_string(),
+                order: 1,
+            }))
+            .await
+            .unwrap();
+        assert_eq!(
+            first.structured_content.unwrap()["differences"],
+            json!([1, 3, 5, 7, 9, 11])
+        );
+
+        let second = oeis
+            .sequence_differences(Parameters(SequenceDifferencesRequest {
+                id: "A000290".to_string(),
+                order: 2,
+            }))
+            .await
+            .unwrap();
+        assert_eq!(
+            second.content.first().unwrap(),
+            &ContentBlock::json(json!(SequenceDifferencesResponse {
+                id: "A000290".to_string(),
+                order: 2,
+                differences: vec![2, 2, 2, 2, 2],
+            }))
+            .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sequence_differences_tool_order_too_large() {
+        let fibonacci = create_test_sequence(45, "Fibonacci numbers");
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000045", fibonacci));
+        let params = Parameters(SequenceDifferencesRequest {
+            id: "A000045".to_string(),
+            order: 7,
+        });
+
+        let result = oeis.sequence_differences(params).await;
+        assert!(result.is_err());
+
+        let error = result.unwrap_err();
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+    }
+
+    #[tokio::test]
+    async fn test_sequence_differences_tool_terms_past_i64() {
+        let large = OEISSequence {
+            data: "100000000000000000000, 100000000000000000001, 100000000000000000003".to_string(),
+            ..create_test_sequence(1, "Large terms")
+        };
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000001", large));
+
+        let result = oeis
+            .sequence_differences(Parameters(SequenceDifferencesRequest {
+                id: "A000001".to_string(),
+                order: 1,
+            }))
+            .await
+            .unwrap();
+
+        let structured = result.structured_content.unwrap();
+        assert_eq!(structured["differences"], json!([1, 2]));
+    }
+
+    #[tokio::test]
+    async fn test_sequence_differences_tool_out_of_range() {
+        let huge = OEISSequence {
+            data: "0, 1000000000000000000000000000000000000000".to_string(),
+            ..create_test_sequence(1, "Huge terms")
+        };
+        let large = OEISSequence {
+            data: "0, 100000000000000000000".to_string(),
+            ..create_test_sequence(2, "Large terms")
+        };
+        let oeis = OEIS::new(
+            MockOEISClient::new()
+                .with_sequence("A000001", huge)
+                .with_sequence("A000002", large),
+        );
+        let request = |id: &str| {
+            Parameters(SequenceDifferencesRequest {
+                id: id.to_string(),
+                order: 1,
+            })
+        };
+
+        // Past 128 bits, the term itself is named
+        let error = oeis
+            .sequence_differences(request("A000001"))
+            .await
+            .unwrap_err();
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+        assert_eq!(
+            error.data.unwrap()["term"],
+            "1000000000000000000000000000000000000000"
+        );
+
+        // A difference past 64 bits cannot be written as a JSON number
+        let error = oeis
+            .sequence_differences(request("A000002"))
+            .await
+            .unwrap_err();
+        assert_eq!(error.code, ErrorCode::INTERNAL_ERROR);
+        assert_eq!(
+            error.data,
+            Some(json!({"n": 0, "value": "100000000000000000000"}))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_export_csv_tool() {
+        let fibonacci = create_test_sequence(45, "Fibonacci numbers");
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000045", fibonacci));
+
+        let result = oeis
+            .export_csv(Parameters(FindRequest {
+                id: "A000045".to_string(),
+            }))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            result.content.first().unwrap(),
+            &ContentBlock::text("index,value\n0,0\n1,1\n2,1\n3,2\n4,3\n5,5\n6,8\n")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_format_terms_tool_grouping() {
+        let factorials = OEISSequence {
+            data: "1, 1, 2, 6, 24, 120, 720, 5040, 40320, 362880, 3628800, 2432902008176640000, \
+                   51090942171709440000, 1124000727777607680000"
+                .to_string(),
+            ..create_test_sequence(142, "Factorial numbers n!")
+        };
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000142", factorials));
+
+        let result = oeis
+            .format_terms(Parameters(FormatTermsRequest {
+                id: "A000142".to_string(),
+                grouping: true,
+            }))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            result.structured_content.unwrap()["terms"],
+            json!([
+                "1",
+                "1",
+                "2",
+                "6",
+                "24",
+                "120",
+                "720",
+                "5,040",
+                "40,320",
+                "362,880",
+                "3,628,800",
+                "2,432,902,008,176,640,000",
+                "51,090,942,171,709,440,000",
+                "1,124,000,727,777,607,680,000"
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_format_terms_tool_without_grouping() {
+        let factorials = OEISSequence {
+            data: "1, 1, 2, 6, 24, 120, 720, 5040, 40320".to_string(),
+            ..create_test_sequence(142, "Factorial numbers n!")
+        };
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000142", factorials));
+
+        let result = oeis
+            .format_terms(Parameters(FormatTermsRequest {
+                id: "A000142".to_string(),
+                grouping: false,
+            }))
+            .await
+            .unwrap();
+
+        let structured = result.structured_content.unwrap();
+        assert_eq!(structured["id"], "A000142");
+        assert_eq!(structured["terms"][7], "5040");
+        assert_eq!(structured["terms"][8], "40320");
+    }
+
+    #[test]
+    fn test_session_id() {
+        let (parts, ()) = axum::http::Request::builder()
+            .header("mcp-session-id", "session-1")
+            .body(())
+            .unwrap()
+            .into_parts();
+        let mut extensions = Extensions::new();
+        extensions.insert(parts);
+
+        assert_eq!(session_id(&extensions), Some("session-1"));
+        assert_eq!(session_id(&Extensions::new()), None);
+    }
+
+    #[test]
+    fn test_group_thousands() {
+        assert_eq!(group_thousands("0"), "0");
+        assert_eq!(group_thousands("999"), "999");
+        assert_eq!(group_thousands("1000"), "1,000");
+        assert_eq!(group_thousands("-1234567"), "-1,234,567");
+        assert_eq!(group_thousands("-123"), "-123");
+        assert_eq!(
+            group_thousands("-9223372036854775809"),
+            "-9,223,372,036,854,775,809"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sequence_links_tool() {
+        let fibonacci = OEISSequence {
+            xref: Some(vec![
+                "Cf. A000032, A000204.".to_string(),
+                "Partial sums: A000071.".to_string(),
+            ]),
+            ..create_test_sequence(45, "Fibonacci numbers")
+        };
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000045", fibonacci));
+
+        let result = oeis
+            .sequence_links(Parameters(FindRequest {
+                id: "45".to_string(),
+            }))
+            .await
+            .unwrap();
+
+        let uris: Vec<&str> = result
+            .content
+            .iter()
+            .map(|content| content.as_resource_link().unwrap().uri.as_str())
+            .collect();
+        assert_eq!(
+            uris,
+            vec![
+                "oeis://bfile/A000045",
+                "https://oeis.org/A000045",
+                "https://oeis.org/play?seq=A000045",
+                "oeis://sequence/A000032",
+                "oeis://sequence/A000204",
+                "oeis://sequence/A000071",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_media_links_tool() {
+        // Derived from the ID alone, so no sequence needs to be registered
+        let oeis = OEIS::new(MockOEISClient::new());
+
+        let result = oeis
+            .media_links(Parameters(FindRequest {
+                id: "a45".to_string(),
+            }))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            result.content.first().unwrap(),
+            &ContentBlock::json(json!(MediaLinksResponse {
+                id: "A000045".to_string(),
+                listen_url: "https://oeis.org/play?seq=A000045".to_string(),
+                graph_url: "https://oeis.org/A000045/graph".to_string(),
+            }))
+            .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_media_links_tool_invalid_id() {
+        let oeis = OEIS::new(MockOEISClient::new());
+
+        let error = oeis
+            .media_links(Parameters(FindRequest {
+                id: "not an id".to_string(),
+            }))
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+    }
+
+    #[tokio::test]
+    async fn test_term_at_tool_offset_one() {
+        let naturals = OEISSequence {
+            data: "1, 2, 3, 4, 5".to_string(),
+            offset: Some("1,2".to_string()),
+            ..create_test_sequence(27, "The positive integers.")
+        };
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000027", naturals));
+
+        let first = oeis
+            .term_at(Parameters(TermAtRequest {
+                id: "A000027".to_string(),
+                n: 1,
+            }))
+            .await
+            .unwrap();
+        assert_eq!(
+            first.content.first().unwrap(),
+            &ContentBlock::json(json!(TermAtResponse {
+                id: "A000027".to_string(),
+                n: 1,
+                value: "1".to_string(),
+            }))
+            .unwrap()
+        );
+
+        let last = oeis
+            .term_at(Parameters(TermAtRequest {
+                id: "A000027".to_string(),
+                n: 5,
+            }))
+            .await
+            .unwrap();
+        assert_eq!(last.structured_content.unwrap()["value"], "5");
+    }
+
+    #[tokio::test]
+    async fn test_term_at_tool_out_of_range() {
+        let naturals = OEISSequence {
+            data: "1, 2, 3, 4, 5".to_string(),
+            offset: Some("1,2".to_string()),
+            ..create_test_sequence(27, "The positive integers.")
+        };
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000027", naturals));
+
+        for n in [0, 6, i64::MIN] {
+            let error = oeis
+                .term_at(Parameters(TermAtRequest {
+                    id: "A000027".to_string(),
+                    n,
+                }))
+                .await
+                .unwrap_err();
+            assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+            assert!(error.message.contains("n = 1..=5"));
+            assert_eq!(error.data, Some(json!({"n": n, "min": 1, "max": 5})));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_transform_sequence_tool_partial_sums_of_naturals() {
+        let naturals = OEISSequence {
+            data: "1, 2, 3, 4, 5, 6".to_string(),
+            ..create_test_sequence(27, "The positive integers.")
+        };
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000027", naturals));
+        let params = Parameters(TransformSequenceRequest {
+            id: "A000027".to_string(),
+            op: Transform::PartialSums,
+        });
+
+        let result = oeis.transform_sequence(params).await.unwrap();
+
+        assert_eq!(
+            result.content.first().unwrap(),
+            &ContentBlock::json(json!(TransformSequenceResponse {
+                id: "A000027".to_string(),
+                op: Transform::PartialSums,
+                values: vec![1, 3, 6, 10, 15, 21],
+            }))
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_transform_terms() {
+        let terms = [1, 2, 3, 4];
+        assert_eq!(
+            transform_terms(&terms, Transform::Differences),
+            Some(vec![1, 1, 1])
+        );
+        assert_eq!(
+            transform_terms(&terms, Transform::RunningProducts),
+            Some(vec![1, 2, 6, 24])
+        );
+        assert_eq!(
+            transform_terms(&[i64::MIN as i128, 0], Transform::Negate),
+            Some(vec![-(i64::MIN as i128), 0])
+        );
+    }
+
+    #[test]
+    fn test_transform_terms_overflow() {
+        let terms = [i64::MAX as i128; 3];
+        assert_eq!(transform_terms(&terms, Transform::RunningProducts), None);
+        assert!(transform_terms(&terms, Transform::PartialSums).is_some());
+        assert_eq!(transform_terms(&[i128::MIN], Transform::Negate), None);
+    }
+
+    #[tokio::test]
+    async fn test_transform_sequence_tool_out_of_json_range() {
+        let large = OEISSequence {
+            data: "9223372036854775807, 9223372036854775807, 9223372036854775807".to_string(),
+            ..create_test_sequence(1, "Large terms")
+        };
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000001", large));
+        let params = Parameters(TransformSequenceRequest {
+            id: "A000001".to_string(),
+            op: Transform::PartialSums,
+        });
+
+        // 3 * i64::MAX fits in 128 bits but not in a JSON number
+        let error = oeis.transform_sequence(params).await.unwrap_err();
+
+        assert_eq!(error.code, ErrorCode::INTERNAL_ERROR);
+        assert_eq!(
+            error.message,
+            "PartialSums overflowed the 64-bit range of JSON numbers at n = 2 (27670116110564327421)"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_triangle_rows_tool_pascal() {
+        let pascal = OEISSequence {
+            data: "1, 1, 1, 1, 2, 1, 1, 3, 3, 1, 1, 4, 6, 4, 1".to_string(),
+            offset: Some("0,5".to_string()),
+            keyword: "nonn,tabl,nice,easy,core,look,hear,changed".to_string(),
+            ..create_test_sequence(7318, "Pascal's triangle read by rows")
+        };
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A007318", pascal));
+
+        let result = oeis
+            .triangle_rows(Parameters(FindRequest {
+                id: "A007318".to_string(),
+            }))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            result.content.first().unwrap(),
+            &ContentBlock::json(json!(TriangleRowsResponse {
+                id: "A007318".to_string(),
+                first_row: 0,
+                rows: vec![
+                    vec![1],
+                    vec![1, 1],
+                    vec![1, 2, 1],
+                    vec![1, 3, 3, 1],
+                    vec![1, 4, 6, 4, 1],
+                ],
+            }))
+            .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_triangle_rows_tool_term_too_large() {
+        let triangle = OEISSequence {
+            data: "1, 1, 1, 1, 99999999999999999999, 1".to_string(),
+            offset: Some("0,1".to_string()),
+            keyword: "nonn,tabl".to_string(),
+            ..create_test_sequence(7318, "Pascal's triangle read by rows")
+        };
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A007318", triangle));
+
+        let error = oeis
+            .triangle_rows(Parameters(FindRequest {
+                id: "A007318".to_string(),
+            }))
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+        assert_eq!(
+            error.message,
+            "A007318 has a term too large for this tool: a(4) = 99999999999999999999 does not fit in i64"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_triangle_rows_tool_not_a_triangle() {
+        let fibonacci = create_test_sequence(45, "Fibonacci numbers");
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000045", fibonacci));
+
+        let error = oeis
+            .triangle_rows(Parameters(FindRequest {
+                id: "A000045".to_string(),
+            }))
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+        assert!(error.message.contains("not a triangle"));
+    }
+
+    #[test]
+    fn test_split_triangle_rows_keeps_incomplete_last_row() {
+        assert_eq!(
+            split_triangle_rows(&[1, 1, 1, 1, 2]),
+            vec![vec![1], vec![1, 1], vec![1, 2]]
+        );
+        assert!(split_triangle_rows(&[]).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sequence_diff_tool_naturals_and_squares() {
+        let naturals = OEISSequence {
+            data: "1, 2, 3, 4, 5, 6".to_string(),
+            offset: Some("1,2".to_string()),
+            ..create_test_sequence(27, "The positive integers.")
+        };
+        let squares = OEISSequence {
+            data: "0, 1, 4, 9".to_string(),
+            offset: Some("0,3".to_string()),
+            ..create_test_sequence(290, "The squares.")
+        };
+        let oeis = OEIS::new(
+            MockOEISClient::new()
+                .with_sequence("A000027", naturals)
+                .with_sequence("A000290", squares),
+        );
+
+        let result = oeis
+            .sequence_diff(Parameters(CompareSequencesRequest {
+                first_id: "A000027".to_string(),
+                second_id: "A000290".to_string(),
+            }))
+            .await
+            .unwrap();
+
+        // Only n = 1..=3 is in both: the naturals start at n = 1, the squares stop at n = 3
+        assert_eq!(
+            result.content.first().unwrap(),
+            &ContentBlock::json(json!(SequenceDiffResponse {
+                first_id: "A000027".to_string(),
+                second_id: "A000290".to_string(),
+                compared: 3,
+                equal: 1,
+                diffs: vec![
+                    TermDiff {
+                        n: 1,
+                        first: 1,
+                        second: 1,
+                        difference: 0,
+                    },
+                    TermDiff {
+                        n: 2,
+                        first: 2,
+                        second: 4,
+                        difference: 2,
+                    },
+                    TermDiff {
+                        n: 3,
+                        first: 3,
+                        second: 9,
+                        difference: 6,
+                    },
+                ],
+            }))
+            .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sequence_diff_tool_missing_second() {
+        let fibonacci = create_test_sequence(45, "Fibonacci numbers");
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000045", fibonacci));
+
+        let error = oeis
+            .sequence_diff(Parameters(CompareSequencesRequest {
+                first_id: "A000045".to_string(),
+                second_id: "A999999".to_string(),
+            }))
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+        assert!(error.message.starts_with("second sequence: "));
+    }
+
+    #[tokio::test]
+    async fn test_sequence_diff_tool_term_too_large() {
+        let factorials = OEISSequence {
+            data: "1, 1, 2, 6, 51090942171709440000".to_string(),
+            ..create_test_sequence(142, "Factorial numbers")
+        };
+        let oeis = OEIS::new(
+            MockOEISClient::new()
+                .with_sequence("A000045", create_test_sequence(45, "Fibonacci numbers"))
+                .with_sequence("A000142", factorials),
+        );
+
+        let error = oeis
+            .sequence_diff(Parameters(CompareSequencesRequest {
+                first_id: "A000045".to_string(),
+                second_id: "A000142".to_string(),
+            }))
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+        assert_eq!(
+            error.data,
+            Some(json!({"id": "A000142", "n": 4, "term": "51090942171709440000"}))
+        );
+    }
+
+    #[test]
+    fn test_diff_terms_without_overlap() {
+        assert!(diff_terms((0, &[1, 2]), (5, &[1, 2])).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_is_subsequence_of_tool_fibonacci_prefix() {
+        let prefix = OEISSequence {
+            data: "0, 1, 1, 2".to_string(),
+            ..create_test_sequence(1, "First Fibonacci numbers")
+        };
+        let oeis = OEIS::new(
+            MockOEISClient::new()
+                .with_sequence("A000001", prefix)
+                .with_sequence("A000045", create_test_sequence(45, "Fibonacci numbers")),
+        );
+
+        let result = oeis
+            .is_subsequence_of(Parameters(IsSubsequenceRequest {
+                candidate_id: "A000001".to_string(),
+                container_id: "A000045".to_string(),
+                mode: SubsequenceMode::Contiguous,
+            }))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            result.content.first().unwrap(),
+            &ContentBlock::json(json!(IsSubsequenceResponse {
+                candidate_id: "A000001".to_string(),
+                container_id: "A000045".to_string(),
+                mode: SubsequenceMode::Contiguous,
+                found: true,
+                start: Some(0),
+                candidate_terms: 4,
+                container_terms: 7,
+            }))
+            .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_is_subsequence_of_tool_scattered_honors_offset() {
+        let candidate = OEISSequence {
+            data: "1, 3, 8".to_string(),
+            ..create_test_sequence(1, "Some Fibonacci numbers")
+        };
+        let container = OEISSequence {
+            offset: Some("1,3".to_string()),
+            ..create_test_sequence(45, "Fibonacci numbers")
+        };
+        let oeis = OEIS::new(
+            MockOEISClient::new()
+                .with_sequence("A000001", candidate)
+                .with_sequence("A000045", container),
+        );
+        let request = |mode| {
+            Parameters(IsSubsequenceRequest {
+                candidate_id: "A000001".to_string(),
+                container_id: "A000045".to_string(),
+                mode,
+            })
+        };
+
+        let result = oeis
+            .is_subsequence_of(request(SubsequenceMode::Contiguous))
+            .await
+            .unwrap();
+        let value = result.structured_content.unwrap();
+        assert_eq!(value["found"], false);
+        assert_eq!(value["start"], serde_json::Value::Null);
+
+        // The first 1 is the container's second term, which is a(2) with offset 1
+        let result = oeis
+            .is_subsequence_of(request(SubsequenceMode::Scattered))
+            .await
+            .unwrap();
+        let value = result.structured_content.unwrap();
+        assert_eq!(value["found"], true);
+        assert_eq!(value["start"], 2);
+    }
+
+    #[test]
+    fn test_find_contiguous() {
+        assert_eq!(find_contiguous(&[1, 2, 3], &[0, 1, 1, 2, 3, 5]), Some(2));
+        assert_eq!(find_contiguous(&[1, 3], &[0, 1, 1, 2, 3, 5]), None);
+        assert_eq!(find_contiguous(&[1, 2, 3, 4], &[1, 2, 3]), None);
+        assert_eq!(find_contiguous(&[], &[1, 2]), Some(0));
+    }
+
+    #[test]
+    fn test_find_scattered() {
+        assert_eq!(find_scattered(&[1, 3, 8], &[0, 1, 1, 2, 3, 5, 8]), Some(1));
+        assert_eq!(find_scattered(&[1, 1, 1], &[0, 1, 1, 2, 3]), None);
+        assert_eq!(find_scattered(&[3, 1], &[1, 2, 3]), None);
+        assert_eq!(find_scattered(&[], &[1, 2]), Some(0));
+    }
+
+    #[tokio::test]
+    async fn test_sequence_stats_tool_squares() {
+        let squares = OEISSequence {
+            data: "0, 1, 4, 9, 16, 25".to_string(),
+            ..create_test_sequence(290, "The squares.")
+        };
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000290", squares));
+
+        let result = oeis
+            .sequence_stats(Parameters(FindRequest {
+                id: "A000290".to_string(),
+            }))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            result.content.first().unwrap(),
+            &ContentBlock::json(json!(SequenceStatsResponse {
+                id: "A000290".to_string(),
+                count: 6,
+                min: Some("0".to_string()),
+                max: Some("25".to_string()),
+                sum: "55".to_string(),
+                monotonicity: Monotonicity::NonDecreasing,
+                signed: false,
+            }))
+            .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sequence_stats_tool_signed() {
+        let liouville = OEISSequence {
+            data: "1, -1, -1, 1, -1, 1, -1, -1".to_string(),
+            keyword: "sign,easy,nice".to_string(),
+            ..create_test_sequence(8836, "Liouville's function lambda(n).")
+        };
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A008836", liouville));
+
+        let result = oeis
+            .sequence_stats(Parameters(FindRequest {
+                id: "A008836".to_string(),
+            }))
+            .await
+            .unwrap();
+
+        let structured = result.structured_content.unwrap();
+        assert_eq!(structured["min"], "-1");
+        assert_eq!(structured["max"], "1");
+        assert_eq!(structured["sum"], "-2");
+        assert_eq!(structured["monotonicity"], "none");
+        assert_eq!(structured["signed"], true);
+    }
+
+    #[tokio::test]
+    async fn test_sequence_stats_tool_terms_past_i64() {
+        // 0! through 22!; 21! and 22! exceed `i64::MAX`
+        let sequence = OEISSequence {
+            data: "1, 1, 2, 6, 24, 120, 720, 5040, 40320, 362880, 3628800, 39916800, 479001600, \
+                   6227020800, 87178291200, 1307674368000, 20922789888000, 355687428096000, \
+                   6402373705728000, 121645100408832000, 2432902008176640000, \
+                   51090942171709440000, 1124000727777607680000"
+                .to_string(),
+            ..create_test_sequence(142, "Factorial numbers")
+        };
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000142", sequence));
+
+        let result = oeis
+            .sequence_stats(Parameters(FindRequest {
+                id: "A000142".to_string(),
+            }))
+            .await
+            .unwrap();
+
+        let structured = result.structured_content.unwrap();
+        assert_eq!(structured["count"], 23);
+        assert_eq!(structured["min"], "1");
+        assert_eq!(structured["max"], "1124000727777607680000");
+        assert_eq!(structured["sum"], "1177652997443428940314");
+        assert_eq!(structured["monotonicity"], "non_decreasing");
+    }
+
+    #[test]
+    fn test_decimal_int() {
+        let parse = |term: &str| term.parse::<DecimalInt>().unwrap();
+        assert_eq!(parse("-0"), DecimalInt::default());
+        assert_eq!(parse("007").to_string(), "7");
+        assert!("1.5".parse::<DecimalInt>().is_err());
+        assert!("-".parse::<DecimalInt>().is_err());
+
+        assert!(parse("-10") < parse("-9"));
+        assert!(parse("-1") < parse("0"));
+        assert!(parse("99") < parse("100"));
+
+        assert_eq!(parse("999").add(&parse("1")).to_string(), "1000");
+        assert_eq!(parse("1000").add(&parse("-1")).to_string(), "999");
+        assert_eq!(parse("-1000").add(&parse("1")).to_string(), "-999");
+        assert_eq!(parse("5").add(&parse("-12")).to_string(), "-7");
+        assert_eq!(parse("12").add(&parse("-12")).to_string(), "0");
+        assert_eq!(
+            parse("9223372036854775807")
+                .add(&parse("9223372036854775807"))
+                .to_string(),
+            "18446744073709551614"
+        );
+    }
+
+    #[test]
+    fn test_monotonicity() {
+        assert_eq!(monotonicity::<i64>(&[]), Monotonicity::Constant);
+        assert_eq!(monotonicity(&[3, 3, 3]), Monotonicity::Constant);
+        assert_eq!(monotonicity(&[5, 3, 3, 1]), Monotonicity::NonIncreasing);
+        assert_eq!(monotonicity(&[i64::MAX, i64::MAX]), Monotonicity::Constant);
+    }
+
+    #[tokio::test]
+    async fn test_detect_progression_tool_naturals() {
+        let naturals = OEISSequence {
+            data: "1, 2, 3, 4, 5, 6, 7, 8".to_string(),
+            ..create_test_sequence(27, "The positive integers.")
+        };
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000027", naturals));
+
+        let result = oeis
+            .detect_progression(Parameters(FindRequest {
+                id: "A000027".to_string(),
+            }))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            result.content.first().unwrap(),
+            &ContentBlock::json(json!(DetectProgressionResponse {
+                id: "A000027".to_string(),
+                count: 8,
+                arithmetic: true,
+                common_difference: Some(1),
+                geometric: false,
+                common_ratio: None,
+            }))
+            .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_detect_progression_tool_powers_of_two() {
+        let powers = OEISSequence {
+            data: "1, 2, 4, 8, 16, 32, 64".to_string(),
+            ..create_test_sequence(79, "Powers of 2: a(n) = 2^n.")
+        };
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000079", powers));
+
+        let result = oeis
+            .detect_progression(Parameters(FindRequest {
+                id: "A000079".to_string(),
+            }))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            result.content.first().unwrap(),
+            &ContentBlock::json(json!(DetectProgressionResponse {
+                id: "A000079".to_string(),
+                count: 7,
+                arithmetic: false,
+                common_difference: None,
+                geometric: true,
+                common_ratio: Some(2.0),
+            }))
+            .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_detect_progression_tool_too_short() {
+        let short = OEISSequence {
+            data: "1, 2".to_string(),
+            ..create_test_sequence(1, "Short sequence")
+        };
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000001", short));
+
+        let error = oeis
+            .detect_progression(Parameters(FindRequest {
+                id: "A000001".to_string(),
+            }))
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+        assert_eq!(
+            error.message,
+            "A000001 has 2 term(s); at least 3 are needed to detect a progression"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_detect_progression_tool_term_too_large() {
+        let powers = OEISSequence {
+            data: "1, 10, 100, 100000000000000000000".to_string(),
+            ..create_test_sequence(11557, "Powers of 10")
+        };
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A011557", powers));
+
+        let error = oeis
+            .detect_progression(Parameters(FindRequest {
+                id: "A011557".to_string(),
+            }))
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+        assert_eq!(error.data.unwrap()["n"], 3);
+    }
+
+    #[test]
+    fn test_common_difference_and_ratio() {
+        assert_eq!(common_difference(&[7, 7, 7]), Some(0));
+        assert_eq!(common_difference(&[10, 7, 4, 1]), Some(-3));
+        assert_eq!(common_difference(&[i64::MIN, 0, i64::MAX]), None);
+        assert_eq!(common_difference(&[0, 1, 1, 2]), None);
+
+        assert_eq!(common_ratio(&[7, 7, 7]), Some(1.0));
+        assert_eq!(common_ratio(&[16, 8, 4, 2]), Some(0.5));
+        assert_eq!(common_ratio(&[1, -3, 9, -27]), Some(-3.0));
+        assert_eq!(common_ratio(&[0, 0, 0]), None);
+        assert_eq!(common_ratio(&[1, 2, 4, 7]), None);
+    }
+
+    #[tokio::test]
+    async fn test_terms_mod_tool_fibonacci_pisano_period() {
+        // F(0)..F(130); everything past F(92) overflows `i64`
+        let mut fibonacci = vec![0u128, 1];
+        while fibonacci.len() <= 130 {
+            fibonacci.push(fibonacci[fibonacci.len() - 1] + fibonacci[fibonacci.len() - 2]);
+        }
+        let sequence = OEISSequence {
+            data: fibonacci
+                .iter()
+                .map(u128::to_string)
+                .collect::<Vec<String>>()
+                .join(", "),
+            ..create_test_sequence(45, "Fibonacci numbers")
+        };
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000045", sequence));
+
+        let result = oeis
+            .terms_mod(Parameters(TermsModRequest {
+                id: "A000045".to_string(),
+                modulus: 10,
+            }))
+            .await
+            .unwrap();
+
+        let structured = result.structured_content.unwrap();
+        assert_eq!(structured["id"], "A000045");
+        assert_eq!(structured["modulus"], 10);
+        let residues: Vec<i64> = serde_json::from_value(structured["residues"].clone()).unwrap();
+        assert_eq!(residues.len(), 131);
+        assert_eq!(residues[..10], [0, 1, 1, 2, 3, 5, 8, 3, 1, 4]);
+        // The Pisano period for 10 is 60
+        assert!((0..residues.len() - 60).all(|n| residues[n] == residues[n + 60]));
+        assert_eq!(residues[..60].iter().filter(|&&r| r == 0).count(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_terms_mod_tool_rejects_zero_modulus() {
+        let oeis = OEIS::new(
+            MockOEISClient::new()
+                .with_sequence("A000045", create_test_sequence(45, "Fibonacci numbers")),
+        );
+
+        for modulus in [0, -3] {
+            let error = oeis
+                .terms_mod(Parameters(TermsModRequest {
+                    id: "A000045".to_string(),
+                    modulus,
+                }))
+                .await
+                .unwrap_err();
+
+            assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+            assert_eq!(error.message, "modulus must be greater than 0");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_server_status_tool() {
+        let oeis = OEIS::new(
+            MockOEISClient::new()
+                .with_sequence("A000045", create_test_sequence(45, "Fibonacci numbers")),
+        );
+
+        let result = oeis
+            .server_status(Parameters(EmptyRequest {}))
+            .await
+            .unwrap();
+
+        let structured = result.structured_content.unwrap();
+        assert_eq!(structured["name"], env!("CARGO_PKG_NAME"));
+        assert_eq!(structured["version"], env!("CARGO_PKG_VERSION"));
+        assert!(!structured["version"].as_str().unwrap().is_empty());
+        assert!(structured["uptime_secs"].is_u64());
+        assert_eq!(structured["oeis_reachable"], true);
+        assert!(structured["oeis_error"].is_null());
+    }
+
+    #[tokio::test]
+    async fn test_server_status_tool_oeis_unreachable() {
+        let oeis = OEIS::new(MockOEISClient::new().with_error("A000045"));
+
+        // An unreachable OEIS is reported, not turned into a tool error
+        let result = oeis
+            .server_status(Parameters(EmptyRequest {}))
+            .await
+            .unwrap();
+
+        let structured = result.structured_content.unwrap();
+        assert_eq!(structured["oeis_reachable"], false);
+        assert_eq!(structured["oeis_error"], "Could not reach OEIS: Mock error");
+    }
+
+    #[tokio::test]
+    async fn test_sequence_age_context_tool() {
+        let sequence = OEISSequence {
+            created: Some("1991-04-30T03:00:00-04:00".to_string()),
+            ..create_test_sequence(45, "Fibonacci numbers")
+        };
+        let oeis = OEIS::new(
+            MockOEISClient::new()
+                .with_sequence("A000045", sequence)
+                .with_latest_sequence(create_test_sequence(390001, "Newest")),
+        );
+
+        let result = oeis
+            .sequence_age_context(Parameters(FindRequest {
+                id: "A000045".to_string(),
+            }))
+            .await
+            .unwrap();
+
+        let structured = result.structured_content.unwrap();
+        assert_eq!(structured["id"], "A000045");
+        assert_eq!(structured["created"], "1991-04-30T03:00:00-04:00");
+        assert_eq!(structured["latest_id"], "A390001");
+        assert_eq!(structured["newer_sequences"], 389956);
+        assert!(structured["position"].as_f64().unwrap() < 0.001);
+        assert_eq!(
+            structured["context"],
+            "A000045 (created 1991-04-30) is among the oldest quarter of OEIS sequences; \
+             about 389956 sequences have been added after it, up to A390001"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sequence_age_context_tool_without_created() {
+        let oeis = OEIS::new(
+            MockOEISClient::new()
+                .with_sequence("A390001", create_test_sequence(390001, "Newest"))
+                .with_latest_sequence(create_test_sequence(389998, "Older")),
+        );
+
+        let result = oeis
+            .sequence_age_context(Parameters(FindRequest {
+                id: "A390001".to_string(),
+            }))
+            .await
+            .unwrap();
+
+        // A sequence newer than the recent entries page becomes the latest itself
+        let structured = result.structured_content.unwrap();
+        assert!(structured["created"].is_null());
+        assert_eq!(structured["latest_id"], "A390001");
+        assert_eq!(structured["position"], 1.0);
+        assert_eq!(
+            structured["context"],
+            "A390001 is one of the most recent additions to OEIS; it is the newest entry"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sequence_age_context_tool_no_recent_sequences() {
+        let oeis = OEIS::new(
+            MockOEISClient::new()
+                .with_sequence("A000045", create_test_sequence(45, "Fibonacci numbers")),
+        );
+
+        let error = oeis
+            .sequence_age_context(Parameters(FindRequest {
+                id: "A000045".to_string(),
+            }))
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.code, ErrorCode::INTERNAL_ERROR);
+    }
+
+    #[test]
+    fn test_decimal_mod() {
+        assert_eq!(decimal_mod("0", 7), Some(0));
+        assert_eq!(decimal_mod("12345", 7), Some(12345 % 7));
+        assert_eq!(decimal_mod("-7", 3), Some(2));
+        assert_eq!(decimal_mod("-9", 3), Some(0));
+        assert_eq!(decimal_mod("5", 1), Some(0));
+        // 10^30 + 7 = (10^30 mod 9 = 1) + 7
+        assert_eq!(decimal_mod("1000000000000000000000000000007", 9), Some(8));
+        assert_eq!(
+            decimal_mod("123", i64::MAX),
+            Some(123),
+            "largest modulus must not overflow"
+        );
+        assert_eq!(decimal_mod("", 5), None);
+        assert_eq!(decimal_mod("-", 5), None);
+        assert_eq!(decimal_mod("1.5", 5), None);
+    }
+
+    #[tokio::test]
+    async fn test_consecutive_ratios_tool_fibonacci() {
+        let fibonacci = OEISSequence {
+            data: "0, 1, 1, 2, 3, 5, 8, 13, 21, 34, 55, 89, 144, 233, 377, 610, 987".to_string(),
+            ..create_test_sequence(45, "Fibonacci numbers")
+        };
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000045", fibonacci));
+
+        let result = oeis
+            .consecutive_ratios(Parameters(FindRequest {
+                id: "A000045".to_string(),
+            }))
+            .await
+            .unwrap();
+
+        let structured = result.structured_content.unwrap();
+        // a(0) = 0, so a(1)/a(0) is skipped
+        assert_eq!(structured["skipped"], json!([0]));
+        let ratios = structured["ratios"].as_array().unwrap();
+        assert_eq!(ratios.len(), 15);
+        assert_eq!(ratios[0]["n"], 1);
+        assert_eq!(ratios[0]["ratio"], 1.0);
+
+        let golden_ratio = (1.0 + 5f64.sqrt()) / 2.0;
+        let errors: Vec<f64> = ratios
+            .iter()
+            .map(|ratio| (ratio["ratio"].as_f64().unwrap() - golden_ratio).abs())
+            .collect();
+        assert!(errors[errors.len() - 1] < 1e-5);
+        assert!(errors[errors.len() - 1] < errors[errors.len() / 2]);
+        assert!(errors[errors.len() / 2] < errors[0]);
     }
 
-    fn build_assistant_messages(&self, sequence: &OEISSequence) -> PromptMessage {
-        let sequence_id_formatted = format!("A{:06}", sequence.number);
-        let comments_section = self.empty_or_join("Comments", &sequence.comment);
-        let formulas_section = self.empty_or_join("Formulas", &sequence.formula);
-        let xref_section = self.empty_or_join("Cross-references", &sequence.xref);
+    #[tokio::test]
+    async fn test_consecutive_ratios_tool_terms_past_i64() {
+        let factorials = OEISSequence {
+            data: "1, 1, 2, 6, 51090942171709440000, 1124000727777607680000".to_string(),
+            ..create_test_sequence(142, "Factorial numbers")
+        };
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000142", factorials));
 
-        let analysis_context = format!(
-            "# OEIS Sequence {}\n\n\
-            **Name:** {}\n\n\
-            **Data (first few terms):** {}\n\n\
-            **Keywords:** {}\n\n\
-            {}{}{}",
-            sequence_id_formatted,
-            sequence.name,
-            sequence.data,
-            sequence.keyword,
-            comments_section,
-            formulas_section,
-            xref_section,
-        );
+        let result = oeis
+            .consecutive_ratios(Parameters(FindRequest {
+                id: "A000142".to_string(),
+            }))
+            .await
+            .unwrap();
 
-        PromptMessage::new_text(Role::Assistant, analysis_context)
+        // 22! / 21! = 22
+        let structured = result.structured_content.unwrap();
+        let last = &structured["ratios"][4];
+        assert_eq!(last["n"], 4);
+        assert!((last["ratio"].as_f64().unwrap() - 22.0).abs() < 1e-9);
     }
 
-    fn empty_or_join(&self, title: &str, contents: &Option<Vec<String>>) -> String {
-        if contents.clone().is_none_or(|c| c.is_empty()) {
-            String::new()
-        } else {
-            format!(
-                "**{}:**\n{}\n\n",
-                title,
-                contents.as_ref().unwrap().join("\n")
-            )
-        }
+    #[tokio::test]
+    async fn test_verify_recurrence_tool_fibonacci() {
+        let fibonacci = create_test_sequence(45, "Fibonacci numbers");
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000045", fibonacci));
+        let params = Parameters(VerifyRecurrenceRequest {
+            id: "A000045".to_string(),
+            coefficients: vec![1, 1],
+            order: 2,
+        });
+
+        let result = oeis.verify_recurrence(params).await.unwrap();
+
+        let structured = result.structured_content.unwrap();
+        assert_eq!(structured["id"], "A000045");
+        assert_eq!(structured["holds"], true);
+        assert_eq!(structured["matched"], json!([2, 3, 4, 5, 6]));
+        assert_eq!(structured["failed"], json!([]));
     }
-}
 
-#[tool_handler]
-#[prompt_handler]
-impl<C: OEISClient + Clone + 'static> ServerHandler for OEIS<C> {
-    fn get_info(&self) -> ServerInfo {
-        let capabilities = ServerCapabilities::builder()
-            .enable_prompts()
-            .enable_resources()
-            .enable_tools()
-            .build();
-        let server_info = Implementation::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))
-            .with_title("OEIS MCP server")
-            .with_description(env!("CARGO_PKG_DESCRIPTION"))
-            .with_website_url("https://github.com/23prime/oeis-mcp-server-rs");
-        ServerInfo::new(capabilities)
-            .with_protocol_version(ProtocolVersion::V_2025_06_18)
-            .with_server_info(server_info)
-            .with_instructions("This server provides access to the OEIS (Online Encyclopedia of Integer Sequences) database. Tools: get_url (returns the OEIS homepage URL), find_by_id (search for a sequence by ID like 'A000045'), search_by_subsequence (search for sequences matching a given subsequence like [1,1,2,3,5]). Prompts: sequence_analysis (provides comprehensive analysis of an OEIS sequence). Resources: oeis://sequence/{id} (direct access to sequence data as JSON). Use this server to look up integer sequences, analyze their mathematical properties, and explore relationships between sequences.")
+    #[tokio::test]
+    async fn test_verify_recurrence_tool_reports_failures() {
+        let fibonacci = create_test_sequence(45, "Fibonacci numbers");
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000045", fibonacci));
+        let params = Parameters(VerifyRecurrenceRequest {
+            id: "A000045".to_string(),
+            coefficients: vec![2, 0],
+            order: 2,
+        });
+
+        let result = oeis.verify_recurrence(params).await.unwrap();
+
+        let structured = result.structured_content.unwrap();
+        assert_eq!(structured["holds"], false);
+        assert_eq!(structured["matched"], json!([3]));
+        assert_eq!(
+            structured["failed"][0],
+            json!({"n": 2, "actual": "1", "predicted": "2"})
+        );
+        assert_eq!(structured["failed"].as_array().unwrap().len(), 4);
     }
 
-    async fn list_resource_templates(
-        &self,
-        _request: Option<PaginatedRequestParams>,
-        _: RequestContext<RoleServer>,
-    ) -> Result<ListResourceTemplatesResult, McpError> {
-        info!("Listing resource templates");
+    #[tokio::test]
+    async fn test_verify_recurrence_tool_prediction_past_i64() {
+        let fibonacci = create_test_sequence(45, "Fibonacci numbers");
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000045", fibonacci));
+        let params = Parameters(VerifyRecurrenceRequest {
+            id: "A000045".to_string(),
+            coefficients: vec![5_000_000_000_000_000_000, 5_000_000_000_000_000_000],
+            order: 2,
+        });
 
-        Ok(ListResourceTemplatesResult {
-            resource_templates: vec![
-                ResourceTemplate::new("oeis://sequence/{id}", "OEIS Sequence")
-                    .with_description("OEIS sequence data by ID (e.g., A000045)")
-                    .with_mime_type("application/json"),
-            ],
-            next_cursor: None,
-            meta: None,
-        })
+        let result = oeis.verify_recurrence(params).await.unwrap();
+
+        // a(6) is predicted as 5 * 10^18 * (a(5) + a(4)) = 4 * 10^19, past even u64::MAX
+        let structured = result.structured_content.unwrap();
+        assert_eq!(structured["holds"], false);
+        assert_eq!(structured["matched"], json!([]));
+        assert_eq!(
+            structured["failed"][4],
+            json!({"n": 6, "actual": "8", "predicted": "40000000000000000000"})
+        );
     }
 
-    async fn read_resource(
-        &self,
-        ReadResourceRequestParams { uri, .. }: ReadResourceRequestParams,
-        _: RequestContext<RoleServer>,
-    ) -> Result<ReadResourceResult, McpError> {
-        info!("Reading resource: {:?}", uri);
+    #[tokio::test]
+    async fn test_verify_recurrence_tool_term_too_large() {
+        let factorials = OEISSequence {
+            data: "1, 1, 2, 6, 24, 120, 2432902008176640000, 51090942171709440000".to_string(),
+            offset: Some("0,3".to_string()),
+            ..create_test_sequence(142, "Factorial numbers")
+        };
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000142", factorials));
+        let params = Parameters(VerifyRecurrenceRequest {
+            id: "A000142".to_string(),
+            coefficients: vec![1, 1],
+            order: 2,
+        });
 
-        // Parse URI pattern: oeis://sequence/{id}
-        if let Some(id) = uri.strip_prefix("oeis://sequence/") {
-            let sequence = self.find_sequence(id).await?;
+        let error = oeis.verify_recurrence(params).await.unwrap_err();
 
-            // Return JSON representation of the sequence
-            let json_content = serde_json::to_string_pretty(&sequence)
-                .map_err(|e| McpError::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+        assert_eq!(
+            error.message,
+            "A000142 has a term too large for this tool: a(7) = 51090942171709440000 does not fit in i64"
+        );
+        assert_eq!(
+            error.data,
+            Some(json!({"id": "A000142", "n": 7, "term": "51090942171709440000"}))
+        );
+    }
 
-            Ok(ReadResourceResult::new(vec![ResourceContents::text(
-                &json_content,
-                uri,
-            )]))
-        } else {
-            Err(McpError::new(
-                ErrorCode::INVALID_PARAMS,
-                format!(
-                    "Invalid resource URI: {}. Expected format: oeis://sequence/{{id}}",
-                    uri
-                ),
-                Some(json!({"uri": uri})),
-            ))
-        }
+    #[tokio::test]
+    async fn test_verify_recurrence_tool_order_mismatch() {
+        let oeis = OEIS::new(MockOEISClient::new());
+        let params = Parameters(VerifyRecurrenceRequest {
+            id: "A000045".to_string(),
+            coefficients: vec![1, 1],
+            order: 3,
+        });
+
+        let error = oeis.verify_recurrence(params).await.unwrap_err();
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use anyhow::anyhow;
-    use async_trait::async_trait;
-    use std::collections::HashMap;
+    #[test]
+    fn test_parse_terms() {
+        let sequence = |data: &str| OEISSequence {
+            data: data.to_string(),
+            ..create_test_sequence(45, "Fibonacci numbers")
+        };
 
-    // Mock OEIS Client for testing
-    #[derive(Clone)]
-    enum MockResponse {
-        // TODO: normalize Option / Vec
-        Success(Option<OEISSequence>),
-        SuccessMulti(Vec<OEISSequence>),
-        Error,
+        let terms: Vec<i64> = parse_terms(&sequence("0, 1, 1, 2, 3, 5, 8")).unwrap();
+        assert_eq!(terms, vec![0, 1, 1, 2, 3, 5, 8]);
+        let terms: Vec<i64> = parse_terms(&sequence("-1,0,1")).unwrap();
+        assert_eq!(terms, vec![-1, 0, 1]);
+        let terms: Vec<i64> = parse_terms(&sequence("")).unwrap();
+        assert!(terms.is_empty());
+
+        let error = parse_terms::<i64>(&sequence("1, 2, 99999999999999999999")).unwrap_err();
+        assert_eq!(
+            error.data,
+            Some(json!({"id": "A000045", "n": 2, "term": "99999999999999999999"}))
+        );
+        let terms: Vec<i128> = parse_terms(&sequence("1, 2, 99999999999999999999")).unwrap();
+        assert_eq!(terms[2], 99_999_999_999_999_999_999);
     }
 
-    #[derive(Clone)]
-    struct MockOEISClient {
-        responses: HashMap<String, MockResponse>,
+    #[test]
+    fn test_finite_differences_does_not_overflow_i64() {
+        let terms = [i64::MIN as i128, i64::MAX as i128];
+        let differences = finite_differences(&terms, 1).unwrap();
+        assert_eq!(differences, vec![i64::MAX as i128 - i64::MIN as i128]);
+        assert_eq!(finite_differences(&[i128::MIN, i128::MAX], 1), None);
     }
 
-    impl MockOEISClient {
-        fn new() -> Self {
-            Self {
-                responses: HashMap::new(),
-            }
-        }
+    #[tokio::test]
+    async fn test_get_terms_tool_zero_count() {
+        let fibonacci = create_test_sequence(45, "Fibonacci numbers");
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000045", fibonacci));
+        let params = Parameters(GetTermsRequest {
+            id: "A000045".to_string(),
+            count: 0,
+        });
 
-        fn with_sequence(mut self, id: &str, sequence: OEISSequence) -> Self {
-            self.responses
-                .insert(id.to_string(), MockResponse::Success(Some(sequence)));
-            self
-        }
+        let result = oeis.get_terms(params).await;
+        assert!(result.is_err());
 
-        fn with_sequences(mut self, subsequence: &[i64], sequences: Vec<OEISSequence>) -> Self {
-            self.responses.insert(
-                subsequence
-                    .iter()
-                    .map(|i| i.to_string())
-                    .collect::<Vec<String>>()
-                    .join(","),
-                MockResponse::SuccessMulti(sequences),
-            );
-            self
-        }
+        let error = result.unwrap_err();
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+    }
 
-        fn with_not_found(mut self, id: &str) -> Self {
-            self.responses
-                .insert(id.to_string(), MockResponse::Success(None));
-            self
-        }
+    #[tokio::test]
+    async fn test_get_terms_tool_not_found() {
+        let oeis = OEIS::new(MockOEISClient::new().with_not_found("A999999"));
+        let params = Parameters(GetTermsRequest {
+            id: "A999999".to_string(),
+            count: 5,
+        });
 
-        fn with_error(mut self, id: &str) -> Self {
-            self.responses.insert(id.to_string(), MockResponse::Error);
-            self
-        }
+        let result = oeis.get_terms(params).await;
+        assert!(result.is_err());
+
+        let error = result.unwrap_err();
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+        assert!(error.message.contains("No sequence found"));
     }
 
-    #[async_trait]
-    impl OEISClient for MockOEISClient {
-        async fn find_by_id(&self, id: &str) -> anyhow::Result<Option<OEISSequence>> {
-            match self.responses.get(id) {
-                Some(MockResponse::Success(sequence)) => Ok(sequence.clone()),
-                Some(MockResponse::SuccessMulti(_)) => {
-                    Err(anyhow!("MockOEISClient: use Success for find_by_id"))
-                }
-                Some(MockResponse::Error) => Err(anyhow!("Mock error")),
-                None => Ok(None),
-            }
-        }
+    #[tokio::test]
+    async fn test_resource_text_sequence_json_by_default() {
+        let fibonacci = create_test_sequence(45, "Fibonacci numbers");
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000045", fibonacci));
 
-        async fn search_by_subsequence(
-            &self,
-            subsequence: &[i64],
-        ) -> anyhow::Result<Vec<OEISSequence>> {
-            let key = subsequence
-                .iter()
-                .map(|i| i.to_string())
-                .collect::<Vec<String>>()
-                .join(",");
+        let (text, format) = oeis.resource_text("oeis://sequence/A000045").await.unwrap();
 
-            match self.responses.get(&key) {
-                Some(MockResponse::SuccessMulti(sequences)) => Ok(sequences.clone()),
-                Some(MockResponse::Success(_)) => Err(anyhow!(
-                    "MockOEISClient: use SuccessMulti for subsequence searches"
-                )),
-                Some(MockResponse::Error) => Err(anyhow!("Mock error")),
-                None => Ok(vec![]),
-            }
-        }
+        assert_eq!(format, ResourceFormat::Json);
+        let value: serde_json::Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(value["number"], 45);
+        assert_eq!(value["name"], "Fibonacci numbers");
     }
 
-    fn create_test_sequence(number: i64, name: &str) -> OEISSequence {
-        OEISSequence {
-            number,
-            data: "0, 1, 1, 2, 3, 5, 8".to_string(),
-            name: name.to_string(),
-            comment: Some(vec!["Test comment".to_string()]),
-            formula: Some(vec!["Test formula".to_string()]),
-            xref: Some(vec!["A000001".to_string()]),
-            keyword: "nonn".to_string(),
-        }
+    #[tokio::test]
+    async fn test_resource_text_sequence_revision() {
+        let fibonacci = OEISSequence {
+            revision: Some(1234),
+            ..create_test_sequence(45, "Fibonacci numbers")
+        };
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000045", fibonacci));
+
+        let (text, _) = oeis.resource_text("oeis://sequence/A000045").await.unwrap();
+        let value: serde_json::Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(value["revision"], 1234);
+
+        let (text, _) = oeis
+            .resource_text("oeis://sequence/A000045?format=markdown")
+            .await
+            .unwrap();
+        assert!(text.contains("**Revision:** 1234"));
     }
 
-    // test for mock client
     #[tokio::test]
-    async fn test_mock_client() {
-        // Test that our mock client works correctly
+    async fn test_resource_text_sequence_markdown() {
         let fibonacci = create_test_sequence(45, "Fibonacci numbers");
-        let client = MockOEISClient::new().with_sequence("A000045", fibonacci.clone());
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000045", fibonacci));
 
-        let result = client.find_by_id("A000045").await.unwrap();
-        assert!(result.is_some());
+        let (text, format) = oeis
+            .resource_text("oeis://sequence/A000045?format=markdown")
+            .await
+            .unwrap();
 
-        let sequence = result.unwrap();
-        assert_eq!(sequence.number, 45);
-        assert_eq!(sequence.name, "Fibonacci numbers");
+        assert_eq!(format, ResourceFormat::Markdown);
+        assert!(text.contains("Fibonacci numbers"));
+        assert!(text.contains("**Offset:** 0,4"));
+        assert!(serde_json::from_str::<serde_json::Value>(&text).is_err());
     }
 
     #[tokio::test]
-    async fn test_mock_client_not_found() {
-        let client = MockOEISClient::new().with_not_found("NON_EXISTENT");
+    async fn test_resource_text_unsupported_format() {
+        let fibonacci = create_test_sequence(45, "Fibonacci numbers");
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000045", fibonacci));
 
-        let result = client.find_by_id("NON_EXISTENT").await.unwrap();
-        assert!(result.is_none());
+        let error = oeis
+            .resource_text("oeis://sequence/A000045?format=xml")
+            .await
+            .unwrap_err();
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+
+        let error = oeis
+            .resource_text("oeis://bfile/A000045?format=markdown")
+            .await
+            .unwrap_err();
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
     }
 
+    // Test for prompts
     #[test]
-    fn test_uri_parsing() {
-        let uri = "oeis://sequence/A000045";
-        let id = uri.strip_prefix("oeis://sequence/");
-        assert_eq!(id, Some("A000045"));
+    fn test_prompt_router_definition() {
+        let oeis = OEIS::new(MockOEISClient::new());
+        assert!(oeis.prompt_router.list_all().len() == 4);
+    }
+
+    #[tokio::test]
+    async fn test_generate_code_prompt() {
+        let fibonacci = OEISSequence {
+            maple: Some(vec![
+                "with(combinat): A000045 := n -> fibonacci(n);".to_string(),
+            ]),
+            program: Some(vec!["(PARI) a(n)=fibonacci(n)".to_string()]),
+            ..create_test_sequence(45, "Fibonacci numbers")
+        };
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000045", fibonacci));
+
+        let messages = oeis
+            .generate_code(Parameters(GenerateCodeRequest {
+                sequence_id: "A000045".to_string(),
+                language: " Rust ".to_string(),
+            }))
+            .await
+            .unwrap();
+        assert_eq!(messages.len(), 2);
+
+        assert_eq!(messages[0].role, Role::User);
+        if let ContentBlock::Text(TextContent { text, .. }) = &messages[0].content {
+            assert!(
+                text.contains("Please write a Rust program that generates OEIS sequence A000045")
+            );
+        } else {
+            panic!("Expected text content");
+        }
+
+        assert_eq!(messages[1].role, Role::Assistant);
+        if let ContentBlock::Text(TextContent { text, .. }) = &messages[1].content {
+            assert!(text.contains("**Data (first few terms):** 0, 1, 1, 2, 3, 5, 8"));
+            assert!(text.contains(
+                "**OEIS programs:**\n```\n(Maple) with(combinat): A000045 := n -> fibonacci(n);\n(PARI) a(n)=fibonacci(n)\n```"
+            ));
+        } else {
+            panic!("Expected text content");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_generate_code_prompt_without_programs() {
+        let fibonacci = create_test_sequence(45, "Fibonacci numbers");
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000045", fibonacci));
+
+        let messages = oeis
+            .generate_code(Parameters(GenerateCodeRequest {
+                sequence_id: "A000045".to_string(),
+                language: "Python".to_string(),
+            }))
+            .await
+            .unwrap();
+
+        if let ContentBlock::Text(TextContent { text, .. }) = &messages[1].content {
+            assert!(text.contains("**OEIS programs:** (none)"));
+        } else {
+            panic!("Expected text content");
+        }
     }
 
-    #[test]
-    fn test_uri_parsing_invalid() {
-        let invalid_uri = "invalid://uri";
-        let id = invalid_uri.strip_prefix("oeis://sequence/");
-        assert_eq!(id, None);
+    #[tokio::test]
+    async fn test_generate_code_prompt_empty_language() {
+        let oeis = OEIS::new(MockOEISClient::new());
+
+        let error = oeis
+            .generate_code(Parameters(GenerateCodeRequest {
+                sequence_id: "A000045".to_string(),
+                language: "  ".to_string(),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
     }
 
-    // test for find_sequence helper
     #[tokio::test]
-    async fn test_find_sequence_success() {
+    async fn test_sequence_analysis_prompt() {
         let fibonacci = create_test_sequence(45, "Fibonacci numbers");
         let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000045", fibonacci.clone()));
 
-        let result = oeis.find_sequence("A000045").await;
-        assert!(result.is_ok());
+        let params = Parameters(SequenceAnalysisRequest {
+            sequence_id: "A000045".to_string(),
+        });
 
-        let sequence = result.unwrap();
-        assert_eq!(sequence.number, 45);
-        assert_eq!(sequence.name, "Fibonacci numbers");
-    }
+        let result = oeis.sequence_analysis(params).await;
+        assert!(result.is_ok());
 
-    #[tokio::test]
-    async fn test_find_sequence_not_found() {
-        let oeis = OEIS::new(MockOEISClient::new().with_not_found("NON_EXISTENT"));
+        let messages = result.unwrap();
+        assert_eq!(messages.len(), 2);
 
-        let result = oeis.find_sequence("NON_EXISTENT").await;
-        assert!(result.is_err());
+        // Check first message is from user
+        assert_eq!(messages[0].role, Role::User);
+        if let ContentBlock::Text(TextContent { text, .. }) = &messages[0].content {
+            assert!(text.contains("comprehensive analysis"));
+            assert!(text.contains("A000045"));
+        } else {
+            panic!("Expected text content");
+        }
 
-        let error = result.unwrap_err();
-        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
-        assert!(error.message.contains("No sequence found"));
+        // Check second message is from assistant with sequence data
+        assert_eq!(messages[1].role, Role::Assistant);
+        if let ContentBlock::Text(TextContent { text, .. }) = &messages[1].content {
+            assert!(text.contains("Fibonacci numbers"));
+            assert!(text.contains("A000045"));
+            assert!(text.contains("0, 1, 1, 2, 3, 5, 8"));
+            assert!(text.contains("**Offset:** 0,4"));
+            assert!(text.contains("**Author:** _N. J. A. Sloane_"));
+        } else {
+            panic!("Expected text content");
+        }
     }
 
     #[tokio::test]
-    async fn test_find_sequence_error() {
-        let oeis = OEIS::new(MockOEISClient::new().with_error("ERROR_CASE"));
+    async fn test_sequence_analysis_prompt_examples_and_references() {
+        let fibonacci = OEISSequence {
+            example: Some(vec!["F(6) = F(5) + F(4) = 5 + 3 = 8.".to_string()]),
+            reference: Some(vec![
+                "D. E. Knuth, The Art of Computer Programming, Vol. 1.".to_string(),
+            ]),
+            ..create_test_sequence(45, "Fibonacci numbers")
+        };
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000045", fibonacci));
 
-        let result = oeis.find_sequence("ERROR_CASE").await;
-        assert!(result.is_err());
+        let params = Parameters(SequenceAnalysisRequest {
+            sequence_id: "A000045".to_string(),
+        });
 
-        let error = result.unwrap_err();
-        assert_eq!(error.code, ErrorCode::INTERNAL_ERROR);
-    }
+        let result = oeis.sequence_analysis(params).await;
+        assert!(result.is_ok());
 
-    // helpers for tool router definition checking
-    fn get_tool(tools: &[Tool], name: &str) -> Option<Tool> {
-        tools.iter().find(|t| t.name == name).cloned()
+        let messages = result.unwrap();
+        if let ContentBlock::Text(TextContent { text, .. }) = &messages[1].content {
+            assert!(text.contains("**Examples:**\nF(6) = F(5) + F(4) = 5 + 3 = 8."));
+            assert!(text.contains(
+                "**References:**\nD. E. Knuth, The Art of Computer Programming, Vol. 1."
+            ));
+        } else {
+            panic!("Expected text content");
+        }
     }
 
-    fn get_tool_description(tool: Tool) -> String {
-        tool.description.as_ref().unwrap().clone().into_owned()
+    #[tokio::test]
+    async fn test_sequence_analysis_prompt_escapes_markdown() {
+        let fibonacci = OEISSequence {
+            comment: Some(vec![
+                "See *emphasis* and _underscore_ in `code`.".to_string(),
+            ]),
+            formula: Some(vec!["a(n) = 2*a(n-1) - a(n-3)*a_0.".to_string()]),
+            ..create_test_sequence(45, "Fibonacci numbers")
+        };
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000045", fibonacci));
+
+        let params = Parameters(SequenceAnalysisRequest {
+            sequence_id: "A000045".to_string(),
+        });
+
+        let messages = oeis.sequence_analysis(params).await.unwrap();
+        if let ContentBlock::Text(TextContent { text, .. }) = &messages[1].content {
+            assert!(
+                text.contains(
+                    "**Comments:**\nSee \\*emphasis\\* and \\_underscore\\_ in \\`code\\`."
+                )
+            );
+            assert!(text.contains("**Formulas:**\na(n) = 2\\*a(n-1) - a(n-3)\\*a\\_0."));
+        } else {
+            panic!("Expected text content");
+        }
     }
 
     #[test]
-    fn test_tool_router_definition() {
-        let oeis = OEIS::new(MockOEISClient::new());
-
-        let tools = oeis.tool_router.list_all();
-        assert!(tools.len() == 3);
+    fn test_escape_markdown() {
+        assert_eq!(escape_markdown("plain text"), "plain text");
+        assert_eq!(escape_markdown(r"a\b"), r"a\\b");
+        assert_eq!(escape_markdown("**bold**"), r"\*\*bold\*\*");
+    }
 
-        let get_url_tool = get_tool(&tools, "get_url");
-        assert!(get_url_tool.is_some());
-        assert!(get_tool_description(get_url_tool.unwrap()) == "Get a URL of OEIS entry.");
+    #[tokio::test]
+    async fn test_sequence_analysis_prompt_links() {
+        let fibonacci = OEISSequence {
+            link: Some(vec![
+                "N. J. A. Sloane, <a href=\"/A000045/b000045.txt\">Table of n, a(n) for n = 0..500</a>"
+                    .to_string(),
+            ]),
+            ..create_test_sequence(45, "Fibonacci numbers")
+        };
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000045", fibonacci));
 
-        let find_by_id_tool = get_tool(&tools, "find_by_id");
-        assert!(find_by_id_tool.is_some());
-        assert!(get_tool_description(find_by_id_tool.unwrap()) == "Find a sequence by its ID.");
+        let params = Parameters(SequenceAnalysisRequest {
+            sequence_id: "A000045".to_string(),
+        });
 
-        let search_by_subsequence_tool = get_tool(&tools, "search_by_subsequence");
-        assert!(search_by_subsequence_tool.is_some());
-        assert!(
-            get_tool_description(search_by_subsequence_tool.unwrap())
-                == "Search sequences by subsequence."
-        );
+        let messages = oeis.sequence_analysis(params).await.unwrap();
+        if let ContentBlock::Text(TextContent { text, .. }) = &messages[1].content {
+            assert!(text.contains(
+                "**Links:**\n- [Table of n, a(n) for n = 0..500](https://oeis.org/A000045/b000045.txt)"
+            ));
+        } else {
+            panic!("Expected text content");
+        }
     }
 
-    #[tokio::test]
-    async fn test_get_url_tool() {
-        let oeis = OEIS::new(MockOEISClient::new());
-
-        let result = oeis.get_url(Parameters(EmptyRequest {})).await;
-        assert!(result.is_ok());
+    #[test]
+    fn test_extract_links() {
+        let raw = vec![
+            "N. J. A. Sloane, <a href=\"/A000045/b000045.txt\">Table of n, a(n) for n = 0..500</a>"
+                .to_string(),
+            "R. Knott, <a href=\"http://www.maths.surrey.ac.uk/fib.html\">Fibonacci numbers</a> and <a href=\"https://example.com/phi\"> The golden ratio </a>"
+                .to_string(),
+            "Plain text without a link".to_string(),
+        ];
 
-        let content = result.unwrap().content;
-        assert_eq!(content.len(), 1);
         assert_eq!(
-            content.first().unwrap(),
-            &ContentBlock::text("https://oeis.org")
+            extract_links(&raw),
+            vec![
+                (
+                    "Table of n, a(n) for n = 0..500".to_string(),
+                    "https://oeis.org/A000045/b000045.txt".to_string()
+                ),
+                (
+                    "Fibonacci numbers".to_string(),
+                    "http://www.maths.surrey.ac.uk/fib.html".to_string()
+                ),
+                (
+                    "The golden ratio".to_string(),
+                    "https://example.com/phi".to_string()
+                ),
+            ]
         );
     }
 
     #[tokio::test]
-    async fn test_find_by_id_tool_found() {
-        let fibonacci = create_test_sequence(45, "Fibonacci numbers");
-        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000045", fibonacci.clone()));
-        let params = Parameters(FindRequest {
-            id: "A000045".to_string(),
+    async fn test_sequence_analysis_prompt_missing_optional_fields() {
+        let sparse = OEISSequence {
+            number: 45,
+            data: "0, 1, 1, 2, 3, 5, 8".to_string(),
+            name: "Fibonacci numbers".to_string(),
+            keyword: "nonn".to_string(),
+            ..Default::default()
+        };
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000045", sparse));
+
+        let params = Parameters(SequenceAnalysisRequest {
+            sequence_id: "A000045".to_string(),
         });
 
-        let result = oeis.find_by_id(params).await;
+        let result = oeis.sequence_analysis(params).await;
         assert!(result.is_ok());
 
-        let content = result.unwrap().content;
-        assert_eq!(content.len(), 1);
+        let messages = result.unwrap();
+        if let ContentBlock::Text(TextContent { text, .. }) = &messages[1].content {
+            assert!(text.contains("Fibonacci numbers"));
+            assert!(!text.contains("None"));
+            assert!(!text.contains("**Offset:**"));
+            assert!(!text.contains("**Author:**"));
+            assert!(!text.contains("**Comments:**"));
+            assert!(!text.contains("**Formulas:**"));
+            assert!(!text.contains("**Cross-references:**"));
+            assert!(!text.contains("**Examples:**"));
+            assert!(!text.contains("**References:**"));
+            assert!(!text.contains("**Links:**"));
+        } else {
+            panic!("Expected text content");
+        }
+    }
+
+    #[test]
+    fn test_empty_or_join() {
+        let oeis = OEIS::new(MockOEISClient::new());
 
+        assert_eq!(oeis.empty_or_join("Comments", &None), "");
+        assert_eq!(oeis.empty_or_join("Comments", &Some(vec![])), "");
         assert_eq!(
-            content.first().unwrap(),
-            &ContentBlock::json(json!(FindResponse { result: fibonacci })).unwrap()
+            oeis.empty_or_join("Comments", &Some(vec!["a".to_string(), "b".to_string()])),
+            "**Comments:**\na\nb\n\n"
         );
     }
 
     #[tokio::test]
-    async fn test_find_by_id_tool_not_found() {
-        let oeis = OEIS::new(MockOEISClient::new().with_not_found("NON_EXISTENT"));
-        let params = Parameters(FindRequest {
-            id: "NON_EXISTENT".to_string(),
+    async fn test_sequence_analysis_prompt_not_found() {
+        let oeis = OEIS::new(MockOEISClient::new().with_not_found("A999999"));
+
+        let params = Parameters(SequenceAnalysisRequest {
+            sequence_id: "A999999".to_string(),
         });
 
-        let result = oeis.find_by_id(params).await;
+        let result = oeis.sequence_analysis(params).await;
         assert!(result.is_err());
 
         let error = result.unwrap_err();
@@ -526,13 +8855,14 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_find_by_id_tool_error() {
-        let oeis = OEIS::new(MockOEISClient::new().with_error("ERROR_CASE"));
-        let params = Parameters(FindRequest {
-            id: "ERROR_CASE".to_string(),
+    async fn test_sequence_analysis_prompt_error() {
+        let oeis = OEIS::new(MockOEISClient::new().with_error("A000500"));
+
+        let params = Parameters(SequenceAnalysisRequest {
+            sequence_id: "A000500".to_string(),
         });
 
-        let result = oeis.find_by_id(params).await;
+        let result = oeis.sequence_analysis(params).await;
         assert!(result.is_err());
 
         let error = result.unwrap_err();
@@ -540,143 +8870,201 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_search_by_subsequence_tool_found() {
+    async fn test_explain_sequence_prompt_differs_per_level() {
         let fibonacci = create_test_sequence(45, "Fibonacci numbers");
-        let catalan = create_test_sequence(108, "Catalan numbers");
-        let sequences = vec![fibonacci.clone(), catalan.clone()];
+        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000045", fibonacci));
 
-        let oeis = OEIS::new(
-            MockOEISClient::new().with_sequences(&[0, 1, 1, 2, 3, 5, 8], sequences.clone()),
-        );
-        let params = Parameters(SearchRequest {
-            subsequence: vec![0, 1, 1, 2, 3, 5, 8],
-        });
+        let mut user_texts = Vec::new();
+        for level in [
+            Level::Child,
+            Level::HighSchool,
+            Level::Undergrad,
+            Level::Expert,
+        ] {
+            let params = Parameters(ExplainSequenceRequest {
+                sequence_id: "A000045".to_string(),
+                level,
+            });
+            let messages = oeis.explain_sequence(params).await.unwrap();
+            assert_eq!(messages.len(), 2);
 
-        let result = oeis.search_by_subsequence(params).await;
-        assert!(result.is_ok());
+            assert_eq!(messages[0].role, Role::User);
+            if let ContentBlock::Text(TextContent { text, .. }) = &messages[0].content {
+                assert!(text.contains("A000045"));
+                user_texts.push(text.clone());
+            } else {
+                panic!("Expected text content");
+            }
 
-        let content = result.unwrap().content;
-        assert_eq!(content.len(), 1);
+            // The sequence context is the same whatever the level
+            assert_eq!(messages[1].role, Role::Assistant);
+            if let ContentBlock::Text(TextContent { text, .. }) = &messages[1].content {
+                assert!(text.contains("Fibonacci numbers"));
+                assert!(text.contains("0, 1, 1, 2, 3, 5, 8"));
+                assert!(text.contains("Test formula"));
+            } else {
+                panic!("Expected text content");
+            }
+        }
 
-        assert_eq!(
-            content.first().unwrap(),
-            &ContentBlock::json(json!(SearchResponse { results: sequences })).unwrap()
+        assert!(user_texts[0].contains("child"));
+        assert!(user_texts[3].contains("expert"));
+        for (i, text) in user_texts.iter().enumerate() {
+            for other in &user_texts[i + 1..] {
+                assert_ne!(text, other);
+            }
+        }
+    }
+
+    #[test]
+    fn test_level_deserializes_snake_case() {
+        let request: ExplainSequenceRequest =
+            serde_json::from_value(json!({"sequence_id": "A000045", "level": "high_school"}))
+                .unwrap();
+        assert_eq!(request.level, Level::HighSchool);
+        assert!(
+            serde_json::from_value::<ExplainSequenceRequest>(
+                json!({"sequence_id": "A000045", "level": "toddler"})
+            )
+            .is_err()
         );
     }
 
     #[tokio::test]
-    async fn test_search_by_subsequence_tool_not_found() {
+    async fn test_explain_sequence_prompt_not_found() {
         let oeis = OEIS::new(MockOEISClient::new());
-        let params = Parameters(SearchRequest {
-            subsequence: vec![999, 888, 777],
+        let params = Parameters(ExplainSequenceRequest {
+            sequence_id: "A999999".to_string(),
+            level: Level::Child,
         });
 
-        let result = oeis.search_by_subsequence(params).await;
-        assert!(result.is_ok());
-
-        let content = result.unwrap().content;
-        assert_eq!(content.len(), 1);
-
-        assert_eq!(
-            content.first().unwrap(),
-            &ContentBlock::json(json!(SearchResponse { results: vec![] })).unwrap()
-        );
+        let error = oeis.explain_sequence(params).await.unwrap_err();
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
     }
 
     #[tokio::test]
-    async fn test_search_by_subsequence_tool_error() {
-        let subsequence = vec![1, 2, 3];
+    async fn test_compare_sequences_prompt() {
+        let fibonacci = OEISSequence {
+            xref: Some(vec!["Cf. A000032, A001519.".to_string()]),
+            keyword: "nonn,core,nice".to_string(),
+            ..create_test_sequence(45, "Fibonacci numbers")
+        };
+        let lucas = OEISSequence {
+            data: "2, 1, 3, 4, 7, 11, 18".to_string(),
+            xref: Some(vec!["Cf. A000045, A001519.".to_string()]),
+            keyword: "nonn,easy".to_string(),
+            ..create_test_sequence(32, "Lucas numbers")
+        };
         let oeis = OEIS::new(
-            MockOEISClient::new().with_error(
-                &subsequence
-                    .iter()
-                    .map(|i| i.to_string())
-                    .collect::<Vec<String>>()
-                    .join(","),
-            ),
+            MockOEISClient::new()
+                .with_sequence("A000045", fibonacci)
+                .with_sequence("A000032", lucas),
         );
-        let params = Parameters(SearchRequest {
-            subsequence: subsequence.clone(),
-        });
-
-        let result = oeis.search_by_subsequence(params).await;
-        assert!(result.is_err());
-
-        let error = result.unwrap_err();
-        assert_eq!(error.code, ErrorCode::INTERNAL_ERROR);
-        assert!(error.message.contains("Mock error"));
-    }
-
-    // Test for prompts
-    #[test]
-    fn test_prompt_router_definition() {
-        let oeis = OEIS::new(MockOEISClient::new());
-        assert!(oeis.prompt_router.list_all().len() == 1);
-    }
-
-    #[tokio::test]
-    async fn test_sequence_analysis_prompt() {
-        let fibonacci = create_test_sequence(45, "Fibonacci numbers");
-        let oeis = OEIS::new(MockOEISClient::new().with_sequence("A000045", fibonacci.clone()));
 
-        let params = Parameters(SequenceAnalysisRequest {
-            sequence_id: "A000045".to_string(),
+        let params = Parameters(CompareSequencesRequest {
+            first_id: "A000045".to_string(),
+            second_id: "A000032".to_string(),
         });
 
-        let result = oeis.sequence_analysis(params).await;
+        let result = oeis.compare_sequences(params).await;
         assert!(result.is_ok());
 
         let messages = result.unwrap();
         assert_eq!(messages.len(), 2);
 
-        // Check first message is from user
         assert_eq!(messages[0].role, Role::User);
         if let ContentBlock::Text(TextContent { text, .. }) = &messages[0].content {
-            assert!(text.contains("comprehensive analysis"));
+            assert!(text.contains("compare"));
             assert!(text.contains("A000045"));
+            assert!(text.contains("A000032"));
         } else {
             panic!("Expected text content");
         }
 
-        // Check second message is from assistant with sequence data
         assert_eq!(messages[1].role, Role::Assistant);
         if let ContentBlock::Text(TextContent { text, .. }) = &messages[1].content {
             assert!(text.contains("Fibonacci numbers"));
-            assert!(text.contains("A000045"));
-            assert!(text.contains("0, 1, 1, 2, 3, 5, 8"));
+            assert!(text.contains("Lucas numbers"));
+            assert!(text.contains("**Shared cross-references:** A001519"));
+            assert!(text.contains("**Overlapping terms:** 1, 2, 3"));
+            assert!(text.contains("**Keywords only in A000045:** core, nice"));
+            assert!(text.contains("**Keywords only in A000032:** easy"));
         } else {
             panic!("Expected text content");
         }
     }
 
     #[tokio::test]
-    async fn test_sequence_analysis_prompt_not_found() {
-        let oeis = OEIS::new(MockOEISClient::new().with_not_found("NON_EXISTENT"));
+    async fn test_compare_sequences_prompt_terms_past_i64() {
+        let factorials = OEISSequence {
+            data: "1, 1, 2, 6, 24, 51090942171709440000, 1124000727777607680000".to_string(),
+            keyword: "nonn,easy".to_string(),
+            ..create_test_sequence(142, "Factorial numbers")
+        };
+        let double_factorials = OEISSequence {
+            data: "1, 2, 8, 48, 51090942171709440000".to_string(),
+            keyword: "nonn, easy".to_string(),
+            ..create_test_sequence(165, "Double factorial of even numbers")
+        };
+        let oeis = OEIS::new(
+            MockOEISClient::new()
+                .with_sequence("A000142", factorials)
+                .with_sequence("A000165", double_factorials),
+        );
 
-        let params = Parameters(SequenceAnalysisRequest {
-            sequence_id: "NON_EXISTENT".to_string(),
+        let params = Parameters(CompareSequencesRequest {
+            first_id: "A000142".to_string(),
+            second_id: "A000165".to_string(),
         });
 
-        let result = oeis.sequence_analysis(params).await;
+        let messages = oeis.compare_sequences(params).await.unwrap();
+
+        if let ContentBlock::Text(TextContent { text, .. }) = &messages[1].content {
+            assert!(text.contains("**Overlapping terms:** 1, 2, 51090942171709440000"));
+            assert!(text.contains("**Keywords only in A000142:** (none)"));
+            assert!(text.contains("**Keywords only in A000165:** (none)"));
+        } else {
+            panic!("Expected text content");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_compare_sequences_prompt_second_not_found() {
+        let fibonacci = create_test_sequence(45, "Fibonacci numbers");
+        let oeis = OEIS::new(
+            MockOEISClient::new()
+                .with_sequence("A000045", fibonacci)
+                .with_not_found("A999999"),
+        );
+
+        let params = Parameters(CompareSequencesRequest {
+            first_id: "A000045".to_string(),
+            second_id: "A999999".to_string(),
+        });
+
+        let result = oeis.compare_sequences(params).await;
         assert!(result.is_err());
 
         let error = result.unwrap_err();
         assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
-        assert!(error.message.contains("No sequence found"));
+        assert!(error.message.starts_with("second sequence"));
+        assert!(error.message.contains("A999999"));
     }
 
     #[tokio::test]
-    async fn test_sequence_analysis_prompt_error() {
-        let oeis = OEIS::new(MockOEISClient::new().with_error("ERROR_CASE"));
+    async fn test_compare_sequences_prompt_first_error() {
+        let oeis = OEIS::new(MockOEISClient::new().with_error("A000500"));
 
-        let params = Parameters(SequenceAnalysisRequest {
-            sequence_id: "ERROR_CASE".to_string(),
+        let params = Parameters(CompareSequencesRequest {
+            first_id: "A000500".to_string(),
+            second_id: "A000045".to_string(),
         });
 
-        let result = oeis.sequence_analysis(params).await;
+        let result = oeis.compare_sequences(params).await;
         assert!(result.is_err());
 
         let error = result.unwrap_err();
         assert_eq!(error.code, ErrorCode::INTERNAL_ERROR);
+        assert!(error.message.starts_with("first sequence"));
     }
 }
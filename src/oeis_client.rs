@@ -1,75 +1,1021 @@
 use std::collections::VecDeque;
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
 
 use async_trait::async_trait;
 use rmcp::schemars::{self, JsonSchema};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use tracing::debug;
+use tracing::{debug, trace, warn};
 
-#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+use crate::config::ClientConfig;
+use crate::metrics::METRICS;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
 pub struct OEISSequence {
     pub number: i64,
     pub data: String,
     pub name: String,
+    pub offset: Option<String>,
     pub comment: Option<Vec<String>>,
+    pub reference: Option<Vec<String>>,
+    pub link: Option<Vec<String>>,
     pub formula: Option<Vec<String>>,
+    pub example: Option<Vec<String>>,
+    pub maple: Option<Vec<String>>,
+    pub mathematica: Option<Vec<String>>,
+    pub program: Option<Vec<String>>,
     pub xref: Option<Vec<String>>,
     pub keyword: String,
+    pub author: Option<String>,
+    pub created: Option<String>,
+    pub revision: Option<i64>,
+}
+
+impl OEISSequence {
+    /// The A-number of this sequence (e.g., "A000045")
+    pub fn id(&self) -> String {
+        format!("A{:06}", self.number)
+    }
+
+    /// A-numbers mentioned in the cross-references, in order of first appearance
+    pub fn xref_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = Vec::new();
+        for line in self.xref.iter().flatten() {
+            for id in extract_ids(line) {
+                if !ids.contains(&id) {
+                    ids.push(id);
+                }
+            }
+        }
+        ids
+    }
+
+    /// The `keyword` field split into named OEIS keyword flags
+    pub fn keywords(&self) -> SequenceKeywords {
+        SequenceKeywords::parse(&self.keyword)
+    }
+
+    /// Index of the first term, from the first part of `offset` (e.g., "1,3" -> 1).
+    /// Defaults to 0 when the offset is missing or malformed.
+    pub fn first_index(&self) -> i64 {
+        self.offset
+            .as_deref()
+            .and_then(|offset| offset.split(',').next())
+            .and_then(|index| index.trim().parse().ok())
+            .unwrap_or(0)
+    }
+}
+
+/// The comma-joined `keyword` field split into the OEIS keywords as named flags
+#[derive(Clone, Debug, Default, PartialEq, Serialize, JsonSchema)]
+pub struct SequenceKeywords {
+    /// An A-number reserved for a sequence not yet submitted
+    pub allocated: bool,
+    /// Depends on the base used to write the numbers
+    pub base: bool,
+    /// Only a few terms are known
+    pub bref: bool,
+    /// Changed in the last few weeks
+    pub changed: bool,
+    /// A continued fraction expansion
+    pub cofr: bool,
+    /// Decimal expansion of a constant
+    pub cons: bool,
+    /// A fundamental sequence
+    pub core: bool,
+    /// An erroneous or duplicated sequence, kept for reference
+    pub dead: bool,
+    /// An unimportant sequence
+    pub dumb: bool,
+    /// A duplicate of another sequence
+    pub dupe: bool,
+    /// Easy to compute further terms
+    pub easy: bool,
+    /// An eigensequence (fixed point of a transform)
+    pub eigen: bool,
+    /// A finite sequence
+    pub fini: bool,
+    /// Numerators or denominators of a sequence of fractions
+    pub frac: bool,
+    /// All terms are listed
+    pub full: bool,
+    /// Further terms are hard to compute
+    pub hard: bool,
+    /// Worth listening to
+    pub hear: bool,
+    /// Less interesting
+    pub less: bool,
+    /// Worth plotting
+    pub look: bool,
+    /// More terms are wanted
+    pub more: bool,
+    /// A multiplicative function
+    pub mult: bool,
+    /// Recently added
+    pub new: bool,
+    /// An exceptionally nice sequence
+    pub nice: bool,
+    /// All terms are nonnegative
+    pub nonn: bool,
+    /// Obscure, needs a better definition
+    pub obsc: bool,
+    /// A recycled A-number
+    pub recycled: bool,
+    /// Contains negative terms
+    pub sign: bool,
+    /// An irregular triangle or table read by rows
+    pub tabf: bool,
+    /// A regular triangle read by rows
+    pub tabl: bool,
+    /// Not yet edited
+    pub uned: bool,
+    /// Little is known about the sequence
+    pub unkn: bool,
+    /// Counts walks or self-avoiding paths
+    pub walk: bool,
+    /// Depends on words in some language
+    pub word: bool,
+    /// Keywords not recognized by this server
+    pub unknown: Vec<String>,
+}
+
+impl SequenceKeywords {
+    /// Parse a comma-joined keyword string like "nonn,easy,nice"
+    pub fn parse(raw: &str) -> Self {
+        let mut keywords = Self::default();
+        for keyword in raw.split(',').map(str::trim).filter(|k| !k.is_empty()) {
+            match keyword {
+                "allocated" => keywords.allocated = true,
+                "base" => keywords.base = true,
+                "bref" => keywords.bref = true,
+                "changed" => keywords.changed = true,
+                "cofr" => keywords.cofr = true,
+                "cons" => keywords.cons = true,
+                "core" => keywords.core = true,
+                "dead" => keywords.dead = true,
+                "dumb" => keywords.dumb = true,
+                "dupe" => keywords.dupe = true,
+                "easy" => keywords.easy = true,
+                "eigen" => keywords.eigen = true,
+                "fini" => keywords.fini = true,
+                "frac" => keywords.frac = true,
+                "full" => keywords.full = true,
+                "hard" => keywords.hard = true,
+                "hear" => keywords.hear = true,
+                "less" => keywords.less = true,
+                "look" => keywords.look = true,
+                "more" => keywords.more = true,
+                "mult" => keywords.mult = true,
+                "new" => keywords.new = true,
+                "nice" => keywords.nice = true,
+                "nonn" => keywords.nonn = true,
+                "obsc" => keywords.obsc = true,
+                "recycled" => keywords.recycled = true,
+                "sign" => keywords.sign = true,
+                "tabf" => keywords.tabf = true,
+                "tabl" => keywords.tabl = true,
+                "uned" => keywords.uned = true,
+                "unkn" => keywords.unkn = true,
+                "walk" => keywords.walk = true,
+                "word" => keywords.word = true,
+                other => keywords.unknown.push(other.to_string()),
+            }
+        }
+        keywords
+    }
+}
+
+/// How a subsequence search treats the signs of terms
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SignMode {
+    /// Match the signs of the terms exactly (OEIS `signed:` query)
+    Exact,
+    /// Ignore the signs of the terms (OEIS `seq:` query)
+    #[default]
+    Absolute,
+}
+
+impl SignMode {
+    /// The OEIS query prefix for this mode
+    fn query_prefix(self) -> &'static str {
+        match self {
+            SignMode::Exact => "signed:",
+            SignMode::Absolute => "seq:",
+        }
+    }
+}
+
+/// Number of sequences matching a search, with the ID of the first one when OEIS lists it
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MatchCount {
+    pub count: usize,
+    /// `None` when nothing matched or the query was too broad for OEIS to list results
+    pub first_id: Option<String>,
+}
+
+/// Sequences matching a search, along with the total number of matches reported by OEIS
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct SearchResult {
+    /// Total number of matching sequences, which may exceed `sequences.len()`
+    pub count: usize,
+    /// Offset of the first returned sequence among all matches
+    pub start: usize,
+    pub sequences: Vec<OEISSequence>,
+}
+
+impl SearchResult {
+    /// Whether more matches are available after this window
+    pub fn has_more(&self) -> bool {
+        self.start + self.sequences.len() < self.count
+    }
+}
+
+/// Wrapper object returned by the OEIS `fmt=json` search endpoint.
+/// Other keys (`greeting`, `query`, ...) are ignored.
+#[derive(Deserialize)]
+struct OEISResponse {
+    count: usize,
+    /// Echo of the requested offset; older responses may omit it
+    start: Option<usize>,
+    results: Option<Vec<OEISSequence>>,
+}
+
+/// Body of an OEIS search response: the wrapper object, or the bare array (or `null`)
+/// of results served by older endpoints
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SearchBody {
+    Wrapped(OEISResponse),
+    Bare(Option<Vec<OEISSequence>>),
+}
+
+impl SearchBody {
+    /// Convert into a `SearchResult`, falling back to the requested `start` when the body has none
+    fn into_result(self, requested_start: usize) -> OEISResult<SearchResult> {
+        match self {
+            SearchBody::Wrapped(OEISResponse {
+                count,
+                start,
+                results,
+            }) => {
+                let start = start.unwrap_or(requested_start);
+                // OEIS reports matches but withholds them when a query is too broad;
+                // a window past the last match is legitimately empty instead
+                if results.is_none() && start < count {
+                    return Err(OEISError::TooManyResults(count));
+                }
+                Ok(SearchResult {
+                    count,
+                    start,
+                    sequences: results.unwrap_or_default(),
+                })
+            }
+            SearchBody::Bare(results) => {
+                let sequences = results.unwrap_or_default();
+                Ok(SearchResult {
+                    count: sequences.len(),
+                    start: requested_start,
+                    sequences,
+                })
+            }
+        }
+    }
+}
+
+/// Body of an OEIS search response reduced to the match count and the listed A-numbers.
+/// Every other field of the sequence bodies is skipped rather than deserialized, and a
+/// too-broad query still reports its count.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CountBody {
+    Wrapped {
+        count: usize,
+        #[serde(default)]
+        results: Option<Vec<NumberOnly>>,
+    },
+    Bare(Option<Vec<NumberOnly>>),
+}
+
+/// A search result with only its A-number read
+#[derive(Deserialize)]
+struct NumberOnly {
+    number: Option<i64>,
+}
+
+impl CountBody {
+    fn into_match_count(self) -> MatchCount {
+        let (count, results) = match self {
+            CountBody::Wrapped { count, results } => (count, results),
+            CountBody::Bare(results) => (results.as_ref().map_or(0, Vec::len), results),
+        };
+        let first_id = results
+            .and_then(|results| results.into_iter().next())
+            .and_then(|first| first.number)
+            .map(|number| format!("A{:06}", number));
+        MatchCount { count, first_id }
+    }
+
+    fn count(self) -> usize {
+        self.into_match_count().count
+    }
+}
+
+/// OEIS query matching sequences that contain `subsequence`
+fn subsequence_query(subsequence: &[i64], mode: SignMode) -> String {
+    let terms = subsequence
+        .iter()
+        .map(|n| n.to_string())
+        .collect::<Vec<String>>()
+        .join(",");
+    format!("{}{}", mode.query_prefix(), terms)
+}
+
+/// OEIS query matching sequences that contain `pattern`, with `_` standing for each unknown term
+fn pattern_query(pattern: &[Option<i64>], mode: SignMode) -> String {
+    let terms = pattern
+        .iter()
+        .map(|term| term.map_or_else(|| "_".to_string(), |n| n.to_string()))
+        .collect::<Vec<String>>()
+        .join(",");
+    format!("{}{}", mode.query_prefix(), terms)
+}
+
+/// Extract A-numbers (an 'A' followed by six or more digits) from free text
+fn extract_ids(text: &str) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut ids = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let preceded_by_word = i > 0 && chars[i - 1].is_ascii_alphanumeric();
+        if chars[i] == 'A' && !preceded_by_word {
+            let digits: String = chars[i + 1..]
+                .iter()
+                .take_while(|c| c.is_ascii_digit())
+                .collect();
+            if digits.len() >= 6 {
+                i += digits.len();
+                ids.push(format!("A{}", digits));
+            }
+        }
+        i += 1;
+    }
+    ids
+}
+
+/// A single `index value` line of an OEIS b-file
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct BFileEntry {
+    pub index: i64,
+    /// Kept as a string since b-file terms routinely exceed `i64`
+    pub value: String,
+}
+
+/// Parse the contents of a b-file, skipping blank lines, `#` comments and malformed lines
+fn parse_bfile(text: &str) -> Vec<BFileEntry> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let index = parts.next()?.parse().ok()?;
+            let value = parts.next()?.to_string();
+            Some(BFileEntry { index, value })
+        })
+        .collect()
+}
+
+/// Best-effort parse of a sequence page in the OEIS internal format (`%N A000045 ...` lines),
+/// either as plain text or wrapped in HTML. `None` unless at least a name and data are found.
+fn parse_internal_format(id: &str, page: &str) -> Option<OEISSequence> {
+    let text = strip_html(page);
+    let field = |tag: &str| -> Vec<&str> {
+        let prefix = format!("%{} {}", tag, id);
+        text.lines()
+            .map(str::trim)
+            .filter_map(|line| line.strip_prefix(prefix.as_str()))
+            .map(str::trim)
+            .collect()
+    };
+
+    // %S, %T, and %U lines split one comma-separated list of terms
+    let data = ["S", "T", "U"]
+        .iter()
+        .flat_map(|tag| field(tag))
+        .collect::<String>()
+        .split(',')
+        .map(str::trim)
+        .filter(|term| !term.is_empty())
+        .collect::<Vec<&str>>()
+        .join(", ");
+    let name = field("N").join(" ");
+    if data.is_empty() || name.is_empty() {
+        return None;
+    }
+
+    Some(OEISSequence {
+        number: id.trim_start_matches('A').parse().ok()?,
+        data,
+        name,
+        offset: field("O").first().map(|offset| offset.to_string()),
+        keyword: field("K").join(","),
+        ..Default::default()
+    })
+}
+
+/// Drop HTML tags and decode the few entities OEIS uses in its internal-format page
+fn strip_html(page: &str) -> String {
+    let mut text = String::with_capacity(page.len());
+    let mut in_tag = false;
+    for c in page.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
 }
 
 #[async_trait]
 pub trait OEISClient: Send + Sync {
-    async fn find_by_id(&self, id: &str) -> anyhow::Result<Option<OEISSequence>>;
-    async fn search_by_subsequence(&self, subsequence: &[i64])
-    -> anyhow::Result<Vec<OEISSequence>>;
+    async fn find_by_id(&self, id: &str) -> OEISResult<Option<OEISSequence>>;
+    /// The verbatim OEIS JSON body of a lookup by ID, without deserializing it
+    async fn find_raw_by_id(&self, id: &str) -> OEISResult<String>;
+    /// Whether the sequence with the canonical ID exists, from the match count of a lookup
+    /// without deserializing the sequence
+    async fn sequence_exists(&self, id: &str) -> OEISResult<bool>;
+    /// Find the sequence whose name is exactly `name` (ignoring case)
+    async fn find_by_name(&self, name: &str) -> OEISResult<Option<OEISSequence>>;
+    /// Search sequences containing the subsequence, skipping the first `start` matches
+    /// and returning at most `max_results` (clamped to `MAX_SEARCH_RESULTS`)
+    async fn search_by_subsequence(
+        &self,
+        subsequence: &[i64],
+        start: usize,
+        mode: SignMode,
+        max_results: usize,
+    ) -> OEISResult<SearchResult>;
+    /// Count the sequences containing the subsequence (ignoring signs) from a single request,
+    /// reading only the ID of the first one instead of deserializing them
+    async fn count_subsequence_matches(&self, subsequence: &[i64]) -> OEISResult<MatchCount>;
+    /// Like `search_by_subsequence`, where each `None` in `pattern` is a wildcard matching any term
+    async fn search_by_pattern(
+        &self,
+        pattern: &[Option<i64>],
+        start: usize,
+        mode: SignMode,
+        max_results: usize,
+    ) -> OEISResult<SearchResult>;
+    /// Search sequences tagged with the given OEIS keyword (e.g., "core"), skipping the first `start` matches
+    async fn search_by_keyword(
+        &self,
+        keyword: &str,
+        start: usize,
+        max_results: usize,
+    ) -> OEISResult<SearchResult>;
+    /// Search sequences with a free-text OEIS query (e.g., "number of partitions")
+    async fn search_text(&self, query: &str) -> OEISResult<SearchResult>;
+    /// Fetch the b-file of a sequence by its canonical ID (e.g., "A000045")
+    async fn fetch_bfile(&self, id: &str) -> OEISResult<Option<Vec<BFileEntry>>>;
+    /// The newest sequence among the most recently created ones, if OEIS returns any
+    async fn latest_sequence(&self) -> OEISResult<Option<OEISSequence>>;
+}
+
+/// Errors returned by `OEISClient` implementations
+#[derive(Debug, Clone, PartialEq)]
+pub enum OEISError {
+    /// OEIS answered 404 for the requested resource
+    NotFound,
+    /// OEIS answered with a non-success status other than 404 / 429
+    Http(reqwest::StatusCode),
+    /// OEIS did not respond within the configured timeout
+    Timeout,
+    /// The response body could not be parsed
+    Deserialize(String),
+    /// OEIS kept answering 429 Too Many Requests after retries
+    RateLimited,
+    /// The request could not be sent (connection refused, DNS failure, etc.)
+    Request(String),
+    /// The query matched this many sequences, too many for OEIS to return any of them
+    TooManyResults(usize),
+    /// The response body was larger than this many bytes, so reading it was aborted
+    BodyTooLarge(usize),
+    /// OEIS kept failing, so the circuit breaker rejects calls for this much longer
+    CircuitOpen(std::time::Duration),
+}
+
+pub type OEISResult<T> = Result<T, OEISError>;
+
+impl OEISError {
+    /// Classify a non-success HTTP status
+    fn from_status(status: reqwest::StatusCode) -> Self {
+        match status {
+            reqwest::StatusCode::NOT_FOUND => Self::NotFound,
+            reqwest::StatusCode::TOO_MANY_REQUESTS => Self::RateLimited,
+            status => Self::Http(status),
+        }
+    }
+}
+
+impl fmt::Display for OEISError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound => write!(f, "Not found on OEIS"),
+            Self::Http(status) => write!(f, "OEIS returned HTTP {}", status),
+            Self::Timeout => write!(f, "OEIS request timed out"),
+            Self::Deserialize(message) => write!(f, "Could not parse OEIS response: {}", message),
+            Self::RateLimited => write!(f, "OEIS rate limit exceeded, try again later"),
+            Self::Request(message) => write!(f, "Could not reach OEIS: {}", message),
+            Self::TooManyResults(count) => write!(
+                f,
+                "Query matched too many sequences ({}); add more terms to narrow it down",
+                count
+            ),
+            Self::BodyTooLarge(max_bytes) => write!(
+                f,
+                "OEIS response exceeded the {} byte limit and was discarded",
+                max_bytes
+            ),
+            Self::CircuitOpen(remaining) => write!(
+                f,
+                "OEIS is temporarily unavailable after repeated failures; try again in {}s",
+                remaining.as_secs() + 1
+            ),
+        }
+    }
+}
+
+impl std::error::Error for OEISError {}
+
+impl From<reqwest::Error> for OEISError {
+    fn from(error: reqwest::Error) -> Self {
+        if error.is_timeout() {
+            Self::Timeout
+        } else if error.is_decode() {
+            Self::Deserialize(error.to_string())
+        } else if let Some(status) = error.status() {
+            Self::from_status(status)
+        } else {
+            Self::Request(error.to_string())
+        }
+    }
+}
+
+pub const DEFAULT_BASE_URL: &str = "https://oeis.org";
+pub const DEFAULT_TIMEOUT_SECS: u64 = 10;
+pub const DEFAULT_MAX_RETRIES: u32 = 2;
+const DEFAULT_RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+// Same as reqwest's defaults: no cap on idle connections, dropped after 90s idle
+pub const DEFAULT_POOL_MAX_IDLE_PER_HOST: usize = usize::MAX;
+pub const DEFAULT_POOL_IDLE_TIMEOUT_SECS: u64 = 90;
+/// Largest response body read from OEIS; b-files of well-studied sequences run to a few MB
+pub const DEFAULT_MAX_BODY_BYTES: usize = 8 * 1024 * 1024;
+
+/// `User-Agent` sent to OEIS (e.g., "oeis-mcp-server/0.1.0"), followed by `(+contact)` when
+/// the operator gives one so OEIS can reach them about the traffic
+pub fn user_agent(contact: Option<&str>) -> String {
+    let product = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+    match contact {
+        Some(contact) => format!("{} (+{})", product, contact),
+        None => product.to_string(),
+    }
 }
 
+/// Number of results returned when a search does not ask for a specific amount
+pub const DEFAULT_MAX_RESULTS: usize = 10;
+/// Hard ceiling on the results of a single search, whatever the caller asks for
+pub const MAX_SEARCH_RESULTS: usize = 100;
+
 #[derive(Clone)]
 pub struct OEISClientImpl {
-    url: String,
+    base_url: String,
+    /// Base URLs tried in order when the primary (or previous mirror) is unavailable
+    mirrors: Vec<String>,
+    timeout: Duration,
+    max_retries: u32,
+    retry_base_delay: Duration,
+    /// Scrape the internal-format page when a JSON lookup by ID cannot be parsed
+    scrape_fallback: bool,
+    max_body_bytes: usize,
+    user_agent: String,
     client: reqwest::Client,
 }
 
 impl OEISClientImpl {
-    pub fn new() -> Self {
+    /// Create a client from the validated startup configuration
+    pub fn from_config(config: &ClientConfig) -> Self {
+        Self::with_url(config.base_url.clone())
+            .with_mirrors(config.mirrors.clone())
+            .with_timeout(config.timeout)
+            .with_retry(config.max_retries, DEFAULT_RETRY_BASE_DELAY)
+            .with_pool(config.pool_max_idle_per_host, config.pool_idle_timeout)
+            .with_scrape_fallback(config.scrape_fallback)
+            .with_max_body_bytes(config.max_body_bytes)
+            .with_user_agent(user_agent(config.user_agent_contact.as_deref()))
+    }
+
+    /// Create a client for the given OEIS base URL (e.g., a mirror or a caching proxy)
+    pub fn with_url(base_url: String) -> Self {
         Self {
-            url: "https://oeis.org/search".to_string(),
-            client: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            mirrors: Vec::new(),
+            timeout: Duration::from_secs(DEFAULT_TIMEOUT_SECS),
+            max_retries: DEFAULT_MAX_RETRIES,
+            retry_base_delay: DEFAULT_RETRY_BASE_DELAY,
+            scrape_fallback: false,
+            max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+            user_agent: user_agent(None),
+            client: build_http_client(
+                DEFAULT_POOL_MAX_IDLE_PER_HOST,
+                Duration::from_secs(DEFAULT_POOL_IDLE_TIMEOUT_SECS),
+            ),
+        }
+    }
+
+    /// Fail over to these base URLs, in order, when OEIS is unreachable or answers 5xx
+    pub fn with_mirrors(mut self, mirrors: Vec<String>) -> Self {
+        self.mirrors = mirrors
+            .into_iter()
+            .map(|mirror| mirror.trim_end_matches('/').to_string())
+            .collect();
+        self
+    }
+
+    /// Set the timeout applied to each OEIS request
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set how many idle connections are kept per host, and how long they are kept
+    pub fn with_pool(mut self, max_idle_per_host: usize, idle_timeout: Duration) -> Self {
+        self.client = build_http_client(max_idle_per_host, idle_timeout);
+        self
+    }
+
+    /// Fall back to scraping `/{id}/internal` when the JSON for a lookup by ID is malformed.
+    /// Only the number, name, data, offset, and keywords are recovered.
+    pub fn with_scrape_fallback(mut self, enabled: bool) -> Self {
+        self.scrape_fallback = enabled;
+        self
+    }
+
+    /// Abort reading any response body larger than `max_body_bytes`
+    pub fn with_max_body_bytes(mut self, max_body_bytes: usize) -> Self {
+        self.max_body_bytes = max_body_bytes;
+        self
+    }
+
+    /// Set the `User-Agent` header sent with every OEIS request
+    pub fn with_user_agent(mut self, user_agent: String) -> Self {
+        self.user_agent = user_agent;
+        self
+    }
+
+    /// Set how many times a transient failure is retried, and the initial backoff delay
+    pub fn with_retry(mut self, max_retries: u32, base_delay: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.retry_base_delay = base_delay;
+        self
+    }
+
+    /// Query the OEIS search API
+    async fn search(&self, query: &str, start: usize) -> OEISResult<SearchResult> {
+        let start_str = start.to_string();
+        let response = self
+            .get(
+                "/search",
+                &[("fmt", "json"), ("q", query), ("start", &start_str)],
+            )
+            .await?;
+        let body: SearchBody = self.read_json(check_status(response)?).await?;
+        body.into_result(start)
+    }
+
+    /// Query the OEIS search API for at most `max_results` (clamped to `MAX_SEARCH_RESULTS`)
+    /// matches, skipping the first `start`
+    async fn search_pages(
+        &self,
+        query: &str,
+        start: usize,
+        max_results: usize,
+    ) -> OEISResult<SearchResult> {
+        let max_results = max_results.clamp(1, MAX_SEARCH_RESULTS);
+
+        // OEIS returns a fixed-size page per request, so keep fetching until enough results are in
+        let mut result = self.search(query, start).await?;
+        while result.sequences.len() < max_results && result.has_more() {
+            let page = self
+                .search(query, result.start + result.sequences.len())
+                .await?;
+            if page.sequences.is_empty() {
+                break;
+            }
+            result.count = page.count;
+            result.sequences.extend(page.sequences);
+        }
+        result.sequences.truncate(max_results);
+        Ok(result)
+    }
+
+    /// Fetch the internal-format page of a sequence and recover its core fields
+    async fn scrape_sequence(&self, id: &str) -> OEISResult<Option<OEISSequence>> {
+        let response = self.get(&format!("/{}/internal", id), &[]).await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let page = self.read_text(check_status(response)?).await?;
+        parse_internal_format(id, &page).map(Some).ok_or_else(|| {
+            OEISError::Deserialize(format!(
+                "no sequence found in the internal format of {}",
+                id
+            ))
+        })
+    }
+
+    /// Read a response body chunk by chunk, aborting as soon as it grows past `max_body_bytes`
+    /// (or right away when the declared `Content-Length` already does)
+    async fn read_body(&self, mut response: reqwest::Response) -> OEISResult<Vec<u8>> {
+        let max = self.max_body_bytes;
+        if response
+            .content_length()
+            .is_some_and(|length| length > max as u64)
+        {
+            return Err(OEISError::BodyTooLarge(max));
+        }
+
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            if body.len() + chunk.len() > max {
+                return Err(OEISError::BodyTooLarge(max));
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok(body)
+    }
+
+    async fn read_json<T: DeserializeOwned>(&self, response: reqwest::Response) -> OEISResult<T> {
+        let body = self.read_body(response).await?;
+        serde_json::from_slice(&body).map_err(|error| OEISError::Deserialize(error.to_string()))
+    }
+
+    async fn read_text(&self, response: reqwest::Response) -> OEISResult<String> {
+        let body = self.read_body(response).await?;
+        Ok(String::from_utf8_lossy(&body).into_owned())
+    }
+
+    /// Send a GET request for `path` to the primary base URL, failing over to each mirror
+    /// in turn while the hosts tried so far are unreachable or answer 5xx after their retries
+    async fn get(&self, path: &str, query: &[(&str, &str)]) -> OEISResult<reqwest::Response> {
+        let mut result = self
+            .get_with_retry(&format!("{}{}", self.base_url, path), query)
+            .await;
+        for mirror in &self.mirrors {
+            let failure = match &result {
+                Ok(response) if response.status().is_server_error() => {
+                    format!("HTTP {}", response.status())
+                }
+                Err(error @ (OEISError::Request(_) | OEISError::Timeout)) => error.to_string(),
+                _ => break,
+            };
+            warn!("OEIS unavailable ({}), failing over to {}", failure, mirror);
+            result = self
+                .get_with_retry(&format!("{}{}", mirror, path), query)
+                .await;
+        }
+        result
+    }
+
+    /// Send a GET request, retrying 429 / 5xx responses and connection errors
+    /// with exponential backoff and jitter
+    async fn get_with_retry(
+        &self,
+        url: &str,
+        query: &[(&str, &str)],
+    ) -> OEISResult<reqwest::Response> {
+        let mut attempt = 0;
+        loop {
+            let request = self
+                .client
+                .get(url)
+                .query(query)
+                .header(reqwest::header::USER_AGENT, &self.user_agent)
+                .timeout(self.timeout)
+                .build()?;
+            // Every method sends through here, so this covers lookups, searches, and b-files alike
+            trace!("OEIS Request: GET {}", request.url());
+
+            METRICS.inc_requests();
+            let result = self.client.execute(request).await;
+
+            match result {
+                Ok(response)
+                    if is_retryable_status(response.status()) && attempt < self.max_retries =>
+                {
+                    debug!("OEIS returned {}, retrying", response.status());
+                }
+                Err(error) if error.is_connect() && attempt < self.max_retries => {
+                    debug!("OEIS connection failed, retrying: {:?}", error);
+                }
+                Ok(response) => {
+                    debug!("OEIS Response: {:?}", response);
+                    if is_retryable_status(response.status()) {
+                        METRICS.inc_errors();
+                    }
+                    return Ok(response);
+                }
+                Err(error) => {
+                    METRICS.inc_errors();
+                    return Err(error.into());
+                }
+            }
+
+            tokio::time::sleep(self.retry_delay(attempt)).await;
+            attempt += 1;
         }
     }
+
+    /// Exponential backoff (`base * 2^attempt`) plus up to 50% random jitter
+    fn retry_delay(&self, attempt: u32) -> Duration {
+        let backoff = self
+            .retry_base_delay
+            .saturating_mul(2u32.saturating_pow(attempt));
+        let max_jitter_ms = backoff.as_millis() as u64 / 2;
+        let jitter_ms = RandomState::new().build_hasher().finish() % (max_jitter_ms + 1);
+        backoff + Duration::from_millis(jitter_ms)
+    }
+}
+
+fn is_retryable_status(status: reqwest::StatusCode) -> bool {
+    status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Build the pooled HTTP client; `reqwest::Client` is an `Arc` internally, so clones share the pool
+fn build_http_client(max_idle_per_host: usize, idle_timeout: Duration) -> reqwest::Client {
+    reqwest::Client::builder()
+        .pool_max_idle_per_host(max_idle_per_host)
+        .pool_idle_timeout(idle_timeout)
+        .build()
+        .expect("failed to build HTTP client")
+}
+
+/// Turn a non-success response into the matching `OEISError`
+fn check_status(response: reqwest::Response) -> OEISResult<reqwest::Response> {
+    if response.status().is_success() {
+        Ok(response)
+    } else {
+        Err(OEISError::from_status(response.status()))
+    }
 }
 
 #[async_trait]
 impl OEISClient for OEISClientImpl {
-    async fn find_by_id(&self, id: &str) -> anyhow::Result<Option<OEISSequence>> {
+    async fn find_by_id(&self, id: &str) -> OEISResult<Option<OEISSequence>> {
+        let oeis_response = match self.search(&format!("id:{}", id), 0).await {
+            Err(OEISError::Deserialize(message)) if self.scrape_fallback => {
+                warn!(
+                    "Could not parse the JSON for {} ({}); scraping its internal format",
+                    id, message
+                );
+                return self.scrape_sequence(id).await;
+            }
+            result => result?,
+        };
+        debug!(
+            "id:{} returned {} result(s)",
+            id,
+            oeis_response.sequences.len()
+        );
+
+        if oeis_response.sequences.len() <= 1 {
+            return Ok(VecDeque::from(oeis_response.sequences).pop_front());
+        }
+
+        // Several entries for an exact-ID query are only usable if exactly one carries that ID
+        let mut exact: Vec<OEISSequence> = oeis_response
+            .sequences
+            .into_iter()
+            .filter(|sequence| sequence.id().eq_ignore_ascii_case(id))
+            .collect();
+        if exact.len() == 1 {
+            return Ok(exact.pop());
+        }
+        warn!(
+            "id:{} is ambiguous ({} matching entries); returning no sequence",
+            id,
+            exact.len()
+        );
+        Ok(None)
+    }
+
+    async fn find_raw_by_id(&self, id: &str) -> OEISResult<String> {
+        let query = format!("id:{}", id);
+        let response = self
+            .get("/search", &[("fmt", "json"), ("q", &query)])
+            .await?;
+        self.read_text(check_status(response)?).await
+    }
+
+    async fn sequence_exists(&self, id: &str) -> OEISResult<bool> {
+        let query = format!("id:{}", id);
         let response = self
-            .client
-            .get(&self.url)
-            .query(&[("fmt", "json"), ("q", &format!("id:{}", id))])
-            .send()
+            .get("/search", &[("fmt", "json"), ("q", &query)])
             .await?;
-        debug!("OEIS Response: {:?}", response);
-        let oeis_response: Option<Vec<OEISSequence>> = response.json().await?;
-        Ok(oeis_response.and_then(|sv| VecDeque::from(sv).pop_front()))
+        let body: CountBody = self.read_json(check_status(response)?).await?;
+        Ok(body.count() > 0)
+    }
+
+    async fn find_by_name(&self, name: &str) -> OEISResult<Option<OEISSequence>> {
+        let name = name.trim();
+        // OEIS has no escape for quotes inside a phrase, so drop them to keep the phrase intact
+        let phrase = name.replace('"', " ");
+        let oeis_response = self.search(&format!("name:\"{}\"", phrase), 0).await?;
+
+        // The phrase matches anywhere in the name, so keep only an exact match
+        Ok(oeis_response
+            .sequences
+            .into_iter()
+            .find(|sequence| sequence.name.eq_ignore_ascii_case(name)))
     }
 
     async fn search_by_subsequence(
         &self,
         subsequence: &[i64],
-    ) -> anyhow::Result<Vec<OEISSequence>> {
-        let subsequence_str = subsequence
-            .iter()
-            .map(|n| n.to_string())
-            .collect::<Vec<String>>()
-            .join(",");
+        start: usize,
+        mode: SignMode,
+        max_results: usize,
+    ) -> OEISResult<SearchResult> {
+        self.search_pages(&subsequence_query(subsequence, mode), start, max_results)
+            .await
+    }
+
+    async fn count_subsequence_matches(&self, subsequence: &[i64]) -> OEISResult<MatchCount> {
+        let query = subsequence_query(subsequence, SignMode::Absolute);
+        let response = self
+            .get("/search", &[("fmt", "json"), ("q", &query)])
+            .await?;
+        let body: CountBody = self.read_json(check_status(response)?).await?;
+        Ok(body.into_match_count())
+    }
+
+    async fn search_by_pattern(
+        &self,
+        pattern: &[Option<i64>],
+        start: usize,
+        mode: SignMode,
+        max_results: usize,
+    ) -> OEISResult<SearchResult> {
+        self.search_pages(&pattern_query(pattern, mode), start, max_results)
+            .await
+    }
+
+    async fn search_by_keyword(
+        &self,
+        keyword: &str,
+        start: usize,
+        max_results: usize,
+    ) -> OEISResult<SearchResult> {
+        self.search_pages(&format!("keyword:{}", keyword), start, max_results)
+            .await
+    }
+
+    async fn search_text(&self, query: &str) -> OEISResult<SearchResult> {
+        self.search(query, 0).await
+    }
+
+    async fn fetch_bfile(&self, id: &str) -> OEISResult<Option<Vec<BFileEntry>>> {
+        let digits = id.trim_start_matches('A');
+        let response = self.get(&format!("/{}/b{}.txt", id, digits), &[]).await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let text = self.read_text(check_status(response)?).await?;
+        Ok(Some(parse_bfile(&text)))
+    }
+
+    async fn latest_sequence(&self) -> OEISResult<Option<OEISSequence>> {
         let response = self
-            .client
-            .get(&self.url)
-            .query(&[("fmt", "json"), ("q", &format!("seq:{}", subsequence_str))])
-            .send()
+            .get(
+                "/search",
+                &[("fmt", "json"), ("q", "keyword:new"), ("sort", "created")],
+            )
             .await?;
-        debug!("OEIS Response: {:?}", response);
-        let oeis_response: Option<Vec<OEISSequence>> = response.json().await?;
-        Ok(oeis_response.unwrap_or_default())
+        let body: SearchBody = self.read_json(check_status(response)?).await?;
+        // Take the highest A-number rather than trusting the order of the page
+        Ok(body
+            .into_result(0)?
+            .sequences
+            .into_iter()
+            .max_by_key(|sequence| sequence.number))
     }
 }
 
@@ -78,13 +1024,12 @@ mod tests {
     use super::*;
     use httpmock::Method::GET;
     use httpmock::{Mock, MockServer};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
 
     // helpers
-    fn setup_test_client(server: &MockServer) -> impl OEISClient {
-        OEISClientImpl {
-            url: format!("{}/search", server.base_url()),
-            client: reqwest::Client::new(),
-        }
+    fn setup_test_client(server: &MockServer) -> OEISClientImpl {
+        OEISClientImpl::with_url(server.base_url()).with_retry(2, Duration::from_millis(1))
     }
 
     fn mock_oeis_search<'a>(server: &'a MockServer, id: &str, status: u16, body: &str) -> Mock<'a> {
@@ -124,7 +1069,117 @@ mod tests {
         })
     }
 
+    fn create_sequence_with_data(data: &str) -> OEISSequence {
+        OEISSequence {
+            number: 1,
+            data: data.to_string(),
+            name: "Test sequence".to_string(),
+            keyword: "nonn".to_string(),
+            ..Default::default()
+        }
+    }
+
     // tests
+    #[test]
+    fn test_with_url() {
+        let client = OEISClientImpl::with_url("http://localhost:8080".to_string());
+        assert_eq!(client.base_url, "http://localhost:8080");
+    }
+
+    #[test]
+    fn test_with_url_trailing_slash() {
+        let client = OEISClientImpl::with_url("http://localhost:8080/".to_string());
+        assert_eq!(client.base_url, "http://localhost:8080");
+    }
+
+    #[test]
+    fn test_id() {
+        let sequence = create_sequence_with_data("");
+        assert_eq!(sequence.id(), "A000001");
+    }
+
+    #[test]
+    fn test_xref_ids() {
+        let sequence = OEISSequence {
+            xref: Some(vec![
+                "Cf. A000032, A001519, A000045.".to_string(),
+                "Sequence in context: A020695 A212804 * A001519".to_string(),
+                "Not an id: XA123456, A12345.".to_string(),
+            ]),
+            ..create_sequence_with_data("")
+        };
+
+        assert_eq!(
+            sequence.xref_ids(),
+            vec!["A000032", "A001519", "A000045", "A020695", "A212804"]
+        );
+    }
+
+    #[test]
+    fn test_xref_ids_empty() {
+        let sequence = create_sequence_with_data("");
+        assert!(sequence.xref_ids().is_empty());
+    }
+
+    #[test]
+    fn test_subsequence_query() {
+        assert_eq!(
+            subsequence_query(&[1, 2, 3], SignMode::Absolute),
+            "seq:1,2,3"
+        );
+        assert_eq!(subsequence_query(&[-3], SignMode::Exact), "signed:-3");
+    }
+
+    #[test]
+    fn test_pattern_query() {
+        assert_eq!(
+            pattern_query(&[Some(1), None, Some(3)], SignMode::Absolute),
+            "seq:1,_,3"
+        );
+        assert_eq!(
+            pattern_query(&[None, Some(-1), None], SignMode::Exact),
+            "signed:_,-1,_"
+        );
+    }
+
+    #[test]
+    fn test_first_index() {
+        let mut sequence = create_sequence_with_data("1, 2, 3");
+        assert_eq!(sequence.first_index(), 0);
+
+        sequence.offset = Some("1,3".to_string());
+        assert_eq!(sequence.first_index(), 1);
+
+        sequence.offset = Some("-2,1".to_string());
+        assert_eq!(sequence.first_index(), -2);
+
+        sequence.offset = Some("garbage".to_string());
+        assert_eq!(sequence.first_index(), 0);
+    }
+
+    #[test]
+    fn test_keywords_multiple() {
+        let sequence = OEISSequence {
+            keyword: "core,nonn,easy,nice".to_string(),
+            ..Default::default()
+        };
+
+        let keywords = sequence.keywords();
+
+        assert!(keywords.core && keywords.nonn && keywords.easy && keywords.nice);
+        assert!(!keywords.sign && !keywords.hard && !keywords.more);
+        assert!(keywords.unknown.is_empty());
+    }
+
+    #[test]
+    fn test_keywords_unknown() {
+        let keywords = SequenceKeywords::parse("sign, shiny ,tabl,");
+
+        assert!(keywords.sign);
+        assert!(keywords.tabl);
+        assert_eq!(keywords.unknown, vec!["shiny".to_string()]);
+    }
+
     #[tokio::test]
     async fn test_find_by_id() {
         let server = MockServer::start();
@@ -135,17 +1190,31 @@ mod tests {
             "A000045",
             200,
             r#"
-                [
-                    {
-                        "number": 45,
-                        "data": "0, 1, 1, 2, 3, 5, 8, 13, 21, 34",
-                        "name": "Fibonacci numbers",
-                        "comment": ["The Fibonacci sequence is defined by the recurrence relation F(n) = F(n-1) + F(n-2) with seed values F(0)=0 and F(1)=1."],
-                        "formula": ["F(n) = (phi^n - (1-phi)^n)/sqrt(5), where phi = (1 + sqrt(5))/2."],
-                        "xref": ["A000045", "A001519"],
-                        "keyword": "nonn"
-                    }
-                ]
+                {
+                    "count": 1,
+                    "start": 0,
+                    "results": [
+                        {
+                            "number": 45,
+                            "data": "0, 1, 1, 2, 3, 5, 8, 13, 21, 34",
+                            "name": "Fibonacci numbers",
+                            "offset": "0,4",
+                            "comment": ["The Fibonacci sequence is defined by the recurrence relation F(n) = F(n-1) + F(n-2) with seed values F(0)=0 and F(1)=1."],
+                            "reference": ["D. E. Knuth, The Art of Computer Programming, Vol. 1."],
+                            "link": ["N. J. A. Sloane, <a href=\"/A000045/b000045.txt\">Table of n, a(n) for n = 0..500</a>"],
+                            "formula": ["F(n) = (phi^n - (1-phi)^n)/sqrt(5), where phi = (1 + sqrt(5))/2."],
+                            "example": ["F(6) = F(5) + F(4) = 5 + 3 = 8."],
+                            "maple": ["with(combinat): A000045 := n->fibonacci(n);"],
+                            "mathematica": ["Table[Fibonacci[n], {n, 0, 50}]"],
+                            "program": ["(PARI) a(n)=fibonacci(n)"],
+                            "xref": ["A000045", "A001519"],
+                            "keyword": "nonn",
+                            "author": "_N. J. A. Sloane_",
+                            "created": "1991-04-30T03:00:00-04:00",
+                            "revision": 1234
+                        }
+                    ]
+                }
                 "#,
         );
 
@@ -155,30 +1224,435 @@ mod tests {
         let found_sequence = result.unwrap();
         assert_eq!(found_sequence.number, 45);
         assert_eq!(found_sequence.name, "Fibonacci numbers");
+        assert_eq!(found_sequence.offset, Some("0,4".to_string()));
+        assert_eq!(found_sequence.author, Some("_N. J. A. Sloane_".to_string()));
+        assert_eq!(
+            found_sequence.created,
+            Some("1991-04-30T03:00:00-04:00".to_string())
+        );
+        assert_eq!(found_sequence.revision, Some(1234));
+        assert_eq!(
+            found_sequence.reference,
+            Some(vec![
+                "D. E. Knuth, The Art of Computer Programming, Vol. 1.".to_string()
+            ])
+        );
+        assert_eq!(
+            found_sequence.link,
+            Some(vec![
+                "N. J. A. Sloane, <a href=\"/A000045/b000045.txt\">Table of n, a(n) for n = 0..500</a>"
+                    .to_string()
+            ])
+        );
+        assert_eq!(
+            found_sequence.example,
+            Some(vec!["F(6) = F(5) + F(4) = 5 + 3 = 8.".to_string()])
+        );
+        assert_eq!(
+            found_sequence.maple,
+            Some(vec![
+                "with(combinat): A000045 := n->fibonacci(n);".to_string()
+            ])
+        );
+        assert_eq!(
+            found_sequence.mathematica,
+            Some(vec!["Table[Fibonacci[n], {n, 0, 50}]".to_string()])
+        );
+        assert_eq!(
+            found_sequence.program,
+            Some(vec!["(PARI) a(n)=fibonacci(n)".to_string()])
+        );
     }
 
     #[tokio::test]
-    async fn test_find_by_id_not_found() {
+    async fn test_find_by_id_multiple_results_picks_exact_id() {
         let server = MockServer::start();
         let client = setup_test_client(&server);
 
-        let _mock = mock_oeis_search(&server, "NON_EXISTENT", 200, "null");
+        let _mock = mock_oeis_search(
+            &server,
+            "A000045",
+            200,
+            r#"{"count": 2, "start": 0, "results": [{"number": 44, "data": "1, 1, 2", "name": "Other", "keyword": "nonn"}, {"number": 45, "data": "0, 1, 1", "name": "Fibonacci numbers", "keyword": "nonn"}]}"#,
+        );
 
-        let result = client.find_by_id("NON_EXISTENT").await.unwrap();
+        let result = client.find_by_id("A000045").await.unwrap();
 
-        assert!(result.is_none());
+        assert_eq!(result.unwrap().number, 45);
     }
 
     #[tokio::test]
-    async fn test_find_by_id_error() {
+    async fn test_find_by_id_ambiguous_returns_none() {
         let server = MockServer::start();
         let client = setup_test_client(&server);
 
-        let _mock = mock_oeis_search(&server, "ERROR_CASE", 500, "");
-
-        let result = client.find_by_id("ERROR_CASE").await;
-
+        let _mock = mock_oeis_search(
+            &server,
+            "A000045",
+            200,
+            r#"{"count": 2, "start": 0, "results": [{"number": 45, "data": "0, 1, 1", "name": "Fibonacci numbers", "keyword": "nonn"}, {"number": 45, "data": "0, 1, 1", "name": "Fibonacci numbers (duplicate)", "keyword": "nonn"}]}"#,
+        );
+
+        let result = client.find_by_id("A000045").await.unwrap();
+
+        assert!(result.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_clones_share_configured_pool() {
+        let server = MockServer::start();
+        let client = OEISClientImpl::with_url(server.base_url())
+            .with_retry(0, Duration::from_millis(1))
+            .with_pool(1, Duration::from_secs(1));
+        let clone = client.clone();
+
+        let mock = mock_oeis_search(
+            &server,
+            "A000045",
+            200,
+            r#"{"count": 1, "results": [{"number": 45, "data": "0, 1, 1", "name": "Fibonacci numbers", "keyword": "nonn"}]}"#,
+        );
+
+        let (first, second) =
+            tokio::join!(client.find_by_id("A000045"), clone.find_by_id("A000045"));
+
+        mock.assert_calls(2);
+        assert_eq!(first.unwrap().unwrap().number, 45);
+        assert_eq!(second.unwrap().unwrap().number, 45);
+    }
+
+    #[tokio::test]
+    async fn test_find_by_id_not_found() {
+        let server = MockServer::start();
+        let client = setup_test_client(&server);
+
+        let _mock = mock_oeis_search(
+            &server,
+            "NON_EXISTENT",
+            200,
+            r#"{"count": 0, "start": 0, "results": null}"#,
+        );
+
+        let result = client.find_by_id("NON_EXISTENT").await.unwrap();
+
+        assert!(result.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_user_agent_header() {
+        let server = MockServer::start();
+        let client = setup_test_client(&server)
+            .with_user_agent(user_agent(Some("https://example.com/contact")));
+
+        let mock = server.mock(|when, then| {
+            when.method(GET).path("/search").header(
+                "user-agent",
+                format!(
+                    "oeis-mcp-server/{} (+https://example.com/contact)",
+                    env!("CARGO_PKG_VERSION")
+                ),
+            );
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .body(r#"{"count": 0, "start": 0, "results": null}"#);
+        });
+
+        client.find_by_id("A000045").await.unwrap();
+
+        mock.assert_calls(1);
+    }
+
+    #[test]
+    fn test_user_agent() {
+        let version = env!("CARGO_PKG_VERSION");
+        assert_eq!(user_agent(None), format!("oeis-mcp-server/{}", version));
+        assert_eq!(
+            user_agent(Some("ops@example.com")),
+            format!("oeis-mcp-server/{} (+ops@example.com)", version)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_find_by_id_error() {
+        let server = MockServer::start();
+        let client = setup_test_client(&server);
+
+        let _mock = mock_oeis_search(&server, "ERROR_CASE", 500, "");
+
+        let result = client.find_by_id("ERROR_CASE").await;
+
+        assert_eq!(
+            result.unwrap_err(),
+            OEISError::Http(reqwest::StatusCode::INTERNAL_SERVER_ERROR)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_find_by_id_timeout() {
+        let server = MockServer::start();
+        let client =
+            OEISClientImpl::with_url(server.base_url()).with_timeout(Duration::from_millis(100));
+
+        let _mock = server.mock(|when, then| {
+            when.method(GET).path("/search");
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .body(r#"{"count": 0, "start": 0, "results": null}"#)
+                .delay(Duration::from_secs(1));
+        });
+
+        let result = client.find_by_id("A000045").await;
+
+        assert_eq!(result.unwrap_err(), OEISError::Timeout);
+    }
+
+    #[tokio::test]
+    async fn test_find_by_id_retry_then_success() {
+        let server = MockServer::start();
+        let client = setup_test_client(&server);
+
+        let failures = Arc::new(AtomicUsize::new(0));
+        let failures_in_mock = failures.clone();
+        let _unavailable_mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/search")
+                .is_true(move |_| failures_in_mock.fetch_add(1, Ordering::SeqCst) < 2);
+            then.status(503);
+        });
+        let success_mock = mock_oeis_search(
+            &server,
+            "A000045",
+            200,
+            r#"{"count": 1, "start": 0, "results": [{"number": 45, "data": "0, 1, 1", "name": "Fibonacci numbers", "keyword": "nonn"}]}"#,
+        );
+
+        let result = client.find_by_id("A000045").await.unwrap();
+
+        assert_eq!(result.unwrap().number, 45);
+        assert_eq!(success_mock.calls(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_find_by_id_rate_limited() {
+        let server = MockServer::start();
+        let client = setup_test_client(&server);
+
+        let mock = mock_oeis_search(&server, "A000045", 429, "");
+
+        let result = client.find_by_id("A000045").await;
+
+        assert_eq!(result.unwrap_err(), OEISError::RateLimited);
+        assert_eq!(mock.calls(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_find_by_id_invalid_body() {
+        let server = MockServer::start();
+        let client = setup_test_client(&server);
+
+        let _mock = mock_oeis_search(&server, "A000045", 200, "not json");
+
+        let result = client.find_by_id("A000045").await;
+
+        assert!(matches!(result.unwrap_err(), OEISError::Deserialize(_)));
+    }
+
+    #[tokio::test]
+    async fn test_find_by_id_scrape_fallback() {
+        let server = MockServer::start();
+        let client = OEISClientImpl::with_url(server.base_url()).with_scrape_fallback(true);
+
+        let _search = mock_oeis_search(
+            &server,
+            "A000045",
+            200,
+            r#"{"count": 1, "results": [{"number": "45"}]}"#,
+        );
+        let internal = server.mock(|when, then| {
+            when.method(GET).path("/A000045/internal");
+            then.status(200).body(
+                "<html><body><tt>%I A000045 M0692 N0256</tt><br>\n\
+                 <tt>%S A000045 0,1,1,2,3,5,8,13,</tt><br>\n\
+                 <tt>%T A000045 21,34,55</tt><br>\n\
+                 <tt>%N A000045 Fibonacci numbers: F(n) = F(n-1) + F(n-2) with F(0) = 0 and F(1) = 1.</tt><br>\n\
+                 <tt>%O A000045 0,4</tt><br>\n\
+                 <tt>%K A000045 core,nonn,nice,easy</tt><br>\n\
+                 </body></html>",
+            );
+        });
+
+        let sequence = client.find_by_id("A000045").await.unwrap().unwrap();
+
+        internal.assert_calls(1);
+        assert_eq!(sequence.number, 45);
+        assert_eq!(
+            sequence.name,
+            "Fibonacci numbers: F(n) = F(n-1) + F(n-2) with F(0) = 0 and F(1) = 1."
+        );
+        assert_eq!(sequence.data, "0, 1, 1, 2, 3, 5, 8, 13, 21, 34, 55");
+        assert_eq!(sequence.offset.as_deref(), Some("0,4"));
+        assert_eq!(sequence.keyword, "core,nonn,nice,easy");
+    }
+
+    #[tokio::test]
+    async fn test_find_by_id_scrape_fallback_unparseable_page() {
+        let server = MockServer::start();
+        let client = OEISClientImpl::with_url(server.base_url()).with_scrape_fallback(true);
+
+        let _search = mock_oeis_search(&server, "A000045", 200, "not json");
+        let _internal = server.mock(|when, then| {
+            when.method(GET).path("/A000045/internal");
+            then.status(200).body("<html>Maintenance</html>");
+        });
+
+        let result = client.find_by_id("A000045").await;
+
+        assert!(matches!(result.unwrap_err(), OEISError::Deserialize(_)));
+    }
+
+    #[tokio::test]
+    async fn test_find_by_id_retry_exhausted() {
+        let server = MockServer::start();
+        let client = setup_test_client(&server);
+
+        let mock = mock_oeis_search(&server, "A000045", 503, "");
+
+        let result = client.find_by_id("A000045").await;
+
         assert!(result.is_err());
+        assert_eq!(mock.calls(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_find_by_id_fails_over_to_mirror() {
+        let primary = MockServer::start();
+        let mirror = MockServer::start();
+        let client =
+            setup_test_client(&primary).with_mirrors(vec![format!("{}/", mirror.base_url())]);
+
+        let primary_mock = mock_oeis_search(&primary, "A000045", 503, "");
+        let mirror_mock = mock_oeis_search(
+            &mirror,
+            "A000045",
+            200,
+            r#"{"count": 1, "start": 0, "results": [{"number": 45, "data": "0, 1, 1", "name": "Fibonacci numbers", "keyword": "nonn"}]}"#,
+        );
+
+        let result = client.find_by_id("A000045").await.unwrap();
+
+        assert_eq!(result.unwrap().number, 45);
+        assert_eq!(primary_mock.calls(), 3);
+        assert_eq!(mirror_mock.calls(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_find_by_id_fails_over_when_unreachable() {
+        let mirror = MockServer::start();
+        // Nothing listens on port 1, so connecting to the primary fails
+        let client = OEISClientImpl::with_url("http://127.0.0.1:1".to_string())
+            .with_retry(0, Duration::from_millis(1))
+            .with_mirrors(vec![mirror.base_url()]);
+
+        let mirror_mock = mock_oeis_search(
+            &mirror,
+            "A000045",
+            200,
+            r#"{"count": 1, "start": 0, "results": [{"number": 45, "data": "0, 1, 1", "name": "Fibonacci numbers", "keyword": "nonn"}]}"#,
+        );
+
+        let result = client.find_by_id("A000045").await.unwrap();
+
+        assert_eq!(result.unwrap().number, 45);
+        mirror_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_find_by_id_no_failover_on_client_error() {
+        let primary = MockServer::start();
+        let mirror = MockServer::start();
+        let client = setup_test_client(&primary).with_mirrors(vec![mirror.base_url()]);
+
+        let _primary_mock = mock_oeis_search(&primary, "A000045", 404, "");
+        let mirror_mock = mock_oeis_search(&mirror, "A000045", 200, "{}");
+
+        let result = client.find_by_id("A000045").await;
+
+        assert_eq!(result.unwrap_err(), OEISError::NotFound);
+        assert_eq!(mirror_mock.calls(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_find_raw_by_id_passes_body_through() {
+        let server = MockServer::start();
+        let client = setup_test_client(&server);
+
+        // Unmodeled fields and formatting must survive untouched
+        let body = r#"{"count": 1, "results": [{"number": 45, "name": "Fibonacci numbers", "new_field": [1, 2]}]}"#;
+        let mock = mock_oeis_search(&server, "A000045", 200, body);
+
+        let raw = client.find_raw_by_id("A000045").await.unwrap();
+
+        mock.assert();
+        assert_eq!(raw, body);
+    }
+
+    #[tokio::test]
+    async fn test_find_raw_by_id_not_found() {
+        let server = MockServer::start();
+        let client = setup_test_client(&server);
+
+        let _mock = mock_oeis_search(&server, "A999999", 404, "");
+
+        let result = client.find_raw_by_id("A999999").await;
+
+        assert_eq!(result.unwrap_err(), OEISError::NotFound);
+    }
+
+    #[tokio::test]
+    async fn test_find_by_id_body_too_large() {
+        let server = MockServer::start();
+        let client = setup_test_client(&server).with_max_body_bytes(1024);
+
+        let oversized = format!(
+            r#"{{"count": 1, "results": [{{"number": 45, "name": "{}"}}]}}"#,
+            "x".repeat(2048)
+        );
+        let _mock = mock_oeis_search(&server, "A000045", 200, &oversized);
+
+        let result = client.find_by_id("A000045").await;
+
+        assert_eq!(result.unwrap_err(), OEISError::BodyTooLarge(1024));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_bfile_body_within_limit() {
+        let server = MockServer::start();
+        let client = setup_test_client(&server).with_max_body_bytes(16);
+
+        let _mock = server.mock(|when, then| {
+            when.method(GET).path("/A000045/b000045.txt");
+            then.status(200).body("0 0\n1 1\n2 1\n");
+        });
+
+        let entries = client.fetch_bfile("A000045").await.unwrap().unwrap();
+
+        assert_eq!(entries.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_find_by_id_no_retry_on_client_error() {
+        let server = MockServer::start();
+        let client = setup_test_client(&server);
+
+        let mock = mock_oeis_search(&server, "A000045", 400, "");
+
+        let result = client.find_by_id("A000045").await;
+
+        assert_eq!(
+            result.unwrap_err(),
+            OEISError::Http(reqwest::StatusCode::BAD_REQUEST)
+        );
+        assert_eq!(mock.calls(), 1);
     }
 
     #[tokio::test]
@@ -191,28 +1665,38 @@ mod tests {
             "1,1,2,3,5",
             200,
             r#"
-                [
-                    {
-                        "number": 45,
-                        "data": "0, 1, 1, 2, 3, 5, 8, 13, 21, 34",
-                        "name": "Fibonacci numbers",
-                        "comment": ["The Fibonacci sequence"],
-                        "formula": ["F(n) = F(n-1) + F(n-2)"],
-                        "xref": ["A000045"],
-                        "keyword": "nonn"
-                    }
-                ]
+                {
+                    "count": 1,
+                    "start": 0,
+                    "results": [
+                        {
+                            "number": 45,
+                            "data": "0, 1, 1, 2, 3, 5, 8, 13, 21, 34",
+                            "name": "Fibonacci numbers",
+                            "comment": ["The Fibonacci sequence"],
+                            "formula": ["F(n) = F(n-1) + F(n-2)"],
+                            "xref": ["A000045"],
+                            "keyword": "nonn"
+                        }
+                    ]
+                }
                 "#,
         );
 
         let result = client
-            .search_by_subsequence(&[1, 1, 2, 3, 5])
+            .search_by_subsequence(&[1, 1, 2, 3, 5], 0, SignMode::Absolute, DEFAULT_MAX_RESULTS)
             .await
             .unwrap();
 
-        assert_eq!(result.len(), 1);
-        assert_eq!(result[0].number, 45);
-        assert_eq!(result[0].name, "Fibonacci numbers");
+        assert_eq!(result.sequences.len(), 1);
+        assert_eq!(result.sequences[0].number, 45);
+        assert_eq!(result.sequences[0].name, "Fibonacci numbers");
+        assert_eq!(result.sequences[0].offset, None);
+        assert_eq!(result.sequences[0].author, None);
+        assert_eq!(result.sequences[0].revision, None);
+        assert_eq!(result.sequences[0].reference, None);
+        assert_eq!(result.sequences[0].example, None);
+        assert_eq!(result.sequences[0].program, None);
     }
 
     #[tokio::test]
@@ -225,36 +1709,43 @@ mod tests {
             "1,2,3",
             200,
             r#"
-                [
-                    {
-                        "number": 27,
-                        "data": "1, 2, 3, 4, 5",
-                        "name": "Natural numbers",
-                        "comment": ["The natural numbers"],
-                        "formula": ["a(n) = n"],
-                        "xref": [],
-                        "keyword": "nonn"
-                    },
-                    {
-                        "number": 290,
-                        "data": "1, 2, 3, 5, 7",
-                        "name": "Primes and composites",
-                        "comment": ["Mixed sequence"],
-                        "formula": [],
-                        "xref": [],
-                        "keyword": "nonn"
-                    }
-                ]
+                {
+                    "count": 2,
+                    "start": 0,
+                    "results": [
+                        {
+                            "number": 27,
+                            "data": "1, 2, 3, 4, 5",
+                            "name": "Natural numbers",
+                            "comment": ["The natural numbers"],
+                            "formula": ["a(n) = n"],
+                            "xref": [],
+                            "keyword": "nonn"
+                        },
+                        {
+                            "number": 290,
+                            "data": "1, 2, 3, 5, 7",
+                            "name": "Primes and composites",
+                            "comment": ["Mixed sequence"],
+                            "formula": [],
+                            "xref": [],
+                            "keyword": "nonn"
+                        }
+                    ]
+                }
                 "#,
         );
 
-        let result = client.search_by_subsequence(&[1, 2, 3]).await.unwrap();
+        let result = client
+            .search_by_subsequence(&[1, 2, 3], 0, SignMode::Absolute, DEFAULT_MAX_RESULTS)
+            .await
+            .unwrap();
 
-        assert_eq!(result.len(), 2);
-        assert_eq!(result[0].number, 27);
-        assert_eq!(result[0].name, "Natural numbers");
-        assert_eq!(result[1].number, 290);
-        assert_eq!(result[1].name, "Primes and composites");
+        assert_eq!(result.sequences.len(), 2);
+        assert_eq!(result.sequences[0].number, 27);
+        assert_eq!(result.sequences[0].name, "Natural numbers");
+        assert_eq!(result.sequences[1].number, 290);
+        assert_eq!(result.sequences[1].name, "Primes and composites");
     }
 
     #[tokio::test]
@@ -262,14 +1753,19 @@ mod tests {
         let server = MockServer::start();
         let client = setup_test_client(&server);
 
-        let _mock = mock_oeis_subsequence_search(&server, "999,888,777", 200, "null");
+        let _mock = mock_oeis_subsequence_search(
+            &server,
+            "999,888,777",
+            200,
+            r#"{"count": 0, "start": 0, "results": null}"#,
+        );
 
         let result = client
-            .search_by_subsequence(&[999, 888, 777])
+            .search_by_subsequence(&[999, 888, 777], 0, SignMode::Absolute, DEFAULT_MAX_RESULTS)
             .await
             .unwrap();
 
-        assert_eq!(result.len(), 0);
+        assert_eq!(result.sequences.len(), 0);
     }
 
     #[tokio::test]
@@ -280,65 +1776,753 @@ mod tests {
         let _mock = mock_oeis_subsequence_search(&server, "123,456,789", 200, "[]");
 
         let result = client
-            .search_by_subsequence(&[123, 456, 789])
+            .search_by_subsequence(&[123, 456, 789], 0, SignMode::Absolute, DEFAULT_MAX_RESULTS)
             .await
             .unwrap();
 
-        assert_eq!(result.len(), 0);
+        assert_eq!(result.sequences.len(), 0);
     }
 
     #[tokio::test]
-    async fn test_search_by_subsequence_error() {
+    async fn test_search_by_subsequence_count() {
         let server = MockServer::start();
         let client = setup_test_client(&server);
 
-        let _mock = mock_oeis_subsequence_search(&server, "1,2,3", 500, "");
+        let _mock = mock_oeis_subsequence_search(
+            &server,
+            "1,2,3",
+            200,
+            r#"
+                {
+                    "count": 1234,
+                    "results": [
+                        {
+                            "number": 27,
+                            "data": "1, 2, 3, 4, 5",
+                            "name": "Natural numbers",
+                            "keyword": "nonn"
+                        }
+                    ]
+                }
+                "#,
+        );
 
-        let result = client.search_by_subsequence(&[1, 2, 3]).await;
+        let result = client
+            .search_by_subsequence(&[1, 2, 3], 0, SignMode::Absolute, 1)
+            .await
+            .unwrap();
 
-        assert!(result.is_err());
+        assert_eq!(result.count, 1234);
+        assert_eq!(result.sequences.len(), 1);
+        assert_eq!(result.sequences[0].number, 27);
     }
 
     #[tokio::test]
-    async fn test_search_by_subsequence_negative_numbers() {
+    async fn test_search_by_subsequence_count_bare_array() {
         let server = MockServer::start();
         let client = setup_test_client(&server);
 
         let _mock = mock_oeis_subsequence_search(
             &server,
-            "-1,0,1",
+            "1,2,3",
             200,
-            r#"
-                [
-                    {
-                        "number": 12345,
-                        "data": "-1, 0, 1, 0, -1",
-                        "name": "Sequence with negative numbers",
-                        "comment": [],
-                        "formula": [],
-                        "xref": [],
-                        "keyword": "sign"
-                    }
-                ]
-                "#,
+            r#"{"count": 1, "start": 0, "results": [{"number": 27, "data": "1, 2, 3", "name": "Natural numbers", "keyword": "nonn"}]}"#,
         );
 
-        let result = client.search_by_subsequence(&[-1, 0, 1]).await.unwrap();
+        let result = client
+            .search_by_subsequence(&[1, 2, 3], 0, SignMode::Absolute, DEFAULT_MAX_RESULTS)
+            .await
+            .unwrap();
 
-        assert_eq!(result.len(), 1);
-        assert_eq!(result[0].number, 12345);
-        assert_eq!(result[0].name, "Sequence with negative numbers");
+        assert_eq!(result.count, 1);
     }
 
     #[tokio::test]
-    async fn test_search_by_subsequence_empty_input() {
+    async fn test_search_by_subsequence_paging() {
+        let server = MockServer::start();
+        let client = setup_test_client(&server);
+
+        let first_window = server.mock(|when, then| {
+            when.method(GET)
+                .path("/search")
+                .query_param("q", "seq:1,2,3")
+                .query_param("start", "0");
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .body(r#"{"count": 3, "results": [{"number": 27, "data": "1, 2, 3", "name": "Natural numbers", "keyword": "nonn"}, {"number": 28, "data": "1, 2, 3", "name": "Second", "keyword": "nonn"}]}"#);
+        });
+        let second_window = server.mock(|when, then| {
+            when.method(GET)
+                .path("/search")
+                .query_param("q", "seq:1,2,3")
+                .query_param("start", "2");
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .body(r#"{"count": 3, "results": [{"number": 29, "data": "1, 2, 3", "name": "Third", "keyword": "nonn"}]}"#);
+        });
+
+        let first = client
+            .search_by_subsequence(&[1, 2, 3], 0, SignMode::Absolute, 2)
+            .await
+            .unwrap();
+        assert_eq!(first.sequences.len(), 2);
+        assert_eq!(first.start, 0);
+        assert!(first.has_more());
+
+        let second = client
+            .search_by_subsequence(
+                &[1, 2, 3],
+                first.sequences.len(),
+                SignMode::Absolute,
+                DEFAULT_MAX_RESULTS,
+            )
+            .await
+            .unwrap();
+        assert_eq!(second.sequences.len(), 1);
+        assert_eq!(second.sequences[0].number, 29);
+        assert_eq!(second.start, 2);
+        assert!(!second.has_more());
+
+        first_window.assert();
+        second_window.assert();
+    }
+
+    // Serves a full page of ten results for any `start`, out of 1000 matches
+    fn mock_full_pages(server: &MockServer) -> Mock<'_> {
+        let results = (0..10)
+            .map(|i| {
+                format!(
+                    r#"{{"number": {}, "data": "1, 2, 3", "name": "Sequence {}", "keyword": "nonn"}}"#,
+                    i, i
+                )
+            })
+            .collect::<Vec<String>>()
+            .join(",");
+        server.mock(|when, then| {
+            when.method(GET)
+                .path("/search")
+                .query_param("q", "seq:1,2,3");
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .body(format!(r#"{{"count": 1000, "results": [{}]}}"#, results));
+        })
+    }
+
+    #[tokio::test]
+    async fn test_search_by_subsequence_max_results_spans_pages() {
+        let server = MockServer::start();
+        let client = setup_test_client(&server);
+        let mock = mock_full_pages(&server);
+
+        let result = client
+            .search_by_subsequence(&[1, 2, 3], 0, SignMode::Absolute, 15)
+            .await
+            .unwrap();
+
+        mock.assert_calls(2);
+        assert_eq!(result.sequences.len(), 15);
+        assert_eq!(result.start, 0);
+        assert!(result.has_more());
+    }
+
+    #[tokio::test]
+    async fn test_search_by_subsequence_max_results_clamped() {
         let server = MockServer::start();
         let client = setup_test_client(&server);
+        let mock = mock_full_pages(&server);
 
-        let _mock = mock_oeis_subsequence_search(&server, "", 200, "null");
+        let result = client
+            .search_by_subsequence(&[1, 2, 3], 0, SignMode::Absolute, 500)
+            .await
+            .unwrap();
+
+        mock.assert_calls(MAX_SEARCH_RESULTS / 10);
+        assert_eq!(result.sequences.len(), MAX_SEARCH_RESULTS);
+        assert_eq!(result.count, 1000);
+    }
+
+    #[tokio::test]
+    async fn test_search_captures_count_and_start() {
+        let server = MockServer::start();
+        let client = setup_test_client(&server);
+
+        let mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/search")
+                .query_param("q", "seq:1,2,3")
+                .query_param("start", "20");
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .body(r#"{"greeting": "Greetings from The On-Line Encyclopedia of Integer Sequences! http://oeis.org/", "query": "seq:1,2,3", "count": 5123, "start": 20, "results": [{"number": 27, "data": "1, 2, 3", "name": "The positive integers.", "keyword": "core,nonn"}]}"#);
+        });
+
+        let result = client
+            .search_by_subsequence(&[1, 2, 3], 20, SignMode::Absolute, 1)
+            .await
+            .unwrap();
+
+        mock.assert();
+        assert_eq!(result.count, 5123);
+        assert_eq!(result.start, 20);
+        assert_eq!(result.sequences[0].number, 27);
+    }
+
+    #[tokio::test]
+    async fn test_search_by_subsequence_too_many_results() {
+        let server = MockServer::start();
+        let client = setup_test_client(&server);
+
+        let _mock = mock_oeis_subsequence_search(
+            &server,
+            "1,2",
+            200,
+            r#"{"greeting": "Greetings from The On-Line Encyclopedia of Integer Sequences! http://oeis.org/", "query": "seq:1,2", "count": 183412, "start": 0, "results": null}"#,
+        );
+
+        let result = client
+            .search_by_subsequence(&[1, 2], 0, SignMode::Absolute, DEFAULT_MAX_RESULTS)
+            .await;
+
+        assert_eq!(result.unwrap_err(), OEISError::TooManyResults(183412));
+    }
+
+    #[tokio::test]
+    async fn test_count_subsequence_matches() {
+        let server = MockServer::start();
+        let client = setup_test_client(&server);
+
+        // Malformed sequence bodies would fail a full search; counting reads only `number`
+        let mock = mock_oeis_subsequence_search(
+            &server,
+            "1,2,3",
+            200,
+            r#"{"count": 1234, "start": 0, "results": [{"number": 45, "data": 1}, {"unexpected": true}]}"#,
+        );
+
+        let matches = client.count_subsequence_matches(&[1, 2, 3]).await.unwrap();
+
+        assert_eq!(
+            matches,
+            MatchCount {
+                count: 1234,
+                first_id: Some("A000045".to_string()),
+            }
+        );
+        mock.assert_calls(1);
+    }
+
+    #[tokio::test]
+    async fn test_sequence_exists() {
+        let server = MockServer::start();
+        let client = setup_test_client(&server);
+
+        // The sequence body is never deserialized, only counted
+        let found = mock_oeis_search(
+            &server,
+            "A000045",
+            200,
+            r#"{"count": 1, "start": 0, "results": [{"unexpected": true}]}"#,
+        );
+        let _missing = mock_oeis_search(
+            &server,
+            "A999999",
+            200,
+            r#"{"count": 0, "start": 0, "results": null}"#,
+        );
+
+        assert!(client.sequence_exists("A000045").await.unwrap());
+        assert!(!client.sequence_exists("A999999").await.unwrap());
+        found.assert_calls(1);
+    }
+
+    #[tokio::test]
+    async fn test_sequence_exists_error() {
+        let server = MockServer::start();
+        let client = setup_test_client(&server);
+
+        let _mock = mock_oeis_search(&server, "A000045", 500, "");
+
+        let result = client.sequence_exists("A000045").await;
+
+        assert_eq!(
+            result.unwrap_err(),
+            OEISError::Http(reqwest::StatusCode::INTERNAL_SERVER_ERROR)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_count_subsequence_matches_too_broad() {
+        let server = MockServer::start();
+        let client = setup_test_client(&server);
+
+        let _mock = mock_oeis_subsequence_search(
+            &server,
+            "1,2",
+            200,
+            r#"{"count": 183412, "start": 0, "results": null}"#,
+        );
+
+        let matches = client.count_subsequence_matches(&[1, 2]).await.unwrap();
+
+        assert_eq!(matches.count, 183412);
+        assert_eq!(matches.first_id, None);
+    }
+
+    #[tokio::test]
+    async fn test_count_subsequence_matches_bare_array() {
+        let server = MockServer::start();
+        let client = setup_test_client(&server);
+
+        let _mock = mock_oeis_subsequence_search(&server, "1,2,3", 200, r#"[{}, {}]"#);
+
+        let matches = client.count_subsequence_matches(&[1, 2, 3]).await.unwrap();
+
+        assert_eq!(matches.count, 2);
+        assert_eq!(matches.first_id, None);
+    }
+
+    #[tokio::test]
+    async fn test_search_past_last_match_is_empty() {
+        let server = MockServer::start();
+        let client = setup_test_client(&server);
+
+        let _mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/search")
+                .query_param("q", "seq:1,2,3")
+                .query_param("start", "10");
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .body(r#"{"count": 3, "start": 10, "results": null}"#);
+        });
+
+        let result = client
+            .search_by_subsequence(&[1, 2, 3], 10, SignMode::Absolute, DEFAULT_MAX_RESULTS)
+            .await
+            .unwrap();
+
+        assert_eq!(result.count, 3);
+        assert!(result.sequences.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_search_bare_array_body() {
+        let server = MockServer::start();
+        let client = setup_test_client(&server);
+
+        let _mock = mock_oeis_subsequence_search(
+            &server,
+            "1,2,3",
+            200,
+            r#"[{"number": 27, "data": "1, 2, 3", "name": "The positive integers.", "keyword": "core,nonn"}]"#,
+        );
+
+        let result = client
+            .search_by_subsequence(&[1, 2, 3], 0, SignMode::Absolute, DEFAULT_MAX_RESULTS)
+            .await
+            .unwrap();
+
+        assert_eq!(result.count, 1);
+        assert_eq!(result.start, 0);
+        assert_eq!(result.sequences[0].number, 27);
+    }
+
+    #[tokio::test]
+    async fn test_search_by_keyword() {
+        let server = MockServer::start();
+        let client = setup_test_client(&server);
+
+        let mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/search")
+                .query_param("fmt", "json")
+                .query_param("q", "keyword:core");
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .body(r#"{"count": 180, "results": [{"number": 45, "data": "0, 1, 1", "name": "Fibonacci numbers", "keyword": "core,nonn,nice"}]}"#);
+        });
+
+        let result = client.search_by_keyword("core", 0, 1).await.unwrap();
+
+        mock.assert();
+        assert_eq!(result.count, 180);
+        assert_eq!(result.sequences.len(), 1);
+        assert_eq!(result.sequences[0].number, 45);
+    }
+
+    #[tokio::test]
+    async fn test_search_by_keyword_paging() {
+        let server = MockServer::start();
+        let client = setup_test_client(&server);
+
+        let first_page = server.mock(|when, then| {
+            when.method(GET)
+                .path("/search")
+                .query_param("q", "keyword:nice")
+                .query_param("start", "0");
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .body(r#"{"count": 40, "results": [{"number": 45, "data": "0, 1, 1", "name": "Fibonacci numbers", "keyword": "nice"}, {"number": 108, "data": "1, 1, 2", "name": "Catalan numbers", "keyword": "nice"}]}"#);
+        });
+        let second_page = server.mock(|when, then| {
+            when.method(GET)
+                .path("/search")
+                .query_param("q", "keyword:nice")
+                .query_param("start", "2");
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .body(r#"{"count": 40, "results": [{"number": 142, "data": "1, 1, 2", "name": "Factorial numbers", "keyword": "nice"}, {"number": 290, "data": "0, 1, 4", "name": "The squares", "keyword": "nice"}]}"#);
+        });
+
+        let result = client.search_by_keyword("nice", 0, 3).await.unwrap();
+
+        first_page.assert_calls(1);
+        second_page.assert_calls(1);
+        assert_eq!(result.count, 40);
+        assert_eq!(
+            result
+                .sequences
+                .iter()
+                .map(|sequence| sequence.number)
+                .collect::<Vec<_>>(),
+            vec![45, 108, 142]
+        );
+        assert!(result.has_more());
+    }
+
+    #[tokio::test]
+    async fn test_search_text() {
+        let server = MockServer::start();
+        let client = setup_test_client(&server);
+
+        let mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/search")
+                .query_param("fmt", "json")
+                .query_param("q", "name:partitions of n & \"distinct parts\"");
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .body(r#"{"count": 2, "results": [{"number": 41, "data": "1, 1, 2, 3, 5", "name": "a(n) = number of partitions of n", "keyword": "core,nonn"}, {"number": 9, "data": "1, 1, 1, 2, 2", "name": "Number of partitions of n into distinct parts", "keyword": "core,nonn"}]}"#);
+        });
+
+        let result = client
+            .search_text("name:partitions of n & \"distinct parts\"")
+            .await
+            .unwrap();
+
+        mock.assert();
+        assert_eq!(result.count, 2);
+        assert_eq!(result.sequences[0].number, 41);
+        assert_eq!(
+            result.sequences[1].name,
+            "Number of partitions of n into distinct parts"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_text_generating_function_is_url_encoded() {
+        let server = MockServer::start();
+        let client = setup_test_client(&server);
+
+        // An unencoded '+' would reach OEIS as a space, so this only matches if the query is encoded
+        let mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/search")
+                .query_param("fmt", "json")
+                .query_param("q", "formula:\"1/(1-x+x^2) & x\"");
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .body(r#"{"count": 1, "results": [{"number": 10892, "data": "1, 1, 0, -1, -1, 0", "name": "Inverse of 6th cyclotomic polynomial", "keyword": "sign,easy"}]}"#);
+        });
+
+        let result = client
+            .search_text("formula:\"1/(1-x+x^2) & x\"")
+            .await
+            .unwrap();
+
+        mock.assert();
+        assert_eq!(result.sequences[0].number, 10892);
+    }
+
+    #[tokio::test]
+    async fn test_find_by_name() {
+        let server = MockServer::start();
+        let client = setup_test_client(&server);
+
+        let mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/search")
+                .query_param("fmt", "json")
+                .query_param("q", "name:\"fibonacci numbers\"");
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .body(r#"{"count": 2, "results": [{"number": 1519, "data": "1, 1, 2, 5, 13", "name": "Bisection of Fibonacci numbers", "keyword": "nonn"}, {"number": 45, "data": "0, 1, 1, 2, 3", "name": "Fibonacci numbers", "keyword": "core,nonn"}]}"#);
+        });
+
+        let result = client.find_by_name("fibonacci numbers").await.unwrap();
+
+        mock.assert();
+        assert_eq!(result.unwrap().number, 45);
+    }
+
+    #[tokio::test]
+    async fn test_find_by_name_no_exact_match() {
+        let server = MockServer::start();
+        let client = setup_test_client(&server);
+
+        let mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/search")
+                .query_param("fmt", "json")
+                .query_param("q", "name:\"Fibonacci\"");
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .body(r#"{"count": 1, "results": [{"number": 45, "data": "0, 1, 1, 2, 3", "name": "Fibonacci numbers", "keyword": "core,nonn"}]}"#);
+        });
+
+        let result = client.find_by_name("Fibonacci").await.unwrap();
+
+        mock.assert();
+        assert!(result.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_find_by_name_strips_inner_quotes() {
+        let server = MockServer::start();
+        let client = setup_test_client(&server);
+
+        let mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/search")
+                .query_param("fmt", "json")
+                .query_param("q", "name:\" a \"");
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .body(r#"{"count": 0, "results": null}"#);
+        });
+
+        let result = client.find_by_name("\"a\"").await.unwrap();
+
+        mock.assert();
+        assert!(result.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_search_by_subsequence_error() {
+        let server = MockServer::start();
+        let client = setup_test_client(&server);
+
+        let _mock = mock_oeis_subsequence_search(&server, "1,2,3", 500, "");
+
+        let result = client
+            .search_by_subsequence(&[1, 2, 3], 0, SignMode::Absolute, DEFAULT_MAX_RESULTS)
+            .await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_search_by_subsequence_negative_numbers() {
+        let server = MockServer::start();
+        let client = setup_test_client(&server);
+
+        let _mock = mock_oeis_subsequence_search(
+            &server,
+            "-1,0,1",
+            200,
+            r#"
+                {
+                    "count": 1,
+                    "start": 0,
+                    "results": [
+                        {
+                            "number": 12345,
+                            "data": "-1, 0, 1, 0, -1",
+                            "name": "Sequence with negative numbers",
+                            "comment": [],
+                            "formula": [],
+                            "xref": [],
+                            "keyword": "sign"
+                        }
+                    ]
+                }
+                "#,
+        );
+
+        let result = client
+            .search_by_subsequence(&[-1, 0, 1], 0, SignMode::Absolute, DEFAULT_MAX_RESULTS)
+            .await
+            .unwrap();
+
+        assert_eq!(result.sequences.len(), 1);
+        assert_eq!(result.sequences[0].number, 12345);
+        assert_eq!(result.sequences[0].name, "Sequence with negative numbers");
+    }
+
+    #[tokio::test]
+    async fn test_search_by_subsequence_exact_sign_mode() {
+        let server = MockServer::start();
+        let client = setup_test_client(&server);
+
+        let mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/search")
+                .query_param("fmt", "json")
+                .query_param("q", "signed:-1,0,1");
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .body(r#"{"count": 1, "start": 0, "results": [{"number": 12345, "data": "-1, 0, 1, 0, -1", "name": "Sequence with negative numbers", "keyword": "sign"}]}"#);
+        });
+
+        let result = client
+            .search_by_subsequence(&[-1, 0, 1], 0, SignMode::Exact, DEFAULT_MAX_RESULTS)
+            .await
+            .unwrap();
+
+        mock.assert();
+        assert_eq!(result.sequences[0].number, 12345);
+    }
+
+    #[tokio::test]
+    async fn test_search_by_pattern_wildcard() {
+        let server = MockServer::start();
+        let client = setup_test_client(&server);
+
+        let mock = mock_oeis_subsequence_search(
+            &server,
+            "1,1,_,3,5",
+            200,
+            r#"{"count": 1, "start": 0, "results": [{"number": 45, "data": "0, 1, 1, 2, 3, 5, 8", "name": "Fibonacci numbers", "keyword": "nonn"}]}"#,
+        );
+
+        let result = client
+            .search_by_pattern(
+                &[Some(1), Some(1), None, Some(3), Some(5)],
+                0,
+                SignMode::Absolute,
+                DEFAULT_MAX_RESULTS,
+            )
+            .await
+            .unwrap();
+
+        mock.assert();
+        assert_eq!(result.sequences[0].number, 45);
+    }
+
+    #[tokio::test]
+    async fn test_search_by_subsequence_empty_input() {
+        let server = MockServer::start();
+        let client = setup_test_client(&server);
+
+        let _mock = mock_oeis_subsequence_search(
+            &server,
+            "",
+            200,
+            r#"{"count": 0, "start": 0, "results": null}"#,
+        );
+
+        let result = client
+            .search_by_subsequence(&[], 0, SignMode::Absolute, DEFAULT_MAX_RESULTS)
+            .await
+            .unwrap();
+
+        assert_eq!(result.sequences.len(), 0);
+    }
+
+    #[test]
+    fn test_parse_bfile() {
+        let text = "# Fibonacci numbers\n0 0\n1 1\n\n2 1\nmalformed\n100 354224848179261915075\n";
+
+        let entries = parse_bfile(text);
+
+        assert_eq!(
+            entries,
+            vec![
+                BFileEntry {
+                    index: 0,
+                    value: "0".to_string()
+                },
+                BFileEntry {
+                    index: 1,
+                    value: "1".to_string()
+                },
+                BFileEntry {
+                    index: 2,
+                    value: "1".to_string()
+                },
+                BFileEntry {
+                    index: 100,
+                    value: "354224848179261915075".to_string()
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_bfile() {
+        let server = MockServer::start();
+        let client = setup_test_client(&server);
+
+        let _mock = server.mock(|when, then| {
+            when.method(GET).path("/A000045/b000045.txt");
+            then.status(200).body("0 0\n1 1\n2 1\n3 2\n");
+        });
+
+        let result = client.fetch_bfile("A000045").await.unwrap();
+
+        let entries = result.unwrap();
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries[3].index, 3);
+        assert_eq!(entries[3].value, "2");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_bfile_not_found() {
+        let server = MockServer::start();
+        let client = setup_test_client(&server);
+
+        let _mock = server.mock(|when, then| {
+            when.method(GET).path("/A999999/b999999.txt");
+            then.status(404);
+        });
+
+        let result = client.fetch_bfile("A999999").await.unwrap();
+
+        assert!(result.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_bfile_error() {
+        let server = MockServer::start();
+        let client = setup_test_client(&server);
+
+        let _mock = server.mock(|when, then| {
+            when.method(GET).path("/A000045/b000045.txt");
+            then.status(500);
+        });
+
+        let result = client.fetch_bfile("A000045").await;
+
+        assert!(result.is_err());
+    }
+    #[tokio::test]
+    async fn test_latest_sequence() {
+        let server = MockServer::start();
+        let client = setup_test_client(&server);
+
+        let mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/search")
+                .query_param("q", "keyword:new")
+                .query_param("sort", "created");
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .body(r#"{"count": 2, "start": 0, "results": [{"number": 389998, "data": "1, 2", "name": "Older", "keyword": "nonn,new"}, {"number": 390001, "data": "1, 3", "name": "Newest", "keyword": "nonn,new"}]}"#);
+        });
 
-        let result = client.search_by_subsequence(&[]).await.unwrap();
+        let latest = client.latest_sequence().await.unwrap().unwrap();
 
-        assert_eq!(result.len(), 0);
+        assert_eq!(latest.id(), "A390001");
+        mock.assert_calls(1);
     }
 }
@@ -0,0 +1,255 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+use tracing::debug;
+
+use crate::oeis_client::{
+    BFileEntry, MatchCount, OEISClient, OEISResult, OEISSequence, SearchResult, SignMode,
+};
+
+/// `OEISClient` decorator that spaces upstream requests to at most `max_rps` per second.
+/// Requests over the limit wait for their slot instead of failing.
+#[derive(Clone)]
+pub struct RateLimitedClient<C: OEISClient> {
+    inner: C,
+    interval: Duration,
+    next_slot: Arc<Mutex<Instant>>,
+}
+
+impl<C: OEISClient> RateLimitedClient<C> {
+    pub fn new(inner: C, max_rps: u32) -> Self {
+        Self {
+            inner,
+            interval: Duration::from_secs(1) / max_rps.max(1),
+            next_slot: Arc::new(Mutex::new(Instant::now())),
+        }
+    }
+
+    /// Reserve the next free slot and sleep until it arrives
+    async fn acquire(&self) {
+        let slot = {
+            let mut next_slot = self.next_slot.lock().await;
+            let slot = (*next_slot).max(Instant::now());
+            *next_slot = slot + self.interval;
+            slot
+        };
+
+        if slot > Instant::now() {
+            debug!("Rate limited: waiting {:?}", slot - Instant::now());
+            tokio::time::sleep_until(slot).await;
+        }
+    }
+}
+
+#[async_trait]
+impl<C: OEISClient> OEISClient for RateLimitedClient<C> {
+    async fn find_by_id(&self, id: &str) -> OEISResult<Option<OEISSequence>> {
+        self.acquire().await;
+        self.inner.find_by_id(id).await
+    }
+
+    async fn find_raw_by_id(&self, id: &str) -> OEISResult<String> {
+        self.acquire().await;
+        self.inner.find_raw_by_id(id).await
+    }
+
+    async fn sequence_exists(&self, id: &str) -> OEISResult<bool> {
+        self.acquire().await;
+        self.inner.sequence_exists(id).await
+    }
+
+    async fn find_by_name(&self, name: &str) -> OEISResult<Option<OEISSequence>> {
+        self.acquire().await;
+        self.inner.find_by_name(name).await
+    }
+
+    async fn search_by_subsequence(
+        &self,
+        subsequence: &[i64],
+        start: usize,
+        mode: SignMode,
+        max_results: usize,
+    ) -> OEISResult<SearchResult> {
+        self.acquire().await;
+        self.inner
+            .search_by_subsequence(subsequence, start, mode, max_results)
+            .await
+    }
+
+    async fn count_subsequence_matches(&self, subsequence: &[i64]) -> OEISResult<MatchCount> {
+        self.acquire().await;
+        self.inner.count_subsequence_matches(subsequence).await
+    }
+
+    async fn search_by_pattern(
+        &self,
+        pattern: &[Option<i64>],
+        start: usize,
+        mode: SignMode,
+        max_results: usize,
+    ) -> OEISResult<SearchResult> {
+        self.acquire().await;
+        self.inner
+            .search_by_pattern(pattern, start, mode, max_results)
+            .await
+    }
+
+    async fn search_by_keyword(
+        &self,
+        keyword: &str,
+        start: usize,
+        max_results: usize,
+    ) -> OEISResult<SearchResult> {
+        self.acquire().await;
+        self.inner
+            .search_by_keyword(keyword, start, max_results)
+            .await
+    }
+
+    async fn search_text(&self, query: &str) -> OEISResult<SearchResult> {
+        self.acquire().await;
+        self.inner.search_text(query).await
+    }
+
+    async fn fetch_bfile(&self, id: &str) -> OEISResult<Option<Vec<BFileEntry>>> {
+        self.acquire().await;
+        self.inner.fetch_bfile(id).await
+    }
+
+    async fn latest_sequence(&self) -> OEISResult<Option<OEISSequence>> {
+        self.acquire().await;
+        self.inner.latest_sequence().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // Mock client counting upstream calls
+    #[derive(Clone, Default)]
+    struct CountingClient {
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl OEISClient for CountingClient {
+        async fn find_by_id(&self, _id: &str) -> OEISResult<Option<OEISSequence>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(None)
+        }
+
+        async fn find_raw_by_id(&self, _id: &str) -> OEISResult<String> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(String::new())
+        }
+
+        async fn sequence_exists(&self, _id: &str) -> OEISResult<bool> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(false)
+        }
+
+        async fn find_by_name(&self, _name: &str) -> OEISResult<Option<OEISSequence>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(None)
+        }
+
+        async fn search_by_subsequence(
+            &self,
+            _subsequence: &[i64],
+            _start: usize,
+            _mode: SignMode,
+            _max_results: usize,
+        ) -> OEISResult<SearchResult> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(SearchResult::default())
+        }
+
+        async fn count_subsequence_matches(&self, _subsequence: &[i64]) -> OEISResult<MatchCount> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(MatchCount::default())
+        }
+
+        async fn search_by_pattern(
+            &self,
+            _pattern: &[Option<i64>],
+            _start: usize,
+            _mode: SignMode,
+            _max_results: usize,
+        ) -> OEISResult<SearchResult> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(SearchResult::default())
+        }
+
+        async fn search_by_keyword(
+            &self,
+            _keyword: &str,
+            _start: usize,
+            _max_results: usize,
+        ) -> OEISResult<SearchResult> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(SearchResult::default())
+        }
+
+        async fn search_text(&self, _query: &str) -> OEISResult<SearchResult> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(SearchResult::default())
+        }
+
+        async fn fetch_bfile(&self, _id: &str) -> OEISResult<Option<Vec<BFileEntry>>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(None)
+        }
+
+        async fn latest_sequence(&self) -> OEISResult<Option<OEISSequence>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(None)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_calls_over_limit_wait_for_their_slot() {
+        let inner = CountingClient::default();
+        let client = RateLimitedClient::new(inner.clone(), 20);
+
+        let started = Instant::now();
+        for _ in 0..4 {
+            client.find_by_id("A000045").await.unwrap();
+        }
+
+        // The first call goes through immediately, the other three are 50ms apart
+        assert!(started.elapsed() >= Duration::from_millis(150));
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_calls_share_the_limit() {
+        let inner = CountingClient::default();
+        let client = RateLimitedClient::new(inner.clone(), 20);
+
+        let started = Instant::now();
+        let (a, b, c) = tokio::join!(
+            client.find_by_id("A000045"),
+            client.search_text("fibonacci"),
+            client.fetch_bfile("A000045"),
+        );
+
+        assert!(a.is_ok() && b.is_ok() && c.is_ok());
+        assert!(started.elapsed() >= Duration::from_millis(100));
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_calls_under_limit_are_not_delayed() {
+        let client = RateLimitedClient::new(CountingClient::default(), 1);
+
+        let started = Instant::now();
+        client.find_by_id("A000045").await.unwrap();
+
+        assert!(started.elapsed() < Duration::from_millis(500));
+    }
+}
@@ -1,17 +1,138 @@
+use tracing::warn;
 use tracing_subscriber::{
+    Layer, Registry,
     layer::SubscriberExt,
     util::SubscriberInitExt,
     {self},
 };
 
+use std::sync::atomic::{AtomicU64, Ordering};
+
 const DEFAULT_LEVEL: &str = "debug";
 
-pub fn setup_tracing() {
+/// Setting this enables OTLP span export (e.g., "http://localhost:4318")
+const OTLP_ENDPOINT_VAR: &str = "OTEL_EXPORTER_OTLP_ENDPOINT";
+
+static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(1);
+
+type BoxedLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
+/// Keeps the OTLP exporter running; dropping it flushes the spans still buffered
+#[must_use]
+pub struct TracingGuard {
+    provider: Option<otel::Provider>,
+}
+
+impl Drop for TracingGuard {
+    fn drop(&mut self) {
+        if let Some(provider) = self.provider.take() {
+            otel::shutdown(provider);
+        }
+    }
+}
+
+/// Log to the console, and also export spans over OTLP when `OTEL_EXPORTER_OTLP_ENDPOINT`
+/// is set. Hold the returned guard until exit.
+pub fn setup_tracing() -> TracingGuard {
+    // Log to stderr so that stdout stays free for the stdio transport
+    let fmt_layer = tracing_subscriber::fmt::layer().with_writer(std::io::stderr);
+    let fmt_layer = if is_json_log_format() {
+        fmt_layer.json().boxed()
+    } else {
+        fmt_layer.boxed()
+    };
+
+    let (otel_layer, provider, otel_error) =
+        match std::env::var_os(OTLP_ENDPOINT_VAR).map(|_| otel::layer()) {
+            Some(Ok((layer, provider))) => (Some(layer), Some(provider), None),
+            Some(Err(e)) => (None, None, Some(e)),
+            None => (None, None, None),
+        };
+
+    // The filter applies to every layer, so only spans at the configured level are exported
     tracing_subscriber::registry()
+        .with(otel_layer)
         .with(
             tracing_subscriber::EnvFilter::try_from_default_env()
                 .unwrap_or_else(|_| DEFAULT_LEVEL.to_string().into()),
         )
-        .with(tracing_subscriber::fmt::layer())
+        .with(fmt_layer)
         .init();
+
+    // Reported only now, once there is a subscriber to log it
+    if let Some(e) = otel_error {
+        warn!(
+            "{} is set, but spans are not exported: {}",
+            OTLP_ENDPOINT_VAR, e
+        );
+    }
+    TracingGuard { provider }
+}
+
+/// Generate a process-unique ID used to correlate the log lines of one request (e.g., "req-42")
+pub fn next_request_id() -> String {
+    format!("req-{}", NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed))
+}
+
+/// `LOG_FORMAT=json` switches to JSON logs; anything else keeps the human-readable format
+fn is_json_log_format() -> bool {
+    std::env::var("LOG_FORMAT").is_ok_and(|format| format.eq_ignore_ascii_case("json"))
+}
+
+#[cfg(feature = "otel")]
+mod otel {
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry_sdk::Resource;
+    use opentelemetry_sdk::trace::SdkTracerProvider;
+    use tracing_subscriber::Layer;
+
+    use super::BoxedLayer;
+
+    pub type Provider = SdkTracerProvider;
+
+    /// Export spans over OTLP/HTTP in batches. The exporter reads the endpoint and the other
+    /// `OTEL_EXPORTER_OTLP_*` settings (headers, timeout) from the environment itself.
+    pub fn layer() -> Result<(BoxedLayer, Provider), String> {
+        let exporter = opentelemetry_otlp::SpanExporter::builder()
+            .with_http()
+            .build()
+            .map_err(|e| e.to_string())?;
+        let provider = SdkTracerProvider::builder()
+            .with_batch_exporter(exporter)
+            .with_resource(
+                Resource::builder()
+                    .with_service_name(env!("CARGO_PKG_NAME"))
+                    .build(),
+            )
+            .build();
+        let tracer = provider.tracer(env!("CARGO_PKG_NAME"));
+
+        Ok((
+            tracing_opentelemetry::layer().with_tracer(tracer).boxed(),
+            provider,
+        ))
+    }
+
+    pub fn shutdown(provider: Provider) {
+        // The subscriber may already be gone at exit, so report to stderr directly
+        if let Err(e) = provider.shutdown() {
+            eprintln!("Failed to flush OpenTelemetry spans: {}", e);
+        }
+    }
+}
+
+/// Without the `otel` feature, the OpenTelemetry crates are not even compiled in
+#[cfg(not(feature = "otel"))]
+mod otel {
+    use super::BoxedLayer;
+
+    pub enum Provider {}
+
+    pub fn layer() -> Result<(BoxedLayer, Provider), String> {
+        Err("this build lacks the `otel` feature".to_string())
+    }
+
+    pub fn shutdown(provider: Provider) {
+        match provider {}
+    }
 }